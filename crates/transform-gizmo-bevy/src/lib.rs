@@ -18,7 +18,7 @@
 //!     .run();
 //! ```
 //!
-//! Add [`GizmoCamera`] component to your Camera entity.
+//! Add [`GizmoCamera`] component to your Camera entity, or enable [`GizmoOptions::auto_gizmo_camera`].
 //!
 //! Add [`GizmoTarget`] component to any of your entities that you would like to manipulate the [`Transform`] of.
 //!
@@ -27,21 +27,58 @@
 //! You can configure the gizmo by modifying the [`GizmoOptions`] resource.
 //!
 //! You can either set it up with [`App::insert_resource`] when creating your App, or at any point in a system with [`ResMut<GizmoOptions>`].
+//!
+//! The gizmo systems are run in the [`GizmoSystems`] sets, which can be used for ordering
+//! your own systems or for adding run conditions to the gizmos. The gizmos are updated in
+//! [`PreUpdate`] right after input handling, so the [`GizmoTarget`]s are already transformed
+//! when your systems run in [`Update`], and drawn in [`Last`] with the camera of the same frame.
+//!
+//! # Rendering to images
+//!
+//! The [`GizmoCamera`] may render to an image instead of a window, for example for the scene
+//! view of an editor UI. The viewport is then sized from the image. Either show the image in
+//! [`GizmoOptions::viewport_rect`] of the window, or give the pointer position in pixels of the
+//! image with [`GizmoPointerSource::Custom`].
+//!
+//! # Determinism
+//!
+//! The gizmos are updated and drawn in a stable order that does not depend on random state
+//! or hash map iteration order, so the same inputs produce the same results and draw data
+//! on every run. See the [determinism](transform_gizmo#determinism) notes of `transform-gizmo`.
+//! The order of the [`GizmoTarget`] queries follows Bevy's own rules, which are deterministic
+//! as long as the entities are spawned in the same order.
+//!
+//! # Features
+//!
+//! - `bevy_ui`: Adds [`GizmoUiOverlay`], which draws the gizmos on top of a UI node
+//!   instead of in the 3D pass. Useful when the scene is rendered to a texture shown in the UI.
+//! - `bevy_gizmos`: Adds [`GizmoLineRendering`], which draws the gizmos as lines with Bevy's
+//!   own gizmos instead of the custom render pipeline.
+//! - `serde`: Serialization support for [`GizmoOptions`] and [`GizmoHotkeys`],
+//!   for example for persisting them with the settings of an editor.
+
+use std::collections::BTreeMap;
 
 use bevy_app::prelude::*;
 use bevy_asset::{AssetApp, Assets};
+use bevy_core_pipeline::core_3d::Camera3d;
 use bevy_ecs::prelude::*;
+use bevy_input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy_input::prelude::*;
-use bevy_math::{DQuat, DVec3, Vec2};
+use bevy_input::InputSystem;
+use bevy_math::{DQuat, DVec3, Mat4, Vec2};
+use bevy_reflect::prelude::*;
+use bevy_render::camera::RenderTarget;
 use bevy_render::prelude::*;
 use bevy_transform::prelude::*;
-use bevy_utils::{HashMap, Uuid};
+use bevy_utils::Uuid;
 use bevy_window::{PrimaryWindow, Window};
 
 use render::{DrawDataHandles, TransformGizmoRenderPlugin};
 use transform_gizmo::config::{
-    GizmoModeKind, TransformPivotPoint, DEFAULT_SNAP_ANGLE, DEFAULT_SNAP_DISTANCE,
-    DEFAULT_SNAP_SCALE,
+    ApplyMode, GizmoModeKind, GlobalScalePolicy, GroundPlane, LocalRotationAxes, ScaleInteraction,
+    SnapOrigin, TransformChannel, TransformLimit, TransformPivotPoint, ZeroScalePolicy,
+    DEFAULT_FOCUS_MARGIN, DEFAULT_SNAP_ANGLE, DEFAULT_SNAP_DISTANCE, DEFAULT_SNAP_SCALE,
 };
 pub use transform_gizmo::{
     math::{Pos2, Rect},
@@ -50,10 +87,22 @@ pub use transform_gizmo::{
 
 pub mod prelude;
 
+#[cfg(feature = "bevy_gizmos")]
+mod lines;
 mod render;
+#[cfg(feature = "bevy_ui")]
+mod ui_overlay;
+
+#[cfg(feature = "bevy_gizmos")]
+pub use lines::GizmoLineRendering;
+#[cfg(feature = "bevy_ui")]
+pub use ui_overlay::GizmoUiOverlay;
 
 const GIZMO_GROUP_UUID: Uuid = Uuid::from_u128(0x_1c90_3d44_0152_45e1_b1c9_889a_0203_e90c);
 
+/// Scroll distance in pixels counted as one mouse wheel step, for touchpads and smooth scrolling
+const WHEEL_PIXELS_PER_STEP: f32 = 100.0;
+
 /// Adds transform gizmos to the App.
 ///
 /// Gizmos are interactive tools that appear in the scene, allowing users to manipulate
@@ -63,30 +112,136 @@ pub struct TransformGizmoPlugin;
 impl Plugin for TransformGizmoPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<render::GizmoDrawData>()
+            .register_type::<GizmoOptions>()
             .init_resource::<GizmoOptions>()
             .init_resource::<GizmoStorage>()
+            .init_resource::<GizmoPointerSource>()
+            .add_event::<TransformGizmoEvent>()
             .add_plugins(TransformGizmoRenderPlugin)
+            .configure_sets(PreUpdate, GizmoSystems::Update.after(InputSystem))
+            .add_systems(
+                PreUpdate,
+                (assign_gizmo_camera, handle_hotkeys, update_gizmos)
+                    .chain()
+                    .in_set(GizmoSystems::Update),
+            )
             .add_systems(
                 Last,
-                (handle_hotkeys, update_gizmos, draw_gizmos, cleanup_old_data).chain(),
+                (
+                    sync_gizmo_camera,
+                    draw_gizmos.in_set(PipelineDrawSystems),
+                    cleanup_old_data,
+                )
+                    .chain()
+                    .in_set(GizmoSystems::Draw),
             );
+
+        #[cfg(feature = "bevy_ui")]
+        app.add_plugins(ui_overlay::GizmoUiOverlayPlugin);
+
+        #[cfg(feature = "bevy_gizmos")]
+        app.add_plugins(lines::GizmoLinesPlugin);
     }
 }
 
+/// Systems that prepare the gizmos for the render pipeline of this crate.
+#[derive(SystemSet, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineDrawSystems;
+
+/// System sets of the transform gizmos.
+///
+/// Useful for ordering other systems relative to the gizmos, or for
+/// running the gizmos only under certain conditions:
+///
+/// ```ignore
+/// app.configure_sets(PreUpdate, GizmoSystems::Update.run_if(in_edit_mode));
+/// ```
+#[derive(SystemSet, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GizmoSystems {
+    /// Handles hotkeys, updates the gizmos and transforms the [`GizmoTarget`]s.
+    /// Runs in the [`PreUpdate`] schedule after [`InputSystem`], so that the transformed
+    /// targets are propagated in the same frame.
+    Update,
+    /// Prepares the gizmos for rendering with the latest camera transform.
+    /// Runs in the [`Last`] schedule, so that the gizmos follow camera movement
+    /// of the same frame.
+    Draw,
+}
+
 /// Various options for configuring the transform gizmos.
-#[derive(Resource, Copy, Clone, Debug)]
+///
+/// Implements [`Reflect`], so that the options can be edited in inspectors.
+/// The modes and the colors of the visuals are not reflected.
+#[derive(Resource, Copy, Clone, Debug, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoOptions {
     /// Modes to use in the gizmos.
+    #[reflect(ignore)]
     pub gizmo_modes: EnumSet<GizmoMode>,
     /// Orientation of the gizmo. This affects the behaviour of transformations.
     pub gizmo_orientation: GizmoOrientation,
     /// Orientation of the gizmo. This affects the behaviour of transformations.
     pub pivot_point: TransformPivotPoint,
+    /// Offset of the gizmo from the median point of the targets.
+    pub anchor_offset: DVec3,
+    /// Whether [`GizmoOptions::anchor_offset`] is given in world space or
+    /// in the local space of the last target.
+    pub anchor_offset_orientation: GizmoOrientation,
+    /// Determines how scaling behaves when global orientation is used.
+    pub global_scale_policy: GlobalScalePolicy,
+    /// Determines how cursor movement is converted to scale.
+    pub scale_interaction: ScaleInteraction,
+    /// Determines how axes with zero scale are scaled.
+    pub zero_scale_policy: ZeroScalePolicy,
+    /// Determines which axes the targets are rotated around when
+    /// local orientation is used with multiple targets.
+    pub local_rotation_axes: LocalRotationAxes,
+    /// If set, dragging can be started anywhere in the viewport while
+    /// [`GizmoOptions::drag_anywhere`] is enabled. The most recently active
+    /// part of the gizmo is used, or this mode if none has been active yet.
+    pub drag_anywhere_mode: Option<GizmoMode>,
+    /// Whether dragging can currently be started anywhere in the viewport.
+    /// This may be overwritten with hotkeys ([`GizmoHotkeys::enable_drag_anywhere`]).
+    pub drag_anywhere: bool,
+    /// If set, translation arrows along the axes of this ground plane are
+    /// replaced with compass arrows lying on the plane.
+    pub ground_plane: Option<GroundPlane>,
+    /// If `true`, rotation handles are drawn as full circles and can be picked
+    /// from anywhere along the circle.
+    pub full_circle_rotation_handles: bool,
+    /// If `true`, uniform scaling can also be done with handles
+    /// at the outer corners of the plane handles.
+    pub uniform_scale_corners: bool,
+    /// Mirrors the gizmo horizontally in screen space.
+    pub mirrored: bool,
     /// Look and feel of the gizmo.
     pub visuals: GizmoVisuals,
+    /// Optional world space limits for the gizmo position along the X, Y and Z axes.
+    pub translation_limits: [Option<TransformLimit>; 3],
+    /// Optional limits for the rotation angle around the X, Y and Z axes, in radians.
+    pub rotation_limits: [Option<TransformLimit>; 3],
+    /// Relative margin by which another part of the gizmo must be a better
+    /// pick than the currently focused part to take the focus from it.
+    pub focus_margin: f32,
+    /// Determines whether target transforms are updated while dragging
+    /// or only once the drag ends.
+    pub apply_mode: ApplyMode,
     /// Whether snapping is enabled in the gizmo transformations.
     /// This may be overwritten with hotkeys ([`GizmoHotkeys::enable_snapping`]).
     pub snapping: bool,
+    /// Whether scrolling the mouse wheel over a handle transforms
+    /// the targets by one snapping increment per step.
+    pub wheel_interaction: bool,
+    /// Determines how the snapping distance of translations is chosen.
+    pub snap_mode: SnapMode,
+    /// Determines where the snapping increments of rotations are counted from.
+    pub rotation_snap_origin: SnapOrigin,
+    /// Determines where the snapping increments of translations are counted from.
+    pub translation_snap_origin: SnapOrigin,
+    /// World space origin of the lattice that translations snap to with [`SnapOrigin::Absolute`].
+    pub snap_origin: DVec3,
     /// When snapping is enabled, snap twice as often.
     /// This may be overwritten with hotkeys ([`GizmoHotkeys::enable_accurate_mode`]).
     pub accurate_mode: bool,
@@ -94,8 +249,16 @@ pub struct GizmoOptions {
     pub snap_angle: f32,
     /// Distance increment for snapping translations.
     pub snap_distance: f32,
+    /// Distance increments for snapping translations along the X, Y and Z axes,
+    /// overriding [`GizmoOptions::snap_distance`] for the axes that are set.
+    /// For example, `[None, Some(0.25), None]` snaps vertical movement to quarter units.
+    /// See [`GizmoConfig::snap_distance_per_axis`].
+    pub snap_distance_per_axis: [Option<f32>; 3],
     /// Scale increment for snapping scalings.
     pub snap_scale: f32,
+    /// If set, rotations snap to orientations aligned with the world axes within this angle, in radians.
+    /// See [`GizmoConfig::alignment_snap_angle`].
+    pub alignment_snap_angle: Option<f32>,
     /// If `true`, all [`GizmoTarget`]s are transformed
     /// using a single gizmo. If `false`, each target
     /// has its own gizmo.
@@ -107,8 +270,23 @@ pub struct GizmoOptions {
     pub hotkeys: Option<GizmoHotkeys>,
     /// Allows you to provide a custom viewport rect, which will be used to
     /// scale the cursor position. By default, this is set to `None` which means
-    /// the full window size is used as the viewport.
+    /// the viewport of the camera in its render target is used.
+    ///
+    /// Useful when the camera renders to a texture that is displayed in the UI.
+    /// See [`GizmoConfig::display_rect`].
     pub viewport_rect: Option<bevy_math::Rect>,
+    /// If `true`, the render target of the camera is displayed in [`GizmoOptions::viewport_rect`]
+    /// with its aspect ratio preserved, centered with bars on the sides or at the top and bottom.
+    /// The cursor is then mapped from the part of the rect where the render target is displayed.
+    /// See [`GizmoConfig::letterboxed_display_rect`].
+    pub letterbox_viewport_rect: bool,
+    /// If `true`, the gizmo operates relative to the camera position
+    /// instead of the world origin. This keeps the gizmo precise
+    /// for targets that are very far away from the world origin.
+    pub rebase_world: bool,
+    /// If `true` and no active camera has a [`GizmoCamera`] component,
+    /// it is added to the active 3D camera with the lowest order.
+    pub auto_gizmo_camera: bool,
 }
 
 impl Default for GizmoOptions {
@@ -117,28 +295,109 @@ impl Default for GizmoOptions {
             gizmo_modes: GizmoMode::all(),
             gizmo_orientation: GizmoOrientation::default(),
             pivot_point: TransformPivotPoint::default(),
+            anchor_offset: DVec3::ZERO,
+            anchor_offset_orientation: GizmoOrientation::default(),
+            global_scale_policy: GlobalScalePolicy::default(),
+            scale_interaction: ScaleInteraction::default(),
+            zero_scale_policy: ZeroScalePolicy::default(),
+            local_rotation_axes: LocalRotationAxes::default(),
+            drag_anywhere_mode: None,
+            drag_anywhere: false,
+            ground_plane: None,
+            full_circle_rotation_handles: false,
+            uniform_scale_corners: false,
+            mirrored: false,
             visuals: Default::default(),
+            translation_limits: [None; 3],
+            rotation_limits: [None; 3],
+            focus_margin: DEFAULT_FOCUS_MARGIN,
+            apply_mode: ApplyMode::default(),
             snapping: false,
+            wheel_interaction: false,
+            snap_mode: SnapMode::default(),
+            rotation_snap_origin: SnapOrigin::default(),
+            translation_snap_origin: SnapOrigin::default(),
+            snap_origin: DVec3::ZERO,
             accurate_mode: false,
             snap_angle: DEFAULT_SNAP_ANGLE,
             snap_distance: DEFAULT_SNAP_DISTANCE,
+            snap_distance_per_axis: [None; 3],
             snap_scale: DEFAULT_SNAP_SCALE,
+            alignment_snap_angle: None,
             group_targets: true,
             mode_override: None,
             hotkeys: None,
             viewport_rect: None,
+            letterbox_viewport_rect: false,
+            rebase_world: false,
+            auto_gizmo_camera: false,
+        }
+    }
+}
+
+impl GizmoOptions {
+    /// Options resembling the transform gizmo of Blender.
+    pub fn blender() -> Self {
+        Self {
+            gizmo_modes: GizmoMode::preset_blender(),
+            visuals: GizmoVisuals {
+                x_color: Color32::from_rgb(255, 51, 82),
+                y_color: Color32::from_rgb(139, 220, 0),
+                z_color: Color32::from_rgb(40, 144, 255),
+                ..Default::default()
+            },
+            hotkeys: Some(GizmoHotkeys::blender()),
+            ..Default::default()
+        }
+    }
+
+    /// Options resembling the transform tool of Unity.
+    pub fn unity() -> Self {
+        Self {
+            gizmo_modes: GizmoMode::preset_unity(),
+            visuals: GizmoVisuals {
+                x_color: Color32::from_rgb(219, 62, 29),
+                y_color: Color32::from_rgb(154, 243, 72),
+                z_color: Color32::from_rgb(58, 122, 248),
+                highlight_color: Some(Color32::from_rgb(246, 242, 50)),
+                ..Default::default()
+            },
+            hotkeys: Some(GizmoHotkeys::unity()),
+            ..Default::default()
+        }
+    }
+
+    /// Options resembling the universal manipulator of Maya.
+    pub fn maya() -> Self {
+        Self {
+            gizmo_modes: GizmoMode::preset_maya(),
+            visuals: GizmoVisuals {
+                x_color: Color32::from_rgb(230, 30, 30),
+                y_color: Color32::from_rgb(30, 230, 30),
+                z_color: Color32::from_rgb(40, 40, 240),
+                highlight_color: Some(Color32::from_rgb(255, 255, 0)),
+                ..Default::default()
+            },
+            hotkeys: Some(GizmoHotkeys::maya()),
+            ..Default::default()
         }
     }
 }
 
 /// Hotkeys for easier interaction with the gizmo.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoHotkeys {
     /// When pressed, transformations snap to according to snap values
     /// specified in [`GizmoOptions`].
     pub enable_snapping: Option<KeyCode>,
     /// When pressed, snapping is twice as accurate.
     pub enable_accurate_mode: Option<KeyCode>,
+    /// When pressed, dragging can be started anywhere in the viewport.
+    /// Requires [`GizmoOptions::drag_anywhere_mode`] to be set.
+    pub enable_drag_anywhere: Option<KeyCode>,
     /// Toggles gizmo to rotate-only mode.
     pub toggle_rotate: Option<KeyCode>,
     /// Toggles gizmo to translate-only mode.
@@ -164,6 +423,7 @@ impl Default for GizmoHotkeys {
         Self {
             enable_snapping: Some(KeyCode::ControlLeft),
             enable_accurate_mode: Some(KeyCode::ShiftLeft),
+            enable_drag_anywhere: Some(KeyCode::AltLeft),
             toggle_rotate: Some(KeyCode::KeyR),
             toggle_translate: Some(KeyCode::KeyG),
             toggle_scale: Some(KeyCode::KeyS),
@@ -176,6 +436,45 @@ impl Default for GizmoHotkeys {
     }
 }
 
+impl GizmoHotkeys {
+    /// Hotkeys resembling Blender. These are the same as the default hotkeys.
+    pub fn blender() -> Self {
+        Self::default()
+    }
+
+    /// Hotkeys resembling Unity, using W, E and R for translation, rotation and scale.
+    pub fn unity() -> Self {
+        Self {
+            enable_accurate_mode: None,
+            enable_drag_anywhere: None,
+            toggle_rotate: Some(KeyCode::KeyE),
+            toggle_translate: Some(KeyCode::KeyW),
+            toggle_scale: Some(KeyCode::KeyR),
+            toggle_x: None,
+            toggle_y: None,
+            toggle_z: None,
+            ..Self::default()
+        }
+    }
+
+    /// Hotkeys resembling Maya, using W, E and R for translation, rotation
+    /// and scale, and J for snapping.
+    pub fn maya() -> Self {
+        Self {
+            enable_snapping: Some(KeyCode::KeyJ),
+            enable_accurate_mode: None,
+            enable_drag_anywhere: None,
+            toggle_rotate: Some(KeyCode::KeyE),
+            toggle_translate: Some(KeyCode::KeyW),
+            toggle_scale: Some(KeyCode::KeyR),
+            toggle_x: None,
+            toggle_y: None,
+            toggle_z: None,
+            ..Self::default()
+        }
+    }
+}
+
 /// Marks an entity as a gizmo target.
 ///
 /// When an entity has this component and a [`Transform`],
@@ -193,6 +492,12 @@ pub struct GizmoTarget {
     /// Whether the gizmo is currently being interacted with.
     pub(crate) is_active: bool,
 
+    /// Mode of the part of the gizmo that is currently focused.
+    pub(crate) focused_mode: Option<GizmoMode>,
+
+    /// Mode of the part of the gizmo that is currently being interacted with.
+    pub(crate) active_mode: Option<GizmoMode>,
+
     /// This gets replaced with the result of the most recent
     /// gizmo interaction that affected this entity.
     pub(crate) latest_result: Option<GizmoResult>,
@@ -209,6 +514,17 @@ impl GizmoTarget {
         self.is_active
     }
 
+    /// Mode of the part of the gizmo that is currently focused,
+    /// for example the axis that would be grabbed when starting a drag.
+    pub fn focused_mode(&self) -> Option<GizmoMode> {
+        self.focused_mode
+    }
+
+    /// Mode of the part of the gizmo that is currently being interacted with.
+    pub fn active_mode(&self) -> Option<GizmoMode> {
+        self.active_mode
+    }
+
     /// This gets replaced with the result of the most recent
     /// gizmo interaction that affected this entity.
     pub fn latest_result(&self) -> Option<GizmoResult> {
@@ -216,15 +532,83 @@ impl GizmoTarget {
     }
 }
 
+/// Sent for each [`GizmoEvent`] of the gizmos, after they are updated.
+///
+/// Useful for feedback such as UI sounds or controller haptics,
+/// for example when a handle is hovered or a snapping increment is crossed.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct TransformGizmoEvent {
+    /// Target entity of the gizmo, or `None` for the gizmo shared by
+    /// all targets when [`GizmoOptions::group_targets`] is enabled.
+    pub target: Option<Entity>,
+    /// The event of the gizmo
+    pub event: GizmoEvent,
+}
+
+/// Locks channels of a [`GizmoTarget`]'s transform, so that
+/// gizmo interactions cannot change them.
+///
+/// For example, `GizmoLockedChannels(TransformChannel::all_rotate())`
+/// prevents the entity from being rotated, while it can still be
+/// translated and scaled along with other targets.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct GizmoLockedChannels(pub EnumSet<TransformChannel>);
+
 /// Marker used to specify which camera to use for gizmos.
+///
+/// The camera may render to a window or to an image, see the
+/// [crate documentation](crate#rendering-to-images).
+///
+/// Each window may have its own gizmo camera, for example for the detached views of an editor.
+/// The gizmos are then used with the camera of the window the cursor is in, and drawn only
+/// in its view. The camera is not switched during a drag.
 #[derive(Component)]
 pub struct GizmoCamera;
 
+/// Source of the pointer used for interacting with the gizmos.
+///
+/// By default, the cursor of the window of the [`GizmoCamera`] and the left mouse button are used.
+/// Replace this resource with [`GizmoPointerSource::Custom`] and update it every frame
+/// to drive the gizmos with something else, such as a gamepad-controlled virtual cursor
+/// or a remote editor. Update it before [`GizmoSystems::Update`] to avoid a frame of delay.
+#[derive(Resource, Copy, Clone, Debug, Default)]
+pub enum GizmoPointerSource {
+    /// Cursor of the window the [`GizmoCamera`] renders to and the left mouse button.
+    /// Cameras rendering to images use the cursor of the primary window.
+    #[default]
+    CameraWindowCursor,
+    /// Pointer state provided by the application.
+    Custom {
+        /// Pointer position in logical pixels, or `None` if the pointer is not available.
+        ///
+        /// The position is in the space of [`GizmoOptions::viewport_rect`] if it is set.
+        /// Otherwise, it is relative to the render target of the [`GizmoCamera`],
+        /// which is either a window or an image.
+        position: Option<Vec2>,
+        /// Whether the pointer is pressed, i.e. the gizmo is being dragged.
+        pressed: bool,
+        /// Whether the pointer was pressed this frame, i.e. a drag is started.
+        just_pressed: bool,
+    },
+}
+
+impl GizmoPointerSource {
+    /// Former name of [`GizmoPointerSource::CameraWindowCursor`]. The cursor is read from
+    /// the window the [`GizmoCamera`] renders to, which is not necessarily the primary window.
+    #[deprecated(note = "renamed to `GizmoPointerSource::CameraWindowCursor`")]
+    #[allow(non_upper_case_globals)]
+    pub const PrimaryWindowCursor: Self = Self::CameraWindowCursor;
+}
+
 #[derive(Resource, Default)]
 struct GizmoStorage {
     target_entities: Vec<Entity>,
-    entity_gizmo_map: HashMap<Entity, Uuid>,
-    gizmos: HashMap<Uuid, Gizmo>,
+    entity_gizmo_map: BTreeMap<Entity, Uuid>,
+    gizmos: GizmoGroup<Uuid>,
+    /// Origin of the gizmo coordinates in the world, see [`GizmoOptions::rebase_world`]
+    world_origin: DVec3,
+    /// Camera the gizmos were last updated with
+    camera: Option<Entity>,
 }
 
 fn handle_hotkeys(
@@ -246,6 +630,10 @@ fn handle_hotkeys(
         gizmo_options.accurate_mode = keyboard_input.pressed(accurate_mode_key);
     }
 
+    if let Some(drag_anywhere_key) = hotkeys.enable_drag_anywhere {
+        gizmo_options.drag_anywhere = keyboard_input.pressed(drag_anywhere_key);
+    }
+
     // Modifier for inverting the mode axis selection.
     // For example, X would force X axis, but Shift-X would force Y and Z axes.
     let invert_modifier = keyboard_input.pressed(KeyCode::ShiftLeft);
@@ -264,30 +652,27 @@ fn handle_hotkeys(
         .toggle_z
         .is_some_and(|key| keyboard_input.just_pressed(key));
 
+    let all_axes = enum_set!(GizmoDirection::X | GizmoDirection::Y | GizmoDirection::Z);
+    let hotkey_axes = |axis: GizmoDirection| {
+        if invert_modifier {
+            all_axes - axis
+        } else {
+            EnumSet::only(axis)
+        }
+    };
+
     let mut new_axes = EnumSet::empty();
 
     if x_hotkey_pressed {
-        new_axes = if invert_modifier {
-            enum_set!(GizmoDirection::Y | GizmoDirection::Z)
-        } else {
-            enum_set!(GizmoDirection::X)
-        };
+        new_axes = hotkey_axes(GizmoDirection::X);
     };
 
     if y_hotkey_pressed {
-        new_axes = if !invert_modifier {
-            enum_set!(GizmoDirection::Y)
-        } else {
-            enum_set!(GizmoDirection::X | GizmoDirection::Z)
-        };
+        new_axes = hotkey_axes(GizmoDirection::Y);
     };
 
     if z_hotkey_pressed {
-        new_axes = if !invert_modifier {
-            enum_set!(GizmoDirection::Z)
-        } else {
-            enum_set!(GizmoDirection::X | GizmoDirection::Y)
-        };
+        new_axes = hotkey_axes(GizmoDirection::Z);
     };
 
     // Replace the previously chosen axes, if any
@@ -332,21 +717,9 @@ fn handle_hotkeys(
         mode_override.map(|mode| mode.kind())
     };
 
-    *mode_override = mode_kind.and_then(|kind| {
-        // Find a mode that matches chosen axes and mode kind.
-        GizmoMode::all_from_axes(*axes)
-            .iter()
-            .find(|mode| mode.kind() == kind)
-            .or({
-                // If nothing matches, choose the default mode.
-                Some(match kind {
-                    GizmoModeKind::Rotate => GizmoMode::RotateView,
-                    GizmoModeKind::Translate => GizmoMode::TranslateView,
-                    GizmoModeKind::Scale => GizmoMode::ScaleUniform,
-                    GizmoModeKind::Arcball => GizmoMode::Arcball,
-                })
-            })
-    });
+    // Find a mode that matches chosen axes and mode kind.
+    // If nothing matches, choose the default mode.
+    *mode_override = mode_kind.map(|kind| kind.with_axes(*axes).unwrap_or(kind.default_mode()));
 
     // Check if gizmo should be deactivated
     if (hotkeys.mouse_click_deactivates
@@ -359,110 +732,240 @@ fn handle_hotkeys(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Adds [`GizmoCamera`] to a 3D camera if enabled with [`GizmoOptions::auto_gizmo_camera`].
+fn assign_gizmo_camera(
+    mut commands: Commands,
+    q_gizmo_camera: Query<&Camera, With<GizmoCamera>>,
+    q_cameras: Query<(Entity, &Camera), (With<Camera3d>, Without<GizmoCamera>)>,
+    gizmo_options: Res<GizmoOptions>,
+) {
+    if !gizmo_options.auto_gizmo_camera || q_gizmo_camera.iter().any(|camera| camera.is_active) {
+        return;
+    }
+
+    if let Some((entity, _)) = q_cameras
+        .iter()
+        .filter(|(_, camera)| camera.is_active)
+        .min_by_key(|(_, camera)| camera.order)
+    {
+        commands.entity(entity).insert(GizmoCamera);
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn update_gizmos(
-    q_window: Query<&Window, With<PrimaryWindow>>,
-    q_gizmo_camera: Query<(&Camera, &GlobalTransform), With<GizmoCamera>>,
-    mut q_targets: Query<(Entity, &mut Transform, &mut GizmoTarget), Without<GizmoCamera>>,
+    q_windows: Query<&Window>,
+    q_primary_window: Query<Entity, With<PrimaryWindow>>,
+    q_gizmo_camera: Query<(Entity, &Camera, &GlobalTransform), With<GizmoCamera>>,
+    mut q_targets: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut GizmoTarget,
+            Option<&GizmoLockedChannels>,
+        ),
+        Without<GizmoCamera>,
+    >,
     mouse: Res<ButtonInput<MouseButton>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    pointer_source: Res<GizmoPointerSource>,
     gizmo_options: Res<GizmoOptions>,
     mut gizmo_storage: ResMut<GizmoStorage>,
+    mut gizmo_events: EventWriter<TransformGizmoEvent>,
+    images: Res<Assets<Image>>,
     mut last_cursor_pos: Local<Vec2>,
-    mut last_scaled_cursor_pos: Local<Vec2>,
+    mut last_display_cursor_pos: Local<Vec2>,
 ) {
-    let Ok(window) = q_window.get_single() else {
-        // No primary window found.
-        return;
+    let primary_window = q_primary_window.get_single().ok();
+    let window_cursor_pos = |camera: &Camera| {
+        camera_window(camera, primary_window)
+            .and_then(|window| q_windows.get(window).ok())
+            .and_then(Window::cursor_position)
     };
 
-    let mut cursor_pos = window.cursor_position().unwrap_or_else(|| *last_cursor_pos);
-    *last_cursor_pos = cursor_pos;
+    let (dragging, drag_started) = match *pointer_source {
+        GizmoPointerSource::CameraWindowCursor => (
+            mouse.pressed(MouseButton::Left),
+            mouse.just_pressed(MouseButton::Left),
+        ),
+        GizmoPointerSource::Custom {
+            pressed,
+            just_pressed,
+            ..
+        } => (pressed, just_pressed),
+    };
 
-    let scale_factor = window.scale_factor();
+    let active_cameras = q_gizmo_camera
+        .iter()
+        .filter(|(_, camera, _)| camera.is_active)
+        .collect::<Vec<_>>();
 
-    let (camera, camera_transform) = {
-        let mut active_camera = None;
+    let previous_camera = active_cameras
+        .iter()
+        .find(|(entity, ..)| Some(*entity) == gizmo_storage.camera);
 
-        for camera in q_gizmo_camera.iter() {
-            if !camera.0.is_active {
-                continue;
-            }
-            if active_camera.is_some() {
-                // multiple active cameras found, warn and skip
-                bevy_log::warn!("Only one camera with a GizmoCamera component is supported.");
-                return;
-            }
-            active_camera = Some(camera);
-        }
+    // The camera is kept during a drag. Otherwise the camera of the window
+    // with the cursor is used, so that each window can have its own gizmo camera.
+    let selected_camera = if dragging && !drag_started {
+        previous_camera
+    } else {
+        active_cameras
+            .iter()
+            .find(|(_, camera, _)| {
+                matches!(*pointer_source, GizmoPointerSource::CameraWindowCursor)
+                    && window_cursor_pos(camera).is_some()
+            })
+            .or(previous_camera)
+    };
 
-        match active_camera {
-            Some(camera) => camera,
-            None => return, // no active cameras in the scene
-        }
+    let Some(&(camera_entity, camera, camera_transform)) =
+        selected_camera.or(active_cameras.first())
+    else {
+        // no active cameras in the scene
+        return;
+    };
+    gizmo_storage.camera = Some(camera_entity);
+
+    let cursor_pos = match *pointer_source {
+        GizmoPointerSource::CameraWindowCursor => window_cursor_pos(camera),
+        GizmoPointerSource::Custom { position, .. } => position,
     };
 
-    let Some(viewport) = camera.logical_viewport_rect() else {
+    let wheel_delta = mouse_wheel
+        .read()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / WHEEL_PIXELS_PER_STEP,
+        })
+        .sum();
+
+    let mut cursor_pos = cursor_pos.unwrap_or_else(|| *last_cursor_pos);
+    *last_cursor_pos = cursor_pos;
+
+    let Some(viewport) = camera_viewport(camera, &images) else {
         return;
     };
 
-    // scale up the cursor pos from the custom viewport rect, if provided
-    if let Some(custom_viewport) = gizmo_options.viewport_rect {
-        let vp_ratio = viewport.size() / custom_viewport.size();
-        let mut scaled_cursor_pos = (cursor_pos - (custom_viewport.min - viewport.min)) * vp_ratio;
-        if !viewport.contains(scaled_cursor_pos) {
-            scaled_cursor_pos = *last_scaled_cursor_pos;
+    // Scale factor of the window, or 1 for images
+    let scale_factor = camera.target_scaling_factor().unwrap_or(1.0);
+
+    let display_rect = gizmo_options.viewport_rect.map(|rect| {
+        if gizmo_options.letterbox_viewport_rect {
+            GizmoConfig::letterboxed_display_rect(viewport.size(), to_rect(rect))
+        } else {
+            to_rect(rect)
         }
-        *last_scaled_cursor_pos = scaled_cursor_pos;
-        cursor_pos = scaled_cursor_pos;
-    };
+    });
 
-    let viewport = Rect::from_min_max(
-        Pos2::new(viewport.min.x, viewport.min.y),
-        Pos2::new(viewport.max.x, viewport.max.y),
-    );
+    // The cursor pos is mapped from the custom viewport rect by the gizmo, if provided.
+    // The last cursor pos inside the rect is used while the cursor is outside of it.
+    if let Some(display_rect) = display_rect {
+        if !display_rect.contains(Pos2::new(cursor_pos.x, cursor_pos.y)) {
+            cursor_pos = *last_display_cursor_pos;
+        }
+        *last_display_cursor_pos = cursor_pos;
+    };
 
     let projection_matrix = camera.projection_matrix();
 
-    let view_matrix = camera_transform.compute_matrix().inverse();
+    // When rebasing, the camera is moved to the origin and targets are
+    // offset by the camera translation, to avoid losing precision in
+    // the view matrix for large world coordinates.
+    let world_origin = if gizmo_options.rebase_world {
+        camera_transform.translation().as_dvec3()
+    } else {
+        DVec3::ZERO
+    };
+    let view_matrix = gizmo_view_matrix(camera_transform, world_origin);
+    gizmo_storage.world_origin = world_origin;
 
     let mut snap_angle = gizmo_options.snap_angle;
     let mut snap_distance = gizmo_options.snap_distance;
+    let mut snap_distance_per_axis = gizmo_options.snap_distance_per_axis;
     let mut snap_scale = gizmo_options.snap_scale;
+    let mut snap_mode = gizmo_options.snap_mode;
 
     if gizmo_options.accurate_mode {
         snap_angle /= 2.0;
         snap_distance /= 2.0;
+        for distance in snap_distance_per_axis.iter_mut().flatten() {
+            *distance /= 2.0;
+        }
         snap_scale /= 2.0;
+
+        if let SnapMode::Adaptive { pixels_per_step } = &mut snap_mode {
+            *pixels_per_step /= 2.0;
+        }
+    }
+
+    // The limits are given in world space, like the targets
+    let mut translation_limits = gizmo_options.translation_limits;
+    for (limit, origin) in translation_limits.iter_mut().zip(world_origin.to_array()) {
+        if let Some(limit) = limit {
+            *limit = TransformLimit::new(limit.min - origin, limit.max - origin);
+        }
     }
 
     let gizmo_config = GizmoConfig {
         view_matrix: view_matrix.as_dmat4().into(),
         projection_matrix: projection_matrix.as_dmat4().into(),
         viewport,
+        display_rect,
         modes: gizmo_options.gizmo_modes,
         mode_override: gizmo_options.mode_override,
         orientation: gizmo_options.gizmo_orientation,
         pivot_point: gizmo_options.pivot_point,
+        anchor_offset: gizmo_options.anchor_offset.into(),
+        anchor_offset_orientation: gizmo_options.anchor_offset_orientation,
+        global_scale_policy: gizmo_options.global_scale_policy,
+        scale_interaction: gizmo_options.scale_interaction,
+        zero_scale_policy: gizmo_options.zero_scale_policy,
+        local_rotation_axes: gizmo_options.local_rotation_axes,
+        drag_anywhere_mode: gizmo_options.drag_anywhere_mode,
+        ground_plane: gizmo_options.ground_plane,
+        full_circle_rotation_handles: gizmo_options.full_circle_rotation_handles,
+        uniform_scale_corners: gizmo_options.uniform_scale_corners,
+        mirrored: gizmo_options.mirrored,
         visuals: gizmo_options.visuals,
+        translation_limits,
+        rotation_limits: gizmo_options.rotation_limits,
+        focus_margin: gizmo_options.focus_margin,
+        apply_mode: gizmo_options.apply_mode,
         snapping: gizmo_options.snapping,
+        wheel_interaction: gizmo_options.wheel_interaction,
+        snap_mode,
+        rotation_snap_origin: gizmo_options.rotation_snap_origin,
+        translation_snap_origin: gizmo_options.translation_snap_origin,
+        snap_origin: (gizmo_options.snap_origin - world_origin).into(),
         snap_angle,
         snap_distance,
+        snap_distance_per_axis,
         snap_scale,
+        alignment_snap_angle: gizmo_options.alignment_snap_angle,
         pixels_per_point: scale_factor,
     };
 
     let gizmo_interaction = GizmoInteraction {
         cursor_pos: (cursor_pos.x, cursor_pos.y),
-        drag_started: mouse.just_pressed(MouseButton::Left),
-        dragging: mouse.any_pressed([MouseButton::Left]),
+        drag_started,
+        dragging,
+        drag_anywhere: gizmo_options.drag_anywhere,
+        wheel_delta,
+        duplicate: false,
     };
 
+    gizmo_storage.gizmos.update_config(gizmo_config);
+
     let mut target_entities: Vec<Entity> = vec![];
     let mut target_transforms: Vec<Transform> = vec![];
+    let mut target_locked_channels: Vec<EnumSet<TransformChannel>> = vec![];
+
+    for (entity, mut target_transform, mut gizmo_target, locked_channels) in &mut q_targets {
+        let locked_channels = locked_channels.copied().unwrap_or_default().0;
 
-    for (entity, mut target_transform, mut gizmo_target) in &mut q_targets {
         target_entities.push(entity);
         target_transforms.push(*target_transform);
+        target_locked_channels.push(locked_channels);
 
         if gizmo_options.group_targets {
             gizmo_storage
@@ -471,33 +974,34 @@ fn update_gizmos(
             continue;
         }
 
-        let mut gizmo_uuid = *gizmo_storage
-            .entity_gizmo_map
-            .entry(entity)
-            .or_insert_with(Uuid::new_v4);
-
-        // Group gizmo was used previously
-        if gizmo_uuid == GIZMO_GROUP_UUID {
-            gizmo_uuid = Uuid::new_v4();
-            gizmo_storage.entity_gizmo_map.insert(entity, gizmo_uuid);
-        }
+        // Derived from the entity, so that the gizmos are the same on every run
+        let gizmo_uuid = Uuid::from_u128(u128::from(entity.to_bits()));
+        gizmo_storage.entity_gizmo_map.insert(entity, gizmo_uuid);
 
-        let gizmo = gizmo_storage.gizmos.entry(gizmo_uuid).or_default();
-        gizmo.update_config(gizmo_config);
-
-        let gizmo_result = gizmo.update(
+        let gizmo_result = gizmo_storage.gizmos.update(
+            gizmo_uuid,
             gizmo_interaction,
             &[math::Transform {
-                translation: target_transform.translation.as_dvec3().into(),
+                translation: (target_transform.translation.as_dvec3() - world_origin).into(),
                 rotation: target_transform.rotation.as_dquat().into(),
                 scale: target_transform.scale.as_dvec3().into(),
             }],
+            &[locked_channels],
         );
 
-        let is_focused = gizmo.is_focused();
+        let gizmo = gizmo_storage.gizmos.get(gizmo_uuid);
+
+        if let Some(gizmo) = gizmo {
+            gizmo_events.send_batch(gizmo.events().iter().map(|&event| TransformGizmoEvent {
+                target: Some(entity),
+                event,
+            }));
+        }
 
-        gizmo_target.is_active = gizmo_result.is_some();
-        gizmo_target.is_focused = is_focused;
+        gizmo_target.is_active = gizmo.and_then(Gizmo::active_mode).is_some();
+        gizmo_target.is_focused = gizmo.is_some_and(Gizmo::is_focused);
+        gizmo_target.focused_mode = gizmo.and_then(Gizmo::focused_mode);
+        gizmo_target.active_mode = gizmo.and_then(Gizmo::active_mode);
 
         if let Some((_, updated_targets)) = &gizmo_result {
             let Some(result_transform) = updated_targets.first() else {
@@ -505,7 +1009,8 @@ fn update_gizmos(
                 continue;
             };
 
-            target_transform.translation = DVec3::from(result_transform.translation).as_vec3();
+            target_transform.translation =
+                (DVec3::from(result_transform.translation) + world_origin).as_vec3();
             target_transform.rotation = DQuat::from(result_transform.rotation).as_quat();
             target_transform.scale = DVec3::from(result_transform.scale).as_vec3();
         }
@@ -514,27 +1019,40 @@ fn update_gizmos(
     }
 
     if gizmo_options.group_targets {
-        let gizmo = gizmo_storage.gizmos.entry(GIZMO_GROUP_UUID).or_default();
-        gizmo.update_config(gizmo_config);
-
-        let gizmo_result = gizmo.update(
+        let gizmo_result = gizmo_storage.gizmos.update(
+            GIZMO_GROUP_UUID,
             gizmo_interaction,
             target_transforms
                 .iter()
                 .map(|transform| transform_gizmo::math::Transform {
-                    translation: transform.translation.as_dvec3().into(),
+                    translation: (transform.translation.as_dvec3() - world_origin).into(),
                     rotation: transform.rotation.as_dquat().into(),
                     scale: transform.scale.as_dvec3().into(),
                 })
                 .collect::<Vec<_>>()
                 .as_slice(),
+            &target_locked_channels,
         );
 
-        let is_focused = gizmo.is_focused();
+        let gizmo = gizmo_storage.gizmos.get(GIZMO_GROUP_UUID);
+
+        if let Some(gizmo) = gizmo {
+            gizmo_events.send_batch(gizmo.events().iter().map(|&event| TransformGizmoEvent {
+                target: None,
+                event,
+            }));
+        }
 
-        for (i, (_, mut target_transform, mut gizmo_target)) in q_targets.iter_mut().enumerate() {
-            gizmo_target.is_active = gizmo_result.is_some();
+        let is_focused = gizmo.is_some_and(Gizmo::is_focused);
+        let focused_mode = gizmo.and_then(Gizmo::focused_mode);
+        let active_mode = gizmo.and_then(Gizmo::active_mode);
+
+        for (i, (_, mut target_transform, mut gizmo_target, _)) in q_targets.iter_mut().enumerate()
+        {
+            gizmo_target.is_active = active_mode.is_some();
             gizmo_target.is_focused = is_focused;
+            gizmo_target.focused_mode = focused_mode;
+            gizmo_target.active_mode = active_mode;
 
             if let Some((_, updated_targets)) = &gizmo_result {
                 let Some(result_transform) = updated_targets.get(i) else {
@@ -542,7 +1060,8 @@ fn update_gizmos(
                     continue;
                 };
 
-                target_transform.translation = DVec3::from(result_transform.translation).as_vec3();
+                target_transform.translation =
+                    (DVec3::from(result_transform.translation) + world_origin).as_vec3();
                 target_transform.rotation = DQuat::from(result_transform.rotation).as_quat();
                 target_transform.scale = DVec3::from(result_transform.scale).as_vec3();
             }
@@ -554,17 +1073,107 @@ fn update_gizmos(
     gizmo_storage.target_entities = target_entities;
 }
 
+/// Converts a Bevy rect to a gizmo rect
+fn to_rect(rect: bevy_math::Rect) -> Rect {
+    Rect::from_min_max(
+        Pos2::new(rect.min.x, rect.min.y),
+        Pos2::new(rect.max.x, rect.max.y),
+    )
+}
+
+/// Viewport of the camera in logical pixels of its render target.
+///
+/// For cameras rendering to an image, the size of the image is used until
+/// Bevy has computed the render target info, which happens after the gizmos are updated
+/// on the first frame of the camera.
+fn camera_viewport(camera: &Camera, images: &Assets<Image>) -> Option<Rect> {
+    if let Some(viewport) = camera.logical_viewport_rect() {
+        return Some(to_rect(viewport));
+    }
+
+    let RenderTarget::Image(image) = &camera.target else {
+        return None;
+    };
+    let image_size = images.get(image)?.size_f32();
+
+    // Images have no scale factor, so physical pixels are logical pixels
+    let (min, max) = camera
+        .viewport
+        .as_ref()
+        .map_or((Vec2::ZERO, image_size), |viewport| {
+            let min = viewport.physical_position.as_vec2();
+            (min, min + viewport.physical_size.as_vec2())
+        });
+
+    Some(to_rect(bevy_math::Rect::from_corners(min, max)))
+}
+
+/// Window the camera renders to.
+/// Cameras rendering to images are assumed to be shown in the primary window.
+fn camera_window(camera: &Camera, primary_window: Option<Entity>) -> Option<Entity> {
+    match &camera.target {
+        RenderTarget::Window(window) => window
+            .normalize(primary_window)
+            .map(|window| window.entity()),
+        _ => primary_window,
+    }
+}
+
+/// View matrix of the camera relative to the origin of the gizmo coordinates
+fn gizmo_view_matrix(camera_transform: &GlobalTransform, world_origin: DVec3) -> Mat4 {
+    let (scale, rotation, translation) = camera_transform.to_scale_rotation_translation();
+    let translation = (translation.as_dvec3() - world_origin).as_vec3();
+
+    Mat4::from_scale_rotation_translation(scale, rotation, translation).inverse()
+}
+
+/// Updates the gizmos with the camera after it has moved during the frame,
+/// so that they are drawn without lagging behind the camera.
+fn sync_gizmo_camera(
+    q_gizmo_camera: Query<(&Camera, &GlobalTransform), With<GizmoCamera>>,
+    images: Res<Assets<Image>>,
+    mut gizmo_storage: ResMut<GizmoStorage>,
+) {
+    let Some(Ok((camera, camera_transform))) = gizmo_storage
+        .camera
+        .map(|camera| q_gizmo_camera.get(camera))
+    else {
+        return;
+    };
+    if !camera.is_active {
+        return;
+    }
+    let Some(viewport) = camera_viewport(camera, &images) else {
+        return;
+    };
+
+    let view_matrix = gizmo_view_matrix(camera_transform, gizmo_storage.world_origin);
+    let projection_matrix = camera.projection_matrix();
+
+    let gizmo_uuids = gizmo_storage.gizmos.draw_order().collect::<Vec<_>>();
+    for gizmo_uuid in gizmo_uuids {
+        if let Some(gizmo) = gizmo_storage.gizmos.get_mut(gizmo_uuid) {
+            gizmo.set_viewport(viewport);
+            gizmo.set_view_projection(view_matrix.as_dmat4(), projection_matrix.as_dmat4());
+        }
+    }
+}
+
 fn draw_gizmos(
     gizmo_storage: Res<GizmoStorage>,
     mut draw_data_assets: ResMut<Assets<render::GizmoDrawData>>,
     mut draw_data_handles: ResMut<DrawDataHandles>,
 ) {
-    for (gizmo_uuid, gizmo) in &gizmo_storage.gizmos {
+    for (draw_order, gizmo_uuid) in gizmo_storage.gizmos.draw_order().enumerate() {
+        let Some(gizmo) = gizmo_storage.gizmos.get(gizmo_uuid) else {
+            continue;
+        };
+
         let draw_data = gizmo.draw();
 
         let mut bevy_draw_data = render::GizmoDrawData::default();
 
-        let (asset, is_new_asset) = if let Some(handle) = draw_data_handles.handles.get(gizmo_uuid)
+        let (asset, is_new_asset) = if let Some(handle) = draw_data_handles.handles.get(&gizmo_uuid)
         {
             (draw_data_assets.get_mut(handle).unwrap(), false)
         } else {
@@ -573,9 +1182,9 @@ fn draw_gizmos(
 
         let viewport = &gizmo.config().viewport;
 
-        asset.0.vertices.clear();
+        asset.draw_data.vertices.clear();
         asset
-            .0
+            .draw_data
             .vertices
             .extend(draw_data.vertices.into_iter().map(|vert| {
                 [
@@ -584,13 +1193,16 @@ fn draw_gizmos(
                 ]
             }));
 
-        asset.0.colors = draw_data.colors;
-        asset.0.indices = draw_data.indices;
+        asset.draw_data.colors = draw_data.colors;
+        asset.draw_data.indices = draw_data.indices;
+        asset.draw_order = draw_order as u32;
+        asset.ui_sort_key = None;
+        asset.camera = gizmo_storage.camera;
 
         if is_new_asset {
             let asset = draw_data_assets.add(bevy_draw_data);
 
-            draw_data_handles.handles.insert(*gizmo_uuid, asset.clone());
+            draw_data_handles.handles.insert(gizmo_uuid, asset.clone());
         }
     }
 }
@@ -620,7 +1232,7 @@ fn cleanup_old_data(
 
     gizmo_storage
         .gizmos
-        .retain(|uuid, _| gizmos_to_keep.contains(uuid));
+        .retain(|uuid| gizmos_to_keep.contains(uuid));
 
     draw_data_handles
         .handles
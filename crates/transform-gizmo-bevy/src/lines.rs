@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::prelude::*;
+use bevy_gizmos::gizmos::Gizmos;
+use bevy_math::Vec2;
+use bevy_render::prelude::*;
+use bevy_transform::prelude::GlobalTransform;
+
+use crate::render::DrawDataHandles;
+use crate::{GizmoCamera, GizmoStorage, GizmoSystems};
+
+/// Distance in front of the camera near plane at which the lines are drawn
+const LINE_DISTANCE: f32 = 0.1;
+
+/// If this resource exists, the gizmos are drawn as outlines with Bevy's [`Gizmos`]
+/// instead of the custom render pipeline of this crate.
+///
+/// Useful for a quick integration, for example when the custom pipeline does not
+/// support the render setup. The handles are drawn as lines only, without fills.
+///
+/// The lines are drawn just in front of the [`GizmoCamera`], so they are occluded
+/// only by objects very close to the camera. Setting `depth_bias` of the default
+/// Bevy gizmo config to -1 draws them on top of everything.
+///
+/// ```ignore
+/// app.insert_resource(GizmoLineRendering);
+/// ```
+#[derive(Resource, Copy, Clone, Debug, Default)]
+pub struct GizmoLineRendering;
+
+pub(crate) struct GizmoLinesPlugin;
+
+impl Plugin for GizmoLinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            Last,
+            crate::PipelineDrawSystems.run_if(not(resource_exists::<GizmoLineRendering>)),
+        )
+        .add_systems(
+            Last,
+            draw_gizmo_lines
+                .in_set(GizmoSystems::Draw)
+                .after(crate::sync_gizmo_camera)
+                .run_if(resource_exists::<GizmoLineRendering>),
+        );
+    }
+}
+
+fn draw_gizmo_lines(
+    gizmo_storage: Res<GizmoStorage>,
+    q_gizmo_camera: Query<(&Camera, &GlobalTransform), With<GizmoCamera>>,
+    mut draw_data_handles: ResMut<DrawDataHandles>,
+    mut gizmos: Gizmos,
+) {
+    // Meshes left over from the render pipeline would otherwise still be drawn
+    draw_data_handles.handles.clear();
+
+    let Some(Ok((camera, camera_transform))) = gizmo_storage
+        .camera
+        .map(|camera| q_gizmo_camera.get(camera))
+    else {
+        return;
+    };
+
+    for gizmo_uuid in gizmo_storage.gizmos.draw_order() {
+        let Some(gizmo) = gizmo_storage.gizmos.get(gizmo_uuid) else {
+            continue;
+        };
+
+        let draw_data = gizmo.draw();
+        let viewport_min = gizmo.config().viewport.min;
+
+        // Vertices are given relative to the window, but rays are cast relative to the viewport
+        let points = draw_data
+            .vertices
+            .iter()
+            .map(|[x, y]| {
+                let viewport_pos = Vec2::new(x - viewport_min.x, y - viewport_min.y);
+                camera
+                    .viewport_to_world(camera_transform, viewport_pos)
+                    .map(|ray| ray.get_point(LINE_DISTANCE))
+            })
+            .collect::<Vec<_>>();
+
+        for (start, end, color) in outline_edges(&draw_data) {
+            if let (Some(start), Some(end)) = (points[start], points[end]) {
+                gizmos.line(start, end, color);
+            }
+        }
+    }
+}
+
+/// Edges of the triangles that are not shared with other triangles, with the color
+/// of the most opaque vertex of their triangle. The fully transparent outer edges
+/// of the anti-aliased shapes are thus drawn in the color of the shape.
+///
+/// The edges are ordered by their vertex indices, so the lines are drawn in the same order every frame.
+fn outline_edges(draw_data: &transform_gizmo::GizmoDrawData) -> Vec<(usize, usize, Color)> {
+    let mut edges = BTreeMap::<(usize, usize), (u32, Color)>::new();
+
+    for triangle in draw_data.indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+
+        let [red, green, blue, alpha] = [a, b, c]
+            .into_iter()
+            .map(|index| draw_data.colors[index])
+            .max_by(|first, second| first[3].total_cmp(&second[3]))
+            .unwrap_or_default();
+
+        if alpha <= 0.0 {
+            continue;
+        }
+
+        // Colors are premultiplied
+        let color = Color::rgba_linear(red / alpha, green / alpha, blue / alpha, alpha);
+
+        for (start, end) in [(a, b), (b, c), (c, a)] {
+            edges
+                .entry((start.min(end), start.max(end)))
+                .or_insert((0, color))
+                .0 += 1;
+        }
+    }
+
+    edges
+        .into_iter()
+        .filter(|(_, (count, _))| *count == 1)
+        .map(|((start, end), (_, color))| (start, end, color))
+        .collect()
+}
@@ -1,3 +1,11 @@
 pub use transform_gizmo::prelude::*;
 
-pub use crate::{GizmoCamera, GizmoOptions, GizmoTarget, TransformGizmoPlugin};
+pub use crate::{
+    GizmoCamera, GizmoLockedChannels, GizmoOptions, GizmoSystems, GizmoTarget, TransformGizmoEvent,
+    TransformGizmoPlugin,
+};
+
+#[cfg(feature = "bevy_gizmos")]
+pub use crate::GizmoLineRendering;
+#[cfg(feature = "bevy_ui")]
+pub use crate::GizmoUiOverlay;
@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
+
 use bevy_app::{App, Plugin};
-use bevy_asset::{load_internal_asset, Asset, Handle};
+use bevy_asset::{Asset, Assets, Handle};
 use bevy_core::cast_slice;
 use bevy_core_pipeline::core_3d::{Transparent3d, CORE_3D_DEPTH_FORMAT};
 use bevy_core_pipeline::prepass::{
@@ -22,25 +24,31 @@ use bevy_render::render_phase::{
     SetItemPipeline, TrackedRenderPass,
 };
 use bevy_render::render_resource::{
-    BlendState, Buffer, BufferInitDescriptor, BufferUsages, ColorTargetState, ColorWrites,
-    CompareFunction, DepthBiasState, DepthStencilState, FragmentState, IndexFormat,
-    MultisampleState, PipelineCache, PrimitiveState, RenderPipelineDescriptor,
-    SpecializedRenderPipeline, SpecializedRenderPipelines, StencilState, TextureFormat,
-    VertexAttribute, VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+    BlendState, Buffer, BufferInitDescriptor, BufferUsages, CachedRenderPipelineId,
+    ColorTargetState, ColorWrites, CompareFunction, DepthBiasState, DepthStencilState,
+    FragmentState, IndexFormat, MultisampleState, PipelineCache, PrimitiveState,
+    RenderPipelineDescriptor, SpecializedRenderPipeline, SpecializedRenderPipelines, StencilState,
+    TextureFormat, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
 };
 use bevy_render::renderer::RenderDevice;
 use bevy_render::texture::BevyDefault;
 use bevy_render::view::{ExtractedView, RenderLayers, ViewTarget};
 use bevy_render::{Extract, Render, RenderApp, RenderSet};
-use bevy_utils::{HashMap, HashSet, Uuid};
+use bevy_utils::{HashMap, Uuid};
 
-const GIZMO_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(7414812681337026784);
+pub(crate) const GIZMO_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(7414812681337026784);
 
 pub(crate) struct TransformGizmoRenderPlugin;
 
 impl Plugin for TransformGizmoRenderPlugin {
     fn build(&self, app: &mut App) {
-        load_internal_asset!(app, GIZMO_SHADER_HANDLE, "gizmo.wgsl", Shader::from_wgsl);
+        app.world.resource_mut::<Assets<Shader>>().insert(
+            GIZMO_SHADER_HANDLE,
+            Shader::from_wgsl(
+                transform_gizmo::reference_wgsl(),
+                "transform_gizmo/gizmo.wgsl",
+            ),
+        );
 
         app.init_resource::<DrawDataHandles>()
             .add_plugins(RenderAssetPlugin::<GizmoDrawData>::default());
@@ -52,6 +60,7 @@ impl Plugin for TransformGizmoRenderPlugin {
         render_app
             .add_render_command::<Transparent3d, DrawGizmo>()
             .init_resource::<SpecializedRenderPipelines<TransformGizmoPipeline>>()
+            .init_resource::<TransformGizmoPipelineIds>()
             .add_systems(
                 Render,
                 queue_transform_gizmos
@@ -73,23 +82,27 @@ impl Plugin for TransformGizmoRenderPlugin {
 
 #[derive(Resource, Default)]
 pub(crate) struct DrawDataHandles {
-    pub(crate) handles: HashMap<Uuid, Handle<GizmoDrawData>>,
+    /// Ordered by the gizmo ids, so that the draw data is extracted in the same order every frame
+    pub(crate) handles: BTreeMap<Uuid, Handle<GizmoDrawData>>,
 }
 
 fn extract_gizmo_data(mut commands: Commands, handles: Extract<Res<DrawDataHandles>>) {
-    let handle_weak_refs = handles
-        .handles
-        .values()
-        .map(|handle| handle.clone_weak())
-        .collect::<HashSet<_>>();
-
-    for handle in handle_weak_refs {
-        commands.spawn((handle,));
+    for handle in handles.handles.values() {
+        commands.spawn((handle.clone_weak(),));
     }
 }
 
 #[derive(Asset, Debug, Default, Clone, TypePath)]
-pub(crate) struct GizmoDrawData(pub(crate) transform_gizmo::GizmoDrawData);
+pub(crate) struct GizmoDrawData {
+    pub(crate) draw_data: transform_gizmo::GizmoDrawData,
+    /// Position of the gizmo in the draw order. Gizmos with larger values are drawn on top.
+    pub(crate) draw_order: u32,
+    /// If set, the gizmo is drawn in the UI pass with this sort key
+    /// instead of the 3D pass. See [`GizmoUiOverlay`](crate::GizmoUiOverlay).
+    pub(crate) ui_sort_key: Option<f32>,
+    /// Camera in whose view the gizmo is drawn in the 3D pass, or all views if `None`
+    pub(crate) camera: Option<Entity>,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct GizmoBuffers {
@@ -97,6 +110,9 @@ pub(crate) struct GizmoBuffers {
     index_buffer: Buffer,
     color_buffer: Buffer,
     index_count: u32,
+    pub(crate) draw_order: u32,
+    pub(crate) ui_sort_key: Option<f32>,
+    camera: Option<Entity>,
 }
 
 impl RenderAsset for GizmoDrawData {
@@ -111,21 +127,21 @@ impl RenderAsset for GizmoDrawData {
         self,
         render_device: &mut SystemParamItem<Self::Param>,
     ) -> Result<Self::PreparedAsset, PrepareAssetError<Self>> {
-        let position_buffer_data = cast_slice(&self.0.vertices);
+        let position_buffer_data = cast_slice(&self.draw_data.vertices);
         let position_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
             usage: BufferUsages::VERTEX,
             label: Some("TransformGizmo Position Buffer"),
             contents: position_buffer_data,
         });
 
-        let index_buffer_data = cast_slice(&self.0.indices);
+        let index_buffer_data = cast_slice(&self.draw_data.indices);
         let index_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
             usage: BufferUsages::INDEX,
             label: Some("TransformGizmo Index Buffer"),
             contents: index_buffer_data,
         });
 
-        let color_buffer_data = cast_slice(&self.0.colors);
+        let color_buffer_data = cast_slice(&self.draw_data.colors);
         let color_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
             usage: BufferUsages::VERTEX,
             label: Some("TransformGizmo Color Buffer"),
@@ -136,12 +152,15 @@ impl RenderAsset for GizmoDrawData {
             index_buffer,
             position_buffer,
             color_buffer,
-            index_count: self.0.indices.len() as u32,
+            index_count: self.draw_data.indices.len() as u32,
+            draw_order: self.draw_order,
+            ui_sort_key: self.ui_sort_key,
+            camera: self.camera,
         })
     }
 }
 
-struct DrawTransformGizmo;
+pub(crate) struct DrawTransformGizmo;
 
 impl<P: PhaseItem> RenderCommand<P> for DrawTransformGizmo {
     type ViewQuery = ();
@@ -187,7 +206,7 @@ impl FromWorld for TransformGizmoPipeline {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
 struct TransformGizmoPipelineKey {
     view_key: MeshPipelineKey,
     perspective: bool,
@@ -223,26 +242,18 @@ impl SpecializedRenderPipeline for TransformGizmoPipeline {
                 shader: GIZMO_SHADER_HANDLE,
                 entry_point: "vertex".into(),
                 shader_defs: shader_defs.clone(),
-                buffers: vec![
-                    VertexBufferLayout {
-                        array_stride: VertexFormat::Float32x2.size(),
+                buffers: transform_gizmo::GizmoDrawData::VERTEX_LAYOUT
+                    .iter()
+                    .map(|attribute| VertexBufferLayout {
+                        array_stride: attribute.size(),
                         step_mode: VertexStepMode::Vertex,
                         attributes: vec![VertexAttribute {
-                            format: VertexFormat::Float32x2,
+                            format: vertex_format(attribute.components),
                             offset: 0,
-                            shader_location: 0,
+                            shader_location: attribute.location,
                         }],
-                    },
-                    VertexBufferLayout {
-                        array_stride: VertexFormat::Float32x4.size(),
-                        step_mode: VertexStepMode::Vertex,
-                        attributes: vec![VertexAttribute {
-                            format: VertexFormat::Float32x4,
-                            offset: 0,
-                            shader_location: 1,
-                        }],
-                    },
-                ],
+                    })
+                    .collect(),
             },
             fragment: Some(FragmentState {
                 shader: GIZMO_SHADER_HANDLE,
@@ -277,18 +288,37 @@ impl SpecializedRenderPipeline for TransformGizmoPipeline {
     }
 }
 
+/// Pipelines specialized for each view key, so that they are not
+/// specialized again for every gizmo and view each frame.
+#[derive(Resource, Default)]
+struct TransformGizmoPipelineIds {
+    pipelines: HashMap<TransformGizmoPipelineKey, CachedRenderPipelineId>,
+}
+
 type DrawGizmo = (SetItemPipeline, SetMeshViewBindGroup<0>, DrawTransformGizmo);
 
+/// Vertex format of an attribute with given number of `f32` components
+pub(crate) fn vertex_format(components: u32) -> VertexFormat {
+    match components {
+        1 => VertexFormat::Float32,
+        2 => VertexFormat::Float32x2,
+        3 => VertexFormat::Float32x3,
+        _ => VertexFormat::Float32x4,
+    }
+}
+
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn queue_transform_gizmos(
     draw_functions: Res<DrawFunctions<Transparent3d>>,
     pipeline: Res<TransformGizmoPipeline>,
     mut pipelines: ResMut<SpecializedRenderPipelines<TransformGizmoPipeline>>,
+    mut pipeline_ids: ResMut<TransformGizmoPipelineIds>,
     pipeline_cache: Res<PipelineCache>,
     msaa: Res<Msaa>,
     transform_gizmos: Query<(Entity, &Handle<GizmoDrawData>)>,
     transform_gizmo_assets: Res<RenderAssets<GizmoDrawData>>,
     mut views: Query<(
+        Entity,
         &ExtractedView,
         &mut RenderPhase<Transparent3d>,
         Option<&RenderLayers>,
@@ -300,9 +330,22 @@ fn queue_transform_gizmos(
         ),
     )>,
 ) {
+    // Gizmos with something to draw in the 3D pass. Gizmos with
+    // a UI sort key are drawn in the UI pass instead.
+    let visible_gizmos = transform_gizmos
+        .iter()
+        .filter_map(|(entity, handle)| Some((entity, transform_gizmo_assets.get(handle.id())?)))
+        .filter(|(_, gizmo)| gizmo.index_count > 0 && gizmo.ui_sort_key.is_none())
+        .collect::<Vec<_>>();
+
+    if visible_gizmos.is_empty() {
+        return;
+    }
+
     let draw_function = draw_functions.read().get_id::<DrawGizmo>().unwrap();
 
     for (
+        view_entity,
         view,
         mut transparent_phase,
         _render_layers,
@@ -328,25 +371,27 @@ fn queue_transform_gizmos(
             view_key |= MeshPipelineKey::DEFERRED_PREPASS;
         }
 
-        for (entity, handle) in &transform_gizmos {
-            let Some(_) = transform_gizmo_assets.get(handle.id()) else {
-                continue;
-            };
-
-            let pipeline = pipelines.specialize(
-                &pipeline_cache,
-                &pipeline,
-                TransformGizmoPipelineKey {
-                    view_key,
-                    perspective: true,
-                },
-            );
+        let key = TransformGizmoPipelineKey {
+            view_key,
+            perspective: true,
+        };
+
+        let pipeline = *pipeline_ids
+            .pipelines
+            .entry(key)
+            .or_insert_with(|| pipelines.specialize(&pipeline_cache, &pipeline, key));
+
+        // Cameras are extracted to the render world with the same entities
+        let view_gizmos = visible_gizmos
+            .iter()
+            .filter(|(_, gizmo)| gizmo.camera.map_or(true, |camera| camera == view_entity));
 
+        for &(entity, gizmo) in view_gizmos {
             transparent_phase.add(Transparent3d {
                 entity,
                 draw_function,
                 pipeline,
-                distance: 0.,
+                distance: gizmo.draw_order as f32,
                 batch_range: 0..1,
                 dynamic_offset: None,
             });
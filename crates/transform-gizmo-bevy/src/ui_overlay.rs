@@ -0,0 +1,206 @@
+use bevy_app::{App, Last, Plugin};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_render::mesh::PrimitiveTopology;
+use bevy_render::render_asset::{prepare_assets, RenderAssets};
+use bevy_render::render_phase::{AddRenderCommand, DrawFunctions, RenderPhase, SetItemPipeline};
+use bevy_render::render_resource::{
+    BlendState, ColorTargetState, ColorWrites, FragmentState, MultisampleState, PipelineCache,
+    PrimitiveState, RenderPipelineDescriptor, SpecializedRenderPipeline,
+    SpecializedRenderPipelines, TextureFormat, VertexAttribute, VertexBufferLayout, VertexState,
+    VertexStepMode,
+};
+use bevy_render::texture::BevyDefault;
+use bevy_render::view::{ExtractedView, ViewTarget};
+use bevy_render::{Render, RenderApp, RenderSet};
+use bevy_transform::prelude::GlobalTransform;
+use bevy_ui::{Node, TransparentUi};
+use bevy_utils::FloatOrd;
+use bevy_window::{PrimaryWindow, Window};
+
+use crate::render::{
+    vertex_format, DrawDataHandles, DrawTransformGizmo, GizmoDrawData, GIZMO_SHADER_HANDLE,
+};
+
+/// Marker for a UI node that shows the output of the [`GizmoCamera`](crate::GizmoCamera).
+///
+/// Useful when the scene is rendered to a texture that is displayed in the UI,
+/// in which case gizmos drawn in the 3D pass would end up below the UI image.
+/// If a node with this component exists, the gizmos are instead drawn in the UI pass,
+/// stretched over the node, on top of it.
+///
+/// The gizmos are still interacted with in the viewport of the camera.
+/// Use [`GizmoPointerSource::Custom`](crate::GizmoPointerSource::Custom) to map the cursor
+/// from the node to the camera viewport.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct GizmoUiOverlay;
+
+pub(crate) struct GizmoUiOverlayPlugin;
+
+impl Plugin for GizmoUiOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, place_in_ui_overlay.after(crate::GizmoSystems::Draw));
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        // The UI pass only exists if the UI plugin has been added
+        if !render_app
+            .world
+            .contains_resource::<DrawFunctions<TransparentUi>>()
+        {
+            return;
+        }
+
+        render_app
+            .add_render_command::<TransparentUi, DrawGizmoUi>()
+            .init_resource::<SpecializedRenderPipelines<TransformGizmoUiPipeline>>()
+            .init_resource::<TransformGizmoUiPipeline>()
+            .add_systems(
+                Render,
+                queue_transform_gizmos_ui
+                    .in_set(RenderSet::Queue)
+                    .after(prepare_assets::<GizmoDrawData>),
+            );
+    }
+}
+
+/// Moves the gizmo vertices from the camera viewport to the overlay node.
+fn place_in_ui_overlay(
+    overlay_nodes: Query<(&Node, &GlobalTransform), With<GizmoUiOverlay>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    draw_data_handles: Res<DrawDataHandles>,
+    mut draw_data_assets: ResMut<Assets<GizmoDrawData>>,
+) {
+    let Some((node, node_transform)) = overlay_nodes.iter().next() else {
+        return;
+    };
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let window_size = window.size();
+    let half_size = node.size() * 0.5;
+    let center = node_transform.translation().truncate();
+    let sort_key = node.stack_index() as f32 + 0.5;
+
+    for handle in draw_data_handles.handles.values() {
+        let Some(asset) = draw_data_assets.get_mut(handle) else {
+            continue;
+        };
+
+        // Both the input and output are normalized device coordinates with the y axis pointing down
+        for [x, y] in &mut asset.draw_data.vertices {
+            *x = (center.x + *x * half_size.x) / window_size.x * 2.0 - 1.0;
+            *y = (center.y + *y * half_size.y) / window_size.y * 2.0 - 1.0;
+        }
+
+        asset.ui_sort_key = Some(sort_key);
+    }
+}
+
+#[derive(Clone, Resource, Default)]
+struct TransformGizmoUiPipeline;
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct TransformGizmoUiPipelineKey {
+    hdr: bool,
+}
+
+impl SpecializedRenderPipeline for TransformGizmoUiPipeline {
+    type Key = TransformGizmoUiPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let format = if key.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("TransformGizmo UI Pipeline".into()),
+            vertex: VertexState {
+                shader: GIZMO_SHADER_HANDLE,
+                entry_point: "vertex".into(),
+                shader_defs: vec![],
+                buffers: transform_gizmo::GizmoDrawData::VERTEX_LAYOUT
+                    .iter()
+                    .map(|attribute| VertexBufferLayout {
+                        array_stride: attribute.size(),
+                        step_mode: VertexStepMode::Vertex,
+                        attributes: vec![VertexAttribute {
+                            format: vertex_format(attribute.components),
+                            offset: 0,
+                            shader_location: attribute.location,
+                        }],
+                    })
+                    .collect(),
+            },
+            fragment: Some(FragmentState {
+                shader: GIZMO_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![],
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..PrimitiveState::default()
+            },
+            // The UI pass has no depth buffer and is not multisampled
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+        }
+    }
+}
+
+type DrawGizmoUi = (SetItemPipeline, DrawTransformGizmo);
+
+fn queue_transform_gizmos_ui(
+    draw_functions: Res<DrawFunctions<TransparentUi>>,
+    pipeline: Res<TransformGizmoUiPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<TransformGizmoUiPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    transform_gizmos: Query<(Entity, &Handle<GizmoDrawData>)>,
+    transform_gizmo_assets: Res<RenderAssets<GizmoDrawData>>,
+    mut views: Query<(&ExtractedView, &mut RenderPhase<TransparentUi>)>,
+) {
+    let draw_function = draw_functions.read().get_id::<DrawGizmoUi>().unwrap();
+
+    for (view, mut ui_phase) in &mut views {
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &pipeline,
+            TransformGizmoUiPipelineKey { hdr: view.hdr },
+        );
+
+        for (entity, handle) in &transform_gizmos {
+            let Some(gizmo) = transform_gizmo_assets.get(handle.id()) else {
+                continue;
+            };
+
+            let Some(sort_key) = gizmo.ui_sort_key else {
+                continue;
+            };
+
+            ui_phase.add(TransparentUi {
+                sort_key: (FloatOrd(sort_key), gizmo.draw_order),
+                entity,
+                pipeline,
+                draw_function,
+                batch_range: 0..1,
+                dynamic_offset: None,
+            });
+        }
+    }
+}
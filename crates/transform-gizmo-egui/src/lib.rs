@@ -44,18 +44,39 @@
 //! ```
 //!
 //!
-use egui::{epaint::Vertex, Mesh, PointerButton, Pos2, Rgba, Ui};
+use egui::{epaint::Vertex, Mesh, PointerButton, Pos2, Rect, Rgba, Ui, Vec2};
 
 use transform_gizmo::math::Transform;
 pub use transform_gizmo::*;
 pub mod prelude;
 
+/// Scroll distance in points counted as one mouse wheel step
+const WHEEL_POINTS_PER_STEP: f32 = 50.0;
+
 pub trait GizmoExt {
     /// Interact with the gizmo and draw it to Ui.
     ///
+    /// While Alt is held, dragging can be started anywhere in the Ui,
+    /// if [`GizmoConfig::drag_anywhere_mode`] is set. Drags started while
+    /// Shift is held request duplication of the targets, see [`Gizmo::rebase_targets`].
+    ///
     /// Returns result of the gizmo interaction.
     fn interact(&mut self, ui: &Ui, targets: &[Transform])
         -> Option<(GizmoResult, Vec<Transform>)>;
+
+    /// Interact with the gizmo and draw it to Ui, when the gizmo viewport is
+    /// not given in screen space.
+    ///
+    /// `offset` is the screen position of the viewport space origin, for example
+    /// the origin of a scrolled panel's content. See [`ViewportMapping`].
+    ///
+    /// Returns result of the gizmo interaction.
+    fn interact_with_offset(
+        &mut self,
+        ui: &Ui,
+        targets: &[Transform],
+        offset: Vec2,
+    ) -> Option<(GizmoResult, Vec<Transform>)>;
 }
 
 impl GizmoExt for Gizmo {
@@ -64,48 +85,78 @@ impl GizmoExt for Gizmo {
         ui: &Ui,
         targets: &[Transform],
     ) -> Option<(GizmoResult, Vec<Transform>)> {
-        let config = self.config();
+        self.interact_with_offset(ui, targets, Vec2::ZERO)
+    }
 
-        let egui_viewport = egui::Rect {
-            min: Pos2::new(config.viewport.min.x, config.viewport.min.y),
-            max: Pos2::new(config.viewport.max.x, config.viewport.max.y),
-        };
+    fn interact_with_offset(
+        &mut self,
+        ui: &Ui,
+        targets: &[Transform],
+        offset: Vec2,
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        let pixels_per_point = ui.ctx().pixels_per_point();
 
-        let cursor_pos = ui
-            .input(|input| input.pointer.hover_pos())
-            .unwrap_or_default();
+        // The mapping rounds the offset to physical pixels,
+        // and the fallback viewport has to use the same offset as the pointer.
+        let mut mapping = ViewportMapping::new(Rect::NOTHING, offset, pixels_per_point);
 
         let mut viewport = self.config().viewport;
         if !viewport.is_finite() {
-            viewport = ui.clip_rect();
+            viewport = ui.clip_rect().translate(-mapping.offset).into();
         }
 
+        // The gizmo is drawn over the display rect, if the viewport is displayed elsewhere
+        mapping.viewport = self.config().display_rect.unwrap_or(viewport).into();
+
+        // Only the part of the viewport that is visible in the Ui is drawn to,
+        // so that the gizmo does not overdraw the surroundings of, for example, a window.
+        let clip_rect = ui.clip_rect().intersect(mapping.screen_viewport());
+
+        // The pointer is ignored while it is outside of the clip rect or
+        // covered by another layer, unless the gizmo is already being dragged.
+        let pointer_pos = ui
+            .input(|input| input.pointer.hover_pos())
+            .filter(|_| self.active_mode().is_some() || ui.rect_contains_pointer(clip_rect));
+
+        let cursor_pos = pointer_pos
+            .map(|pos| mapping.to_viewport(pos))
+            .unwrap_or_default();
+
         self.update_config(GizmoConfig {
             viewport,
-            pixels_per_point: ui.ctx().pixels_per_point(),
+            pixels_per_point,
             ..*self.config()
         });
 
         let gizmo_result = self.update(
             GizmoInteraction {
                 cursor_pos: (cursor_pos.x, cursor_pos.y),
-                drag_started: ui
-                    .input(|input| input.pointer.button_pressed(PointerButton::Primary)),
+                drag_started: pointer_pos.is_some()
+                    && ui.input(|input| input.pointer.button_pressed(PointerButton::Primary)),
                 dragging: ui.input(|input| input.pointer.button_down(PointerButton::Primary)),
+                drag_anywhere: ui.input(|input| input.modifiers.alt),
+                wheel_delta: pointer_pos.map_or(0.0, |_| {
+                    ui.input(|input| input.raw_scroll_delta.y) / WHEEL_POINTS_PER_STEP
+                }),
+                duplicate: ui.input(|input| input.modifiers.shift),
             },
             targets,
         );
 
+        if !clip_rect.is_positive() {
+            return gizmo_result;
+        }
+
         let draw_data = self.draw();
 
-        ui.painter().with_clip_rect(egui_viewport).add(Mesh {
+        ui.painter().with_clip_rect(clip_rect).add(Mesh {
             indices: draw_data.indices,
             vertices: draw_data
                 .vertices
                 .into_iter()
                 .zip(draw_data.colors)
                 .map(|(pos, [r, g, b, a])| Vertex {
-                    pos: pos.into(),
+                    pos: mapping.to_screen(self.config().viewport_to_display(pos.into()).into()),
                     uv: Pos2::default(),
                     color: Rgba::from_rgba_premultiplied(r, g, b, a).into(),
                 })
@@ -116,3 +167,52 @@ impl GizmoExt for Gizmo {
         gizmo_result
     }
 }
+
+/// Maps positions between egui screen space and the viewport space of the gizmo.
+///
+/// The offset is rounded to physical pixels, so that the gizmo
+/// stays pixel-aligned when, for example, a panel is scrolled.
+///
+/// ```
+/// use egui::{pos2, vec2, Rect};
+/// use transform_gizmo_egui::ViewportMapping;
+///
+/// let viewport = Rect::from_min_size(pos2(100.0, 50.0), vec2(200.0, 100.0));
+/// let mapping = ViewportMapping::new(viewport, vec2(10.3, -20.0), 2.0);
+///
+/// assert_eq!(
+///     mapping.screen_viewport(),
+///     Rect::from_min_size(pos2(110.5, 30.0), vec2(200.0, 100.0))
+/// );
+/// assert_eq!(mapping.to_viewport(pos2(160.5, 80.0)), pos2(150.0, 100.0));
+/// assert_eq!(mapping.to_screen(pos2(150.0, 100.0)), pos2(160.5, 80.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewportMapping {
+    viewport: Rect,
+    offset: Vec2,
+}
+
+impl ViewportMapping {
+    /// Creates a mapping for a viewport, whose space has its origin at `offset` in screen space.
+    pub fn new(viewport: Rect, offset: Vec2, pixels_per_point: f32) -> Self {
+        let offset = (offset * pixels_per_point).round() / pixels_per_point;
+
+        Self { viewport, offset }
+    }
+
+    /// Viewport in screen space.
+    pub fn screen_viewport(&self) -> Rect {
+        self.viewport.translate(self.offset)
+    }
+
+    /// Maps a position from screen space to viewport space.
+    pub fn to_viewport(&self, screen_pos: Pos2) -> Pos2 {
+        screen_pos - self.offset
+    }
+
+    /// Maps a position from viewport space to screen space.
+    pub fn to_screen(&self, viewport_pos: Pos2) -> Pos2 {
+        viewport_pos + self.offset
+    }
+}
@@ -0,0 +1,86 @@
+//! Tests that the gizmo is painted only inside the Ui it is embedded in.
+
+use egui::{pos2, vec2, Context, RawInput, Rect, Shape, Window};
+use transform_gizmo_egui::math::{DMat4, DVec3, Transform};
+use transform_gizmo_egui::prelude::*;
+
+const SCREEN_SIZE: f32 = 800.0;
+
+/// Gizmo whose viewport is the whole screen, with the target in the middle of it.
+fn gizmo() -> Gizmo {
+    Gizmo::new(GizmoConfig {
+        view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y).into(),
+        projection_matrix: DMat4::perspective_rh_gl(std::f64::consts::FRAC_PI_2, 1.0, 0.1, 100.0)
+            .into(),
+        viewport: Rect::from_min_size(pos2(0.0, 0.0), vec2(SCREEN_SIZE, SCREEN_SIZE)).into(),
+        modes: GizmoMode::all(),
+        ..Default::default()
+    })
+}
+
+/// Runs a few frames with the gizmo inside a window partially covering it, and returns
+/// the clip rects of the painted gizmo meshes along with the rect of the window.
+fn run_in_window(nested: bool) -> (Vec<Rect>, Rect) {
+    let ctx = Context::default();
+    let mut gizmo = gizmo();
+    let mut window_rect = Rect::NOTHING;
+    let mut mesh_clip_rects = vec![];
+
+    for _ in 0..3 {
+        let output = ctx.run(
+            RawInput {
+                screen_rect: Some(Rect::from_min_size(
+                    pos2(0.0, 0.0),
+                    vec2(SCREEN_SIZE, SCREEN_SIZE),
+                )),
+                ..Default::default()
+            },
+            |ctx| {
+                let response = Window::new("Gizmo")
+                    .fixed_pos(pos2(350.0, 350.0))
+                    .fixed_size(vec2(100.0, 100.0))
+                    .show(ctx, |ui| {
+                        if nested {
+                            ui.group(|ui| {
+                                ui.label("Nested");
+                                gizmo.interact(ui, &[Transform::default()]);
+                            });
+                        } else {
+                            gizmo.interact(ui, &[Transform::default()]);
+                        }
+                    });
+
+                window_rect = response.map_or(Rect::NOTHING, |response| response.response.rect);
+            },
+        );
+
+        mesh_clip_rects = output
+            .shapes
+            .into_iter()
+            .filter(|clipped| matches!(clipped.shape, Shape::Mesh(_)))
+            .map(|clipped| clipped.clip_rect)
+            .collect();
+    }
+
+    (mesh_clip_rects, window_rect)
+}
+
+#[test]
+fn gizmo_is_clipped_to_window() {
+    let (clip_rects, window_rect) = run_in_window(false);
+
+    assert!(!clip_rects.is_empty());
+    for clip_rect in clip_rects {
+        assert!(window_rect.contains_rect(clip_rect));
+    }
+}
+
+#[test]
+fn gizmo_is_clipped_to_nested_container() {
+    let (clip_rects, window_rect) = run_in_window(true);
+
+    assert!(!clip_rects.is_empty());
+    for clip_rect in clip_rects {
+        assert!(window_rect.contains_rect(clip_rect));
+    }
+}
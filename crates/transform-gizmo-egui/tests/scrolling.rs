@@ -0,0 +1,68 @@
+//! Tests that the pointer maps to the gizmo when it is embedded in a scrolled container.
+
+use egui::{pos2, vec2, CentralPanel, Context, Event, RawInput, Rect, ScrollArea};
+use transform_gizmo_egui::math::{DMat4, DVec3, Transform};
+use transform_gizmo_egui::prelude::*;
+
+const SCREEN_SIZE: f32 = 800.0;
+
+/// Scroll offset that does not fall on a physical pixel
+const SCROLL_OFFSET: f32 = 10.3;
+
+#[test]
+fn pointer_maps_to_handle_in_scrolled_container() {
+    let ctx = Context::default();
+    // Without a viewport, the gizmo falls back to the clip rect of the Ui
+    let mut gizmo = Gizmo::new(GizmoConfig {
+        view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y).into(),
+        projection_matrix: DMat4::perspective_rh_gl(std::f64::consts::FRAC_PI_2, 1.0, 0.1, 100.0)
+            .into(),
+        modes: enum_set!(GizmoMode::TranslateX),
+        ..Default::default()
+    });
+
+    let mut clip_rect = Rect::NOTHING;
+    let mut offset = egui::Vec2::ZERO;
+
+    for _ in 0..3 {
+        // On the arrow of the X axis, which points right from the center of the clip rect
+        let pointer_pos = clip_rect.center() + vec2(50.0, 0.0);
+
+        ctx.run(
+            RawInput {
+                screen_rect: Some(Rect::from_min_size(
+                    pos2(0.0, 0.0),
+                    vec2(SCREEN_SIZE, SCREEN_SIZE),
+                )),
+                events: if clip_rect.is_finite() {
+                    vec![Event::PointerMoved(pointer_pos)]
+                } else {
+                    vec![]
+                },
+                ..Default::default()
+            },
+            |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ScrollArea::vertical()
+                        .vertical_scroll_offset(SCROLL_OFFSET)
+                        .show(ui, |ui| {
+                            clip_rect = ui.clip_rect();
+                            offset = ui.cursor().min.to_vec2();
+
+                            gizmo.interact_with_offset(ui, &[Transform::default()], offset);
+                            ui.allocate_space(vec2(ui.available_width(), 2.0 * SCREEN_SIZE));
+                        });
+                });
+            },
+        );
+    }
+
+    // The viewport is displayed exactly over the clip rect, so the gizmo is drawn
+    // at the same place where the pointer is mapped to.
+    let mapping = ViewportMapping::new(gizmo.config().viewport.into(), offset, 1.0);
+    let screen_viewport = mapping.screen_viewport();
+    assert!((screen_viewport.min - clip_rect.min).length() < 1e-3);
+    assert!((screen_viewport.max - clip_rect.max).length() < 1e-3);
+
+    assert_eq!(gizmo.focused_mode(), Some(GizmoMode::TranslateX));
+}
@@ -0,0 +1,391 @@
+//! Provides a 3D transformation gizmo for the Iced library.
+//!
+//! transform-gizmo-iced provides a feature-rich and configurable gizmo
+//! that can be used for 3d transformations (translation, rotation, scale)
+//! inside an iced application.
+//!
+//! # Usage
+//!
+//! Wrap the view that the gizmo is drawn over, such as a [`Shader`](iced::widget::Shader)
+//! rendering the scene, in a [`GizmoOverlay`]. The overlay owns the `Gizmo`, interacts with it
+//! using the mouse events over the wrapped view and draws it on top of the view.
+//!
+//! The configuration is given every time the view is built, for example when the camera moves.
+//! The gizmo is interacted with in the same order as the given transforms. When the gizmo is
+//! interacted with, the message returned by `on_change` is published with the modified transforms.
+//!
+//! ```ignore
+//! fn view(&self) -> Element<'_, Message> {
+//!     GizmoOverlay::new(
+//!         self.scene.view(),
+//!         GizmoConfig {
+//!             view_matrix: self.view_matrix.into(),
+//!             projection_matrix: self.projection_matrix.into(),
+//!             modes: GizmoMode::all(),
+//!             orientation: GizmoOrientation::Local,
+//!             ..Default::default()
+//!         },
+//!         &self.transforms,
+//!         |_result, new_transforms| Message::TransformsChanged(new_transforms),
+//!     )
+//!     .into()
+//! }
+//! ```
+//!
+//! Custom widgets can instead store a `Gizmo` and a [`GizmoInput`] in their state, and use
+//! [`GizmoExt::interact`] and [`GizmoExt::mesh`] directly.
+
+use iced::advanced::graphics::color;
+use iced::advanced::graphics::mesh::{self, Mesh, SolidVertex2D};
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Renderer as _};
+use iced::advanced::widget::{tree, Tree, Widget};
+use iced::advanced::{Clipboard, Shell};
+use iced::event::{self, Event};
+use iced::keyboard;
+use iced::mouse::{self, Cursor};
+use iced::widget::canvas;
+use iced::{Color, Element, Length, Rectangle, Size, Vector};
+
+use transform_gizmo::math::{Pos2, Rect, Transform};
+pub use transform_gizmo::*;
+pub mod prelude;
+
+/// Scroll distance in pixels counted as one mouse wheel step, for touchpads and smooth scrolling
+const WHEEL_PIXELS_PER_STEP: f32 = 100.0;
+
+/// Mouse input state of a gizmo, collected from iced canvas events.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GizmoInput {
+    cursor_pos: (f32, f32),
+    drag_started: bool,
+    dragging: bool,
+    drag_anywhere: bool,
+    wheel_delta: f32,
+    duplicate: bool,
+}
+
+impl GizmoInput {
+    /// Update the input state from an event received by a canvas program.
+    ///
+    /// Cursor position is stored relative to the canvas bounds.
+    /// While Alt is held, dragging can be started anywhere in the canvas,
+    /// if [`GizmoConfig::drag_anywhere_mode`] is set. Drags started while Shift
+    /// is held request duplication of the targets, see [`Gizmo::rebase_targets`].
+    pub fn handle_event(&mut self, event: &canvas::Event, bounds: Rectangle, cursor: Cursor) {
+        match event {
+            canvas::Event::Mouse(event) => self.handle_mouse(event, bounds, cursor),
+            canvas::Event::Keyboard(event) => self.handle_keyboard(event),
+            canvas::Event::Touch(_) => self.update_cursor(bounds, cursor),
+        }
+    }
+
+    fn update_cursor(&mut self, bounds: Rectangle, cursor: Cursor) {
+        if let Some(position) = cursor.position() {
+            self.cursor_pos = (position.x - bounds.x, position.y - bounds.y);
+        }
+    }
+
+    fn handle_mouse(&mut self, event: &mouse::Event, bounds: Rectangle, cursor: Cursor) {
+        self.update_cursor(bounds, cursor);
+
+        match event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) if cursor.is_over(bounds) => {
+                self.drag_started = true;
+                self.dragging = true;
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                self.dragging = false;
+            }
+            mouse::Event::WheelScrolled { delta } if cursor.is_over(bounds) => {
+                self.wheel_delta += match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / WHEEL_PIXELS_PER_STEP,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_keyboard(&mut self, event: &keyboard::Event) {
+        if let keyboard::Event::ModifiersChanged(modifiers) = event {
+            self.drag_anywhere = modifiers.alt();
+            self.duplicate = modifiers.shift();
+        }
+    }
+
+    /// Interaction to give to the gizmo. A started drag and
+    /// the mouse wheel steps are reported only once.
+    pub fn interaction(&mut self) -> GizmoInteraction {
+        GizmoInteraction {
+            cursor_pos: self.cursor_pos,
+            drag_started: std::mem::take(&mut self.drag_started),
+            dragging: self.dragging,
+            drag_anywhere: self.drag_anywhere,
+            wheel_delta: std::mem::take(&mut self.wheel_delta),
+            duplicate: self.duplicate,
+        }
+    }
+}
+
+/// Extension trait for interacting with a [`Gizmo`] and drawing it in iced.
+///
+/// Used by [`GizmoOverlay`], and by custom widgets that manage the gizmo themselves.
+pub trait GizmoExt {
+    /// Interact with the gizmo inside a widget with given bounds.
+    ///
+    /// Returns result of the gizmo interaction.
+    fn interact(
+        &mut self,
+        input: &mut GizmoInput,
+        bounds: Rectangle,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)>;
+
+    /// Triangle mesh of the gizmo with per-vertex colors, relative to the widget bounds.
+    ///
+    /// Returns `None` when there is nothing to draw. Draw the mesh with
+    /// [`mesh::Renderer::draw_mesh`], translated to the widget bounds.
+    fn mesh(&self, size: Size) -> Option<Mesh>;
+}
+
+impl GizmoExt for Gizmo {
+    fn interact(
+        &mut self,
+        input: &mut GizmoInput,
+        bounds: Rectangle,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        // The gizmo is drawn relative to the widget bounds
+        self.set_viewport(Rect::from_min_max(
+            Pos2::ZERO,
+            Pos2::new(bounds.width, bounds.height),
+        ));
+
+        self.update(input.interaction(), targets)
+    }
+
+    fn mesh(&self, size: Size) -> Option<Mesh> {
+        let mut draw_data = self.draw();
+        if draw_data.indices.is_empty() {
+            return None;
+        }
+        draw_data.convert_colors(ColorFormat::SrgbUnmultiplied);
+
+        let vertices = draw_data
+            .vertices
+            .iter()
+            .zip(&draw_data.colors)
+            .map(|(&position, &[r, g, b, a])| SolidVertex2D {
+                position,
+                color: color::pack(Color::from_rgba(r, g, b, a)),
+            })
+            .collect();
+
+        Some(Mesh::Solid {
+            buffers: mesh::Indexed {
+                vertices,
+                indices: draw_data.indices,
+            },
+            size,
+        })
+    }
+}
+
+/// Widget that draws a gizmo on top of its content and interacts with it
+/// using the mouse events over the content.
+///
+/// Events are passed to the content when the gizmo is not being dragged,
+/// so the content can still be used for camera controls, for example.
+pub struct GizmoOverlay<'a, Message, Theme = iced::Theme> {
+    content: Element<'a, Message, Theme, iced::Renderer>,
+    config: GizmoConfig,
+    targets: &'a [Transform],
+    on_change: Box<dyn Fn(GizmoResult, Vec<Transform>) -> Message + 'a>,
+}
+
+impl<'a, Message, Theme> GizmoOverlay<'a, Message, Theme> {
+    /// Creates a gizmo overlay for the content.
+    ///
+    /// `on_change` is called with the result of the interaction and the modified
+    /// targets, and the returned message is published.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Theme, iced::Renderer>>,
+        config: GizmoConfig,
+        targets: &'a [Transform],
+        on_change: impl Fn(GizmoResult, Vec<Transform>) -> Message + 'a,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            config,
+            targets,
+            on_change: Box::new(on_change),
+        }
+    }
+}
+
+/// Gizmo and its input, kept in the widget tree between views.
+#[derive(Default)]
+struct State {
+    gizmo: Gizmo,
+    input: GizmoInput,
+}
+
+impl<Message, Theme> Widget<Message, Theme, iced::Renderer> for GizmoOverlay<'_, Message, Theme> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+
+        let is_input = match &event {
+            Event::Mouse(mouse_event) => {
+                state.input.handle_mouse(mouse_event, bounds, cursor);
+                true
+            }
+            Event::Keyboard(keyboard_event) => {
+                state.input.handle_keyboard(keyboard_event);
+                true
+            }
+            _ => false,
+        };
+
+        if is_input {
+            // Keep the viewport set by `interact`, so that caches are not invalidated needlessly
+            let config = GizmoConfig {
+                viewport: state.gizmo.config().viewport,
+                ..self.config
+            };
+            if *state.gizmo.config() != config {
+                state.gizmo.update_config(config);
+            }
+
+            if let Some((result, transforms)) =
+                state.gizmo.interact(&mut state.input, bounds, self.targets)
+            {
+                shell.publish((self.on_change)(result, transforms));
+                return event::Status::Captured;
+            }
+
+            if state.gizmo.active_mode().is_some() {
+                return event::Status::Captured;
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State>();
+
+        if let Some(mesh) = state.gizmo.mesh(bounds.size()) {
+            renderer.with_layer(bounds, |renderer| {
+                renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
+                    mesh::Renderer::draw_mesh(renderer, mesh);
+                });
+            });
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+
+        if state.gizmo.active_mode().is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message, Theme> From<GizmoOverlay<'a, Message, Theme>>
+    for Element<'a, Message, Theme, iced::Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+{
+    fn from(overlay: GizmoOverlay<'a, Message, Theme>) -> Self {
+        Self::new(overlay)
+    }
+}
@@ -0,0 +1,229 @@
+//! Provides a 3D transformation gizmo for the three-d renderer.
+//!
+//! transform-gizmo-three-d provides a feature-rich and configurable gizmo
+//! that can be used for 3d transformations (translation, rotation, scale).
+//!
+//! # Usage
+//!
+//! Create a new `Gizmo` and a [`GizmoInput`] instance once.
+//!
+//! ```
+//! use transform_gizmo_three_d::prelude::*;
+//!
+//! let gizmo = Gizmo::default();
+//! let input = GizmoInput::default();
+//! ```
+//!
+//! Update the gizmo configuration as needed, for example, when the camera moves.
+//!
+//! ```ignore
+//! gizmo.update_config(GizmoConfig {
+//!     view_matrix: view_matrix.into(),
+//!     projection_matrix: projection_matrix.into(),
+//!     modes: GizmoMode::all(),
+//!     orientation: GizmoOrientation::Local,
+//!     ..Default::default()
+//! });
+//! ```
+//!
+//! Each frame, feed the frame events to the [`GizmoInput`] and interact with the gizmo.
+//! The result is [`Some`] if the gizmo was successfully interacted with this frame.
+//! In the result you can find the modified transforms, in the same order as was given to the function
+//! as arguments.
+//!
+//! ```ignore
+//! input.handle_events(&frame_input.events, frame_input.viewport);
+//!
+//! if let Some((_, new_transforms)) =
+//!     gizmo.interact(&mut input, &frame_input, &[transform])
+//! {
+//!     transform = new_transforms[0];
+//! }
+//! ```
+//!
+//! Finally, render the gizmo on top of the scene.
+//!
+//! ```ignore
+//! gizmo.render(&context, &frame_input.screen(), frame_input.viewport);
+//! ```
+//!
+//!
+use three_d::{
+    vec3, Blend, Camera, ColorMaterial, Context, CpuMesh, DepthTest, Event, FrameInput, Gm,
+    Indices, Mesh, MouseButton, Positions, RenderStates, RenderTarget, Srgba, Viewport, WriteMask,
+};
+
+use transform_gizmo::math::{Pos2, Rect, Transform};
+pub use transform_gizmo::*;
+pub mod prelude;
+
+/// Mouse input state of a gizmo, collected from three-d events.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GizmoInput {
+    cursor_pos: (f32, f32),
+    drag_started: bool,
+    dragging: bool,
+    drag_anywhere: bool,
+    wheel_delta: f32,
+    duplicate: bool,
+}
+
+impl GizmoInput {
+    /// Update the input state from the events of a frame.
+    ///
+    /// Cursor position is stored in physical pixels, relative to the
+    /// top left corner of the viewport. While Alt is held, dragging can be
+    /// started anywhere in the viewport, if [`GizmoConfig::drag_anywhere_mode`] is set.
+    /// Drags started while Shift is held request duplication of the targets,
+    /// see [`Gizmo::rebase_targets`].
+    pub fn handle_events(&mut self, events: &[Event], viewport: Viewport) {
+        for event in events {
+            match event {
+                Event::MousePress {
+                    button: MouseButton::Left,
+                    position,
+                    handled: false,
+                    ..
+                } => {
+                    self.cursor_pos = viewport_position(viewport, position.x, position.y);
+                    self.drag_started = true;
+                    self.dragging = true;
+                }
+                Event::MouseRelease {
+                    button: MouseButton::Left,
+                    position,
+                    ..
+                } => {
+                    self.cursor_pos = viewport_position(viewport, position.x, position.y);
+                    self.dragging = false;
+                }
+                Event::MouseMotion { position, .. } => {
+                    self.cursor_pos = viewport_position(viewport, position.x, position.y);
+                }
+                Event::MouseWheel {
+                    delta,
+                    handled: false,
+                    ..
+                } if delta.1 != 0.0 => {
+                    // Each wheel event counts as a single step
+                    self.wheel_delta += delta.1.signum();
+                }
+                Event::ModifiersChange { modifiers } => {
+                    self.drag_anywhere = modifiers.alt;
+                    self.duplicate = modifiers.shift;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Interaction to give to the gizmo. A started drag and
+    /// the mouse wheel steps are reported only once.
+    pub fn interaction(&mut self) -> GizmoInteraction {
+        GizmoInteraction {
+            cursor_pos: self.cursor_pos,
+            drag_started: std::mem::take(&mut self.drag_started),
+            dragging: self.dragging,
+            drag_anywhere: self.drag_anywhere,
+            wheel_delta: std::mem::take(&mut self.wheel_delta),
+            duplicate: self.duplicate,
+        }
+    }
+}
+
+pub trait GizmoExt {
+    /// Interact with the gizmo in the viewport of the given frame.
+    ///
+    /// Returns result of the gizmo interaction.
+    fn interact(
+        &mut self,
+        input: &mut GizmoInput,
+        frame_input: &FrameInput,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)>;
+
+    /// Create a renderable object of the gizmo, to be rendered with
+    /// a 2D camera of the given viewport.
+    fn object(&self, context: &Context, viewport: Viewport) -> Gm<Mesh, ColorMaterial>;
+
+    /// Render the gizmo to a render target, on top of anything rendered before.
+    fn render(&self, context: &Context, target: &RenderTarget<'_>, viewport: Viewport);
+}
+
+impl GizmoExt for Gizmo {
+    fn interact(
+        &mut self,
+        input: &mut GizmoInput,
+        frame_input: &FrameInput,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        let viewport = frame_input.viewport;
+
+        self.update_config(GizmoConfig {
+            viewport: Rect::from_min_max(
+                Pos2::ZERO,
+                Pos2::new(viewport.width as f32, viewport.height as f32),
+            ),
+            pixels_per_point: frame_input.device_pixel_ratio,
+            ..*self.config()
+        });
+
+        self.update(input.interaction(), targets)
+    }
+
+    fn object(&self, context: &Context, viewport: Viewport) -> Gm<Mesh, ColorMaterial> {
+        let mut draw_data = self.draw();
+        draw_data.convert_colors(ColorFormat::SrgbUnmultiplied);
+
+        // three-d has the origin of the viewport in the bottom left corner
+        let height = viewport.height as f32;
+
+        let cpu_mesh = CpuMesh {
+            positions: Positions::F32(
+                draw_data
+                    .vertices
+                    .into_iter()
+                    .map(|[x, y]| vec3(x, height - y, 0.0))
+                    .collect(),
+            ),
+            colors: Some(draw_data.colors.into_iter().map(to_srgba).collect()),
+            indices: Indices::U32(draw_data.indices),
+            ..Default::default()
+        };
+
+        let material = ColorMaterial {
+            color: Srgba::WHITE,
+            is_transparent: true,
+            render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        Gm::new(Mesh::new(context, &cpu_mesh), material)
+    }
+
+    fn render(&self, context: &Context, target: &RenderTarget<'_>, viewport: Viewport) {
+        let camera = Camera::new_2d(viewport);
+        target.render(&camera, &self.object(context, viewport), &[]);
+    }
+}
+
+/// Converts a position in three-d viewport coordinates to
+/// a position relative to the top left corner of the viewport.
+fn viewport_position(viewport: Viewport, x: f32, y: f32) -> (f32, f32) {
+    (
+        x - viewport.x as f32,
+        (viewport.y as f32 + viewport.height as f32) - y,
+    )
+}
+
+/// Converts an unmultiplied sRGB color of the gizmo to a three-d color.
+fn to_srgba(color: [f32; 4]) -> Srgba {
+    let [r, g, b, a] = color.map(|component| (component * 255.0).round() as u8);
+
+    Srgba::new(r, g, b, a)
+}
@@ -0,0 +1,3 @@
+pub use transform_gizmo::prelude::*;
+
+pub use crate::{GizmoExt, GizmoInput};
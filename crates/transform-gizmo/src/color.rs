@@ -0,0 +1,160 @@
+//! Colors used in the API of the gizmo.
+//!
+//! With the `epaint` feature, [`Color32`] converts to and from `ecolor::Color32`.
+
+/// sRGB color with premultiplied alpha, 8 bits per channel
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color32([u8; 4]);
+
+impl Color32 {
+    pub const TRANSPARENT: Self = Self::from_rgba_premultiplied(0, 0, 0, 0);
+    pub const BLACK: Self = Self::from_rgb(0, 0, 0);
+    pub const WHITE: Self = Self::from_rgb(255, 255, 255);
+    pub const RED: Self = Self::from_rgb(255, 0, 0);
+    pub const GREEN: Self = Self::from_rgb(0, 255, 0);
+    pub const BLUE: Self = Self::from_rgb(0, 0, 255);
+    pub const YELLOW: Self = Self::from_rgb(255, 255, 0);
+
+    /// Opaque color
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self([r, g, b, 255])
+    }
+
+    /// Color whose color channels are already multiplied by its alpha
+    pub const fn from_rgba_premultiplied(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self([r, g, b, a])
+    }
+
+    /// Color whose color channels are not yet multiplied by its alpha
+    pub fn from_rgba_unmultiplied(r: u8, g: u8, b: u8, a: u8) -> Self {
+        if a == 255 {
+            return Self::from_rgb(r, g, b);
+        }
+
+        let alpha = linear_from_u8(a);
+        let [r, g, b] =
+            [r, g, b].map(|channel| gamma_u8_from_linear(linear_from_gamma_u8(channel) * alpha));
+
+        Self([r, g, b, a])
+    }
+
+    pub const fn r(&self) -> u8 {
+        self.0[0]
+    }
+
+    pub const fn g(&self) -> u8 {
+        self.0[1]
+    }
+
+    pub const fn b(&self) -> u8 {
+        self.0[2]
+    }
+
+    pub const fn a(&self) -> u8 {
+        self.0[3]
+    }
+
+    /// Premultiplied sRGB channels
+    pub const fn to_array(&self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Linear RGBA channels with premultiplied alpha, in the range of 0 to 1
+    pub fn to_linear(&self) -> [f32; 4] {
+        let [r, g, b, a] = self.0;
+        [
+            linear_from_gamma_u8(r),
+            linear_from_gamma_u8(g),
+            linear_from_gamma_u8(b),
+            linear_from_u8(a),
+        ]
+    }
+
+    /// Color from linear RGBA channels with premultiplied alpha, in the range of 0 to 1
+    pub fn from_linear([r, g, b, a]: [f32; 4]) -> Self {
+        Self([
+            gamma_u8_from_linear(r),
+            gamma_u8_from_linear(g),
+            gamma_u8_from_linear(b),
+            u8_from_linear(a),
+        ])
+    }
+
+    /// Multiplies all channels, including alpha, in sRGB space.
+    /// Makes the color more transparent when `factor` is below 1.
+    pub fn gamma_multiply(self, factor: f32) -> Self {
+        Self(
+            self.0
+                .map(|channel| round_to_u8(f32::from(channel) * factor)),
+        )
+    }
+
+    /// Multiplies all channels, including alpha, in linear space.
+    /// Makes the color more transparent when `factor` is below 1.
+    pub fn linear_multiply(self, factor: f32) -> Self {
+        Self::from_linear(self.to_linear().map(|channel| channel * factor))
+    }
+}
+
+/// Converts a gamma-encoded sRGB channel in the range of 0 to 1 to linear space
+pub(crate) fn linear_from_gamma(gamma: f32) -> f32 {
+    if gamma < 0.0 {
+        0.0
+    } else if gamma <= 0.04045 {
+        gamma / 12.92
+    } else if gamma < 1.0 {
+        ((gamma + 0.055) / 1.055).powf(2.4)
+    } else {
+        1.0
+    }
+}
+
+/// Converts a linear channel in the range of 0 to 1 to gamma-encoded sRGB
+pub(crate) fn gamma_from_linear(linear: f32) -> f32 {
+    if linear < 0.0 {
+        0.0
+    } else if linear <= 0.003_130_8 {
+        12.92 * linear
+    } else if linear < 1.0 {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    } else {
+        1.0
+    }
+}
+
+fn linear_from_gamma_u8(channel: u8) -> f32 {
+    linear_from_gamma(linear_from_u8(channel))
+}
+
+fn gamma_u8_from_linear(linear: f32) -> u8 {
+    u8_from_linear(gamma_from_linear(linear))
+}
+
+fn linear_from_u8(channel: u8) -> f32 {
+    f32::from(channel) / 255.0
+}
+
+fn u8_from_linear(linear: f32) -> u8 {
+    round_to_u8(linear * 255.0)
+}
+
+fn round_to_u8(value: f32) -> u8 {
+    // Float to integer casts saturate, so values outside of 0 to 255 are clamped
+    (value + 0.5) as u8
+}
+
+#[cfg(feature = "epaint")]
+impl From<ecolor::Color32> for Color32 {
+    fn from(color: ecolor::Color32) -> Self {
+        Self(color.to_array())
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<Color32> for ecolor::Color32 {
+    fn from(color: Color32) -> Self {
+        let [r, g, b, a] = color.to_array();
+        Self::from_rgba_premultiplied(r, g, b, a)
+    }
+}
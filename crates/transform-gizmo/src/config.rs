@@ -1,13 +1,18 @@
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, RangeInclusive};
 
-pub use ecolor::Color32;
+pub use crate::color::Color32;
 
-use emath::Rect;
 use enumset::{enum_set, EnumSet, EnumSetType};
 
 use crate::math::{
-    screen_to_world, world_to_screen, DMat4, DQuat, DVec3, DVec4, Transform, Vec4Swizzles,
+    float_to_f32, mat4_from_mint, quat_from_mint, quat_to_mint, screen_to_world, vec3_from_mint,
+    vec3_to_mint, world_to_screen, Aabb, DMat3, DMat4, DVec3, DVec4, FMat4, FQuat, FVec3, Float,
+    Pos2, Rect, Transform, Vec2, Vec4Swizzles,
 };
+use crate::rect::remap_pos;
+
+/// Distance from the edges of the handles within which they can still be picked, in screen pixels
+const PICK_TOLERANCE: f32 = 5.0;
 
 /// The default snapping distance for rotation in radians
 pub const DEFAULT_SNAP_ANGLE: f32 = std::f32::consts::PI / 32.0;
@@ -15,19 +20,41 @@ pub const DEFAULT_SNAP_ANGLE: f32 = std::f32::consts::PI / 32.0;
 pub const DEFAULT_SNAP_DISTANCE: f32 = 0.1;
 /// The default snapping distance for scale
 pub const DEFAULT_SNAP_SCALE: f32 = 0.1;
+/// The default margin for keeping the focus on the focused subgizmo
+pub const DEFAULT_FOCUS_MARGIN: f32 = 0.1;
 
 /// Configuration of a gizmo.
 ///
 /// Defines how the gizmo is drawn to the screen and
 /// how it can be interacted with.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoConfig {
     /// View matrix for the gizmo, aligning it with the camera's viewpoint.
+    ///
+    /// Stored as rows, so each of `x`, `y`, `z` and `w` is a row of the matrix. Matrices of
+    /// math libraries are converted correctly with `.into()`, but raw arrays are easily
+    /// transposed by accident. Prefer [`GizmoConfig::set_view_matrix`] and its variants.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub view_matrix: mint::RowMatrix4<f64>,
     /// Projection matrix for the gizmo, determining how it is projected onto the screen.
+    ///
+    /// Stored as rows, like [`GizmoConfig::view_matrix`].
+    /// Prefer [`GizmoConfig::set_projection_matrix`] and its variants.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub projection_matrix: mint::RowMatrix4<f64>,
     /// Screen area where the gizmo is displayed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub viewport: Rect,
+    /// Screen area where the viewport is displayed, if different from [`GizmoConfig::viewport`].
+    ///
+    /// Useful when the view is rendered to a texture, which is then displayed at a
+    /// different position or size, for example as an image in the UI. Cursor positions
+    /// are then given in screen coordinates, and mapped from this area to the viewport.
+    /// See [`GizmoConfig::display_to_viewport`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub display_rect: Option<Rect>,
     /// The gizmo's operation modes.
     pub modes: EnumSet<GizmoMode>,
     /// If set, this mode is forced active and other modes are disabled
@@ -36,14 +63,91 @@ pub struct GizmoConfig {
     pub orientation: GizmoOrientation,
     /// Pivot point for transformations
     pub pivot_point: TransformPivotPoint,
+    /// Offset of the gizmo from the median point of the targets.
+    /// Allows placing the gizmo at, for example, the bottom of a mesh instead of its origin.
+    /// With [`TransformPivotPoint::MedianPoint`], rotations are centered around the offset gizmo.
+    pub anchor_offset: mint::Vector3<f64>,
+    /// Whether [`GizmoConfig::anchor_offset`] is given in world space or
+    /// in the local space of the last target.
+    pub anchor_offset_orientation: GizmoOrientation,
+    /// Determines how scaling behaves when global orientation is used.
+    pub global_scale_policy: GlobalScalePolicy,
+    /// Determines how cursor movement is converted to scale.
+    pub scale_interaction: ScaleInteraction,
+    /// Determines how axes with zero scale are scaled.
+    pub zero_scale_policy: ZeroScalePolicy,
+    /// Determines which axes the targets are rotated around when
+    /// [`GizmoOrientation::Local`] is used with multiple targets.
+    pub local_rotation_axes: LocalRotationAxes,
+    /// If set, dragging can be started anywhere in the viewport while
+    /// [`GizmoInteraction::drag_anywhere`](crate::GizmoInteraction::drag_anywhere) is set.
+    /// The most recently active subgizmo is used for such drags, or the subgizmo
+    /// of this mode if none has been active yet.
+    pub drag_anywhere_mode: Option<GizmoMode>,
+    /// If set, translation arrows along the axes of this ground plane are replaced
+    /// with compass arrows lying on the plane, pointing in both directions of each axis.
+    /// Useful for architectural and terrain editors.
+    pub ground_plane: Option<GroundPlane>,
+    /// If `true`, rotation handles are drawn as full circles and can be picked
+    /// from anywhere along the circle, including the half facing away from the camera.
+    /// Otherwise only the half facing the camera is shown, unless viewed directly from the front.
+    pub full_circle_rotation_handles: bool,
+    /// If `true`, [`GizmoMode::ScaleUniform`] also adds small handles at the outer
+    /// corners of the plane handles, which are easier to hit than the scale circle.
+    pub uniform_scale_corners: bool,
+    /// Mirrors the gizmo horizontally in screen space. Plane handles are placed
+    /// on the opposite side of the gizmo, and view plane translation follows
+    /// the mirrored cursor movement. Useful for right-to-left UI layouts and
+    /// mirrored viewports.
+    pub mirrored: bool,
+    /// Optional world space limits for the gizmo position along the X, Y and Z axes.
+    /// Translation stops when a limit is reached.
+    pub translation_limits: [Option<TransformLimit>; 3],
+    /// Optional limits for the rotation angle around the X, Y and Z axes, in radians.
+    /// The angle is measured from the identity rotation of the gizmo.
+    /// Rotation stops when a limit is reached.
+    pub rotation_limits: [Option<TransformLimit>; 3],
+    /// Relative margin by which another part of the gizmo must be a better
+    /// pick than the currently focused part to take the focus from it.
+    /// Prevents the focus from flickering when the cursor is near the
+    /// boundary of two parts. Zero disables this.
+    pub focus_margin: f32,
+    /// Determines whether transformations are applied while dragging
+    /// or only once the drag ends.
+    pub apply_mode: ApplyMode,
     /// Toggles snapping to predefined increments during transformations for precision.
     pub snapping: bool,
+    /// Whether scrolling the mouse wheel over a handle transforms the targets by one
+    /// snapping increment per step, see [`GizmoInteraction::wheel_delta`](crate::GizmoInteraction::wheel_delta).
+    pub wheel_interaction: bool,
+    /// Determines how the snapping distance of translations is chosen.
+    pub snap_mode: SnapMode,
+    /// Determines where the snapping increments of rotations are counted from.
+    pub rotation_snap_origin: SnapOrigin,
+    /// Determines where the snapping increments of translations are counted from.
+    pub translation_snap_origin: SnapOrigin,
+    /// World space origin of the lattice that translations snap to with [`SnapOrigin::Absolute`].
+    /// Useful for anchoring the snapping to a scene-specific grid, such as the grid offset of a level.
+    pub snap_origin: mint::Vector3<f64>,
     /// Angle increment for snapping rotations, in radians.
     pub snap_angle: f32,
     /// Distance increment for snapping translations.
     pub snap_distance: f32,
+    /// Distance increments for snapping translations along the X, Y and Z axes of the
+    /// gizmo orientation, overriding [`GizmoConfig::snap_distance`] and [`SnapMode`] for
+    /// the axes that are set. Useful for different grid steps for horizontal and vertical movement.
+    ///
+    /// Plane translations snap by the increments of both axes of the plane.
+    pub snap_distance_per_axis: [Option<f32>; 3],
     /// Scale increment for snapping scalings.
     pub snap_scale: f32,
+    /// If set, rotations snap to orientations where the axes of the gizmo are aligned with
+    /// the world axes or the axes of the references given with
+    /// [`Gizmo::set_alignment_references`](crate::Gizmo::set_alignment_references),
+    /// whenever within this angle of them, in radians. Independent of [`GizmoConfig::snapping`].
+    ///
+    /// Rotations around a single axis only snap to orientations reachable around that axis.
+    pub alignment_snap_angle: Option<f32>,
     /// Visual settings for the gizmo, affecting appearance and visibility.
     pub visuals: GizmoVisuals,
     /// Ratio of window's physical size to logical size.
@@ -56,13 +160,36 @@ impl Default for GizmoConfig {
             view_matrix: DMat4::IDENTITY.into(),
             projection_matrix: DMat4::IDENTITY.into(),
             viewport: Rect::NOTHING,
+            display_rect: None,
             modes: GizmoMode::all(),
             mode_override: None,
             orientation: GizmoOrientation::default(),
             pivot_point: TransformPivotPoint::default(),
+            anchor_offset: DVec3::ZERO.into(),
+            anchor_offset_orientation: GizmoOrientation::default(),
+            global_scale_policy: GlobalScalePolicy::default(),
+            scale_interaction: ScaleInteraction::default(),
+            zero_scale_policy: ZeroScalePolicy::default(),
+            local_rotation_axes: LocalRotationAxes::default(),
+            drag_anywhere_mode: None,
+            ground_plane: None,
+            full_circle_rotation_handles: false,
+            uniform_scale_corners: false,
+            mirrored: false,
+            translation_limits: [None; 3],
+            rotation_limits: [None; 3],
+            focus_margin: DEFAULT_FOCUS_MARGIN,
+            apply_mode: ApplyMode::default(),
             snapping: false,
+            wheel_interaction: false,
+            snap_mode: SnapMode::default(),
+            rotation_snap_origin: SnapOrigin::default(),
+            translation_snap_origin: SnapOrigin::default(),
+            snap_origin: DVec3::ZERO.into(),
             snap_angle: DEFAULT_SNAP_ANGLE,
+            alignment_snap_angle: None,
             snap_distance: DEFAULT_SNAP_DISTANCE,
+            snap_distance_per_axis: [None; 3],
             snap_scale: DEFAULT_SNAP_SCALE,
             visuals: GizmoVisuals::default(),
             pixels_per_point: 1.0,
@@ -71,19 +198,197 @@ impl Default for GizmoConfig {
 }
 
 impl GizmoConfig {
+    /// Sets the view matrix from either a [`mint::RowMatrix4`] or a [`mint::ColumnMatrix4`],
+    /// or any matrix type convertible to them, such as `glam::DMat4`.
+    ///
+    /// In debug builds, panics if the matrix is not a rigid transform, which usually
+    /// means that it was transposed.
+    ///
+    /// ```
+    /// use transform_gizmo::math::{DMat4, DVec3};
+    /// use transform_gizmo::GizmoConfig;
+    ///
+    /// let view = DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y);
+    ///
+    /// let mut config = GizmoConfig::default();
+    /// config.set_view_matrix(view);
+    /// assert_eq!(config.view_matrix, view.into());
+    ///
+    /// config.set_view_matrix_col_major(view.to_cols_array_2d());
+    /// assert_eq!(config.view_matrix, view.into());
+    /// ```
+    pub fn set_view_matrix(&mut self, view_matrix: impl Into<mint::RowMatrix4<f64>>) {
+        self.view_matrix = view_matrix.into();
+        debug_assert_view_matrix(self.view_matrix);
+    }
+
+    /// Sets the view matrix from an array of columns, as used by OpenGL and most math libraries.
+    /// See [`GizmoConfig::set_view_matrix`].
+    pub fn set_view_matrix_col_major(&mut self, columns: [[f64; 4]; 4]) {
+        self.set_view_matrix(mint::ColumnMatrix4::from(columns));
+    }
+
+    /// Sets the view matrix from an array of rows, as used by DirectX.
+    /// See [`GizmoConfig::set_view_matrix`].
+    pub fn set_view_matrix_row_major(&mut self, rows: [[f64; 4]; 4]) {
+        self.set_view_matrix(mint::RowMatrix4::from(rows));
+    }
+
+    /// Sets the projection matrix from either a [`mint::RowMatrix4`] or a [`mint::ColumnMatrix4`],
+    /// or any matrix type convertible to them, such as `glam::DMat4`.
+    ///
+    /// In debug builds, panics if the matrix is neither a perspective nor an orthographic
+    /// projection, which usually means that it was transposed.
+    pub fn set_projection_matrix(&mut self, projection_matrix: impl Into<mint::RowMatrix4<f64>>) {
+        self.projection_matrix = projection_matrix.into();
+        debug_assert_projection_matrix(self.projection_matrix);
+    }
+
+    /// Sets the projection matrix from an array of columns, as used by OpenGL and most math libraries.
+    /// See [`GizmoConfig::set_projection_matrix`].
+    pub fn set_projection_matrix_col_major(&mut self, columns: [[f64; 4]; 4]) {
+        self.set_projection_matrix(mint::ColumnMatrix4::from(columns));
+    }
+
+    /// Sets the projection matrix from an array of rows, as used by DirectX.
+    /// See [`GizmoConfig::set_projection_matrix`].
+    pub fn set_projection_matrix_row_major(&mut self, rows: [[f64; 4]; 4]) {
+        self.set_projection_matrix(mint::RowMatrix4::from(rows));
+    }
+
+    /// Projects a world space position to screen coordinates, using the
+    /// view and projection matrices and the viewport of the config.
+    ///
+    /// Returns `None` if the position is behind the camera.
+    pub fn world_to_screen(&self, world_pos: mint::Vector3<f64>) -> Option<Pos2> {
+        let view_projection =
+            mat4_from_mint(self.projection_matrix) * mat4_from_mint(self.view_matrix);
+
+        world_to_screen(self.viewport, view_projection, vec3_from_mint(world_pos))
+    }
+
+    /// Maps a screen position inside [`GizmoConfig::display_rect`] to the viewport.
+    ///
+    /// Returns the position as is if there is no display rect.
+    ///
+    /// ```
+    /// use transform_gizmo::math::{Pos2, Rect, Vec2};
+    /// use transform_gizmo::GizmoConfig;
+    ///
+    /// // 1920x1080 render target displayed at half size in the UI
+    /// let config = GizmoConfig {
+    ///     viewport: Rect::from_min_size(Pos2::ZERO, Vec2::new(1920.0, 1080.0)),
+    ///     display_rect: Some(Rect::from_min_size(
+    ///         Pos2::new(100.0, 50.0),
+    ///         Vec2::new(960.0, 540.0),
+    ///     )),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// // Center of the displayed image is the center of the viewport
+    /// let viewport_pos = config.display_to_viewport(Pos2::new(580.0, 320.0));
+    /// assert_eq!(viewport_pos, Pos2::new(960.0, 540.0));
+    /// assert_eq!(config.viewport_to_display(viewport_pos), Pos2::new(580.0, 320.0));
+    /// ```
+    pub fn display_to_viewport(&self, pos: Pos2) -> Pos2 {
+        match self.display_rect {
+            Some(display_rect) if display_rect.is_positive() => {
+                remap_pos(display_rect, self.viewport, pos)
+            }
+            _ => pos,
+        }
+    }
+
+    /// Number of screen pixels per viewport pixel when the viewport is displayed
+    /// in [`GizmoConfig::display_rect`], or 1 if there is no display rect.
+    ///
+    /// If the display rect has a different aspect ratio than the viewport, the smaller
+    /// of the horizontal and vertical scales is used. Pixel distances of the interaction,
+    /// such as how far from a handle it can still be picked, are given in screen pixels
+    /// and divided by this scale, so they stay the same regardless of the display size.
+    pub fn display_scale(&self) -> f32 {
+        match self.display_rect {
+            Some(display_rect) if display_rect.is_positive() && self.viewport.is_positive() => {
+                (display_rect.width() / self.viewport.width())
+                    .min(display_rect.height() / self.viewport.height())
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Largest rect with the aspect ratio of `viewport_size` that fits inside `area`, centered in it.
+    ///
+    /// When a render target is displayed with its aspect ratio preserved, leaving bars at
+    /// the sides or at the top and bottom of the area (letterboxing), this is the rect
+    /// where the render target is actually displayed, to be used as [`GizmoConfig::display_rect`].
+    ///
+    /// ```
+    /// use transform_gizmo::math::{Pos2, Rect, Vec2};
+    /// use transform_gizmo::GizmoConfig;
+    ///
+    /// // 1920x1080 render target displayed in a square area, with bars at the top and bottom
+    /// let area = Rect::from_min_size(Pos2::new(100.0, 0.0), Vec2::splat(960.0));
+    /// let display_rect = GizmoConfig::letterboxed_display_rect(Vec2::new(1920.0, 1080.0), area);
+    ///
+    /// assert_eq!(
+    ///     display_rect,
+    ///     Rect::from_min_size(Pos2::new(100.0, 210.0), Vec2::new(960.0, 540.0))
+    /// );
+    /// ```
+    pub fn letterboxed_display_rect(viewport_size: Vec2, area: Rect) -> Rect {
+        if viewport_size.x <= 0.0 || viewport_size.y <= 0.0 {
+            return area;
+        }
+
+        let scale = (area.width() / viewport_size.x).min(area.height() / viewport_size.y);
+
+        Rect::from_center_size(area.center(), viewport_size * scale)
+    }
+
+    /// Maps a viewport position to the screen, inside [`GizmoConfig::display_rect`].
+    /// Inverse of [`GizmoConfig::display_to_viewport`].
+    ///
+    /// Returns the position as is if there is no display rect.
+    pub fn viewport_to_display(&self, pos: Pos2) -> Pos2 {
+        match self.display_rect {
+            Some(display_rect) if self.viewport.is_positive() => {
+                remap_pos(self.viewport, display_rect, pos)
+            }
+            _ => pos,
+        }
+    }
+
+    /// Normal of the axis plane that faces the camera the most,
+    /// used with [`GizmoMode::TranslateBestPlane`]
+    pub(crate) fn best_plane_normal(&self) -> GizmoDirection {
+        let mut forward = self.view_forward();
+        if self.local_space() {
+            forward = self.rotation.inverse() * forward;
+        }
+
+        let forward = forward.abs();
+        if forward.x >= forward.y && forward.x >= forward.z {
+            GizmoDirection::X
+        } else if forward.y >= forward.z {
+            GizmoDirection::Y
+        } else {
+            GizmoDirection::Z
+        }
+    }
+
     /// Forward vector of the view camera
-    pub(crate) fn view_forward(&self) -> DVec3 {
-        DVec4::from(self.view_matrix.z).xyz()
+    pub(crate) fn view_forward(&self) -> FVec3 {
+        vec3_from_mint(DVec4::from(self.view_matrix.z).xyz().into())
     }
 
     /// Up vector of the view camera
-    pub(crate) fn view_up(&self) -> DVec3 {
-        DVec4::from(self.view_matrix.y).xyz()
+    pub(crate) fn view_up(&self) -> FVec3 {
+        vec3_from_mint(DVec4::from(self.view_matrix.y).xyz().into())
     }
 
     /// Right vector of the view camera
-    pub(crate) fn view_right(&self) -> DVec3 {
-        DVec4::from(self.view_matrix.x).xyz()
+    pub(crate) fn view_right(&self) -> FVec3 {
+        vec3_from_mint(DVec4::from(self.view_matrix.x).xyz().into())
     }
 
     /// Whether local orientation is used
@@ -93,8 +398,8 @@ impl GizmoConfig {
 
     /// Transform orientation of the gizmo
     pub(crate) fn orientation(&self) -> GizmoOrientation {
-        if self.is_scaling() {
-            // Scaling currently only works in local orientation,
+        if self.is_scaling() && self.global_scale_policy == GlobalScalePolicy::ForceLocal {
+            // Scaling is forced to local orientation,
             // so the configured orientation is ignored.
             GizmoOrientation::Local
         } else {
@@ -112,6 +417,8 @@ impl GizmoConfig {
     pub(crate) fn modes_changed(&self, other: &Self) -> bool {
         (self.modes != other.modes && self.mode_override.is_none())
             || (self.mode_override != other.mode_override)
+            || (self.ground_plane != other.ground_plane)
+            || (self.uniform_scale_corners != other.uniform_scale_corners)
     }
 }
 
@@ -119,25 +426,35 @@ impl GizmoConfig {
 pub(crate) struct PreparedGizmoConfig {
     config: GizmoConfig,
     /// Rotation of the gizmo
-    pub(crate) rotation: DQuat,
+    pub(crate) rotation: FQuat,
     /// Translation of the gizmo
-    pub(crate) translation: DVec3,
+    pub(crate) translation: FVec3,
     /// Scale of the gizmo
-    pub(crate) scale: DVec3,
+    pub(crate) scale: FVec3,
     /// Combined view-projection matrix
-    pub(crate) view_projection: DMat4,
+    pub(crate) view_projection: FMat4,
+    /// Inverse of the combined view-projection matrix
+    pub(crate) inverse_view_projection: FMat4,
+    /// View and projection matrices that the cached matrices were calculated from
+    cached_matrices: Option<(mint::RowMatrix4<f64>, mint::RowMatrix4<f64>)>,
     /// Model matrix from targets
-    pub(crate) model_matrix: DMat4,
+    pub(crate) model_matrix: FMat4,
     /// Combined model-view-projection matrix
-    pub(crate) mvp: DMat4,
+    pub(crate) mvp: FMat4,
     /// Scale factor for the gizmo rendering
     pub(crate) scale_factor: f32,
     /// How close the mouse pointer needs to be to a subgizmo before it is focused
     pub(crate) focus_distance: f32,
     /// Whether left-handed projection is used
+    #[cfg(feature = "rotate")]
     pub(crate) left_handed: bool,
     /// Direction from the camera to the gizmo in world space
-    pub(crate) eye_to_model_dir: DVec3,
+    pub(crate) eye_to_model_dir: FVec3,
+    /// Distance increment used for snapping translations, see [`SnapMode`]
+    pub(crate) translation_snap_distance: f32,
+    /// Minimum and maximum corners of the combined bounds of the targets,
+    /// in the space of the gizmo without scale, if any of the targets has bounds
+    pub(crate) bounds: Option<(FVec3, FVec3)>,
 }
 
 impl Deref for PreparedGizmoConfig {
@@ -156,65 +473,168 @@ impl DerefMut for PreparedGizmoConfig {
 
 impl PreparedGizmoConfig {
     pub(crate) fn update_for_config(&mut self, config: GizmoConfig) {
-        let projection_matrix = DMat4::from(config.projection_matrix);
-        let view_matrix = DMat4::from(config.view_matrix);
+        self.config = config;
+        self.update_view_projection();
+    }
 
-        let view_projection = projection_matrix * view_matrix;
+    /// Updates the values calculated from the view and projection matrices.
+    ///
+    /// The matrices are only recalculated if the view or projection matrix has changed.
+    pub(crate) fn update_view_projection(&mut self) {
+        let matrices = (self.config.view_matrix, self.config.projection_matrix);
 
-        let left_handed = if projection_matrix.z_axis.w == 0.0 {
-            projection_matrix.z_axis.z > 0.0
-        } else {
-            projection_matrix.z_axis.w > 0.0
-        };
+        if self.cached_matrices != Some(matrices) {
+            let projection_matrix = mat4_from_mint(self.config.projection_matrix);
+            let view_matrix = mat4_from_mint(self.config.view_matrix);
 
-        self.config = config;
-        self.view_projection = view_projection;
-        self.left_handed = left_handed;
+            let view_projection = projection_matrix * view_matrix;
 
-        self.update_transform(Transform {
-            scale: self.scale.into(),
-            rotation: self.rotation.into(),
-            translation: self.translation.into(),
-        });
+            #[cfg(feature = "rotate")]
+            let left_handed = if projection_matrix.z_axis.w == 0.0 {
+                projection_matrix.z_axis.z > 0.0
+            } else {
+                projection_matrix.z_axis.w > 0.0
+            };
+
+            self.view_projection = view_projection;
+            self.inverse_view_projection = view_projection.inverse();
+            #[cfg(feature = "rotate")]
+            self.left_handed = left_handed;
+            self.cached_matrices = Some(matrices);
+        }
+
+        self.update_transform(self.as_transform());
     }
 
     pub(crate) fn update_for_targets(&mut self, targets: &[Transform]) {
-        let mut scale = DVec3::ZERO;
-        let mut translation = DVec3::ZERO;
-        let mut rotation = DQuat::IDENTITY;
+        let mut scale = FVec3::ZERO;
+        let mut translation = FVec3::ZERO;
+        let mut rotation = FQuat::IDENTITY;
 
         let mut target_count = 0;
         for target in targets {
-            scale += DVec3::from(target.scale);
-            translation += DVec3::from(target.translation);
-            rotation = DQuat::from(target.rotation);
+            scale += vec3_from_mint(target.scale);
+            translation += vec3_from_mint(target.translation);
+            rotation = quat_from_mint(target.rotation);
 
             target_count += 1;
         }
 
         if target_count == 0 {
-            scale = DVec3::ONE;
+            scale = FVec3::ONE;
         } else {
-            translation /= target_count as f64;
-            scale /= target_count as f64;
+            translation /= target_count as Float;
+            scale /= target_count as Float;
         }
 
+        let anchor_offset = vec3_from_mint(self.config.anchor_offset);
+        translation += match self.config.anchor_offset_orientation {
+            GizmoOrientation::Global => anchor_offset,
+            GizmoOrientation::Local => rotation * anchor_offset,
+        };
+
         self.update_transform(Transform {
-            scale: scale.into(),
-            rotation: rotation.into(),
-            translation: translation.into(),
+            scale: vec3_to_mint(scale),
+            rotation: quat_to_mint(rotation),
+            translation: vec3_to_mint(translation),
         });
+
+        // The snapping distance is only updated here, so that
+        // it stays constant while the gizmo is being dragged.
+        self.update_translation_snap_distance();
+    }
+
+    /// Updates the combined bounds of the targets. `bounds` is given in the same order as `targets`.
+    pub(crate) fn update_bounds(&mut self, targets: &[Transform], bounds: &[Option<Aabb>]) {
+        let inverse_rotation = self.rotation.inverse();
+
+        self.bounds = targets
+            .iter()
+            .zip(bounds)
+            .filter_map(|(target, bounds)| Some((target, bounds.as_ref()?)))
+            .flat_map(|(target, bounds)| {
+                let scale = vec3_from_mint(target.scale);
+                let rotation = quat_from_mint(target.rotation);
+                let translation = vec3_from_mint(target.translation);
+
+                bounds
+                    .corners()
+                    .map(|corner| translation + rotation * (scale * vec3_from_mint(corner.into())))
+            })
+            .map(|corner| inverse_rotation * (corner - self.translation))
+            .fold(None, |bounds, corner| match bounds {
+                Some((min, max)) => Some((corner.min(min), corner.max(max))),
+                None => Some((corner, corner)),
+            });
+    }
+
+    /// Updates the translation snapping distance, which depends on the snap mode and scale factor
+    pub(crate) fn update_translation_snap_distance(&mut self) {
+        self.translation_snap_distance = match self.config.snap_mode {
+            SnapMode::Fixed | SnapMode::BoundsSize => self.config.snap_distance,
+            SnapMode::Adaptive { pixels_per_step } => round_to_nice_step(
+                self.scale_factor * pixels_per_step / self.config.display_scale(),
+            ),
+        };
+    }
+
+    /// Translation snapping distance along the given world space direction.
+    /// Differs from [`Self::translation_snap_distance`] with [`SnapMode::BoundsSize`]
+    /// and [`GizmoConfig::snap_distance_per_axis`].
+    pub(crate) fn translation_snap_distance_along(&self, direction: FVec3) -> Float {
+        let snap_distance = Float::from(self.translation_snap_distance);
+
+        if let (SnapMode::BoundsSize, Some((min, max))) = (self.config.snap_mode, self.bounds) {
+            // The bounds are in the space of the gizmo
+            let direction = (self.rotation.inverse() * direction).normalize_or_zero();
+            let size = (max - min).dot(direction.abs());
+
+            if size > 1e-5 {
+                return size;
+            }
+        }
+
+        if self
+            .config
+            .snap_distance_per_axis
+            .iter()
+            .all(Option::is_none)
+        {
+            return snap_distance;
+        }
+
+        let axis_distances = FVec3::from(
+            self.config
+                .snap_distance_per_axis
+                .map(|distance| distance.map_or(snap_distance, Float::from)),
+        );
+
+        let direction = if self.local_space() {
+            self.rotation.inverse() * direction
+        } else {
+            direction
+        };
+
+        // Weighted by how much the direction points along each axis
+        let direction = direction.normalize_or_zero().abs();
+        let weight = direction.element_sum();
+
+        if weight > 1e-5 {
+            axis_distances.dot(direction) / weight
+        } else {
+            snap_distance
+        }
     }
 
     pub(crate) fn update_transform(&mut self, transform: Transform) {
-        self.translation = transform.translation.into();
-        self.rotation = transform.rotation.into();
-        self.scale = transform.scale.into();
+        self.translation = vec3_from_mint(transform.translation);
+        self.rotation = quat_from_mint(transform.rotation);
+        self.scale = vec3_from_mint(transform.scale);
         self.model_matrix =
-            DMat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation);
+            FMat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation);
         self.mvp = self.view_projection * self.model_matrix;
 
-        self.scale_factor = self.mvp.as_ref()[15] as f32
+        self.scale_factor = float_to_f32(self.mvp.as_ref()[15])
             / self.projection_matrix.x.x as f32
             / self.config.viewport.width()
             * 2.0;
@@ -224,27 +644,75 @@ impl PreparedGizmoConfig {
 
         let gizmo_view_near = screen_to_world(
             self.config.viewport,
-            self.view_projection.inverse(),
+            self.inverse_view_projection,
             gizmo_screen_pos,
             -1.0,
         );
 
-        self.focus_distance = self.scale_factor * (self.config.visuals.stroke_width / 2.0 + 5.0);
-
         self.eye_to_model_dir = (gizmo_view_near - self.translation).normalize_or_zero();
+
+        self.update_focus_distance();
+    }
+
+    /// Updates the focus distance, which depends on the scale factor and visuals
+    pub(crate) fn update_focus_distance(&mut self) {
+        self.focus_distance =
+            self.scale_factor * (self.config.visuals.stroke_width / 2.0 + self.pick_tolerance());
+    }
+
+    /// Distance from the edges of the handles within which they can still be picked,
+    /// in viewport pixels. Constant in screen pixels, see [`GizmoConfig::display_scale`].
+    pub(crate) fn pick_tolerance(&self) -> f32 {
+        PICK_TOLERANCE / self.config.display_scale()
     }
 
     pub(crate) fn as_transform(&self) -> Transform {
         Transform {
-            scale: self.scale.into(),
-            rotation: self.rotation.into(),
-            translation: self.translation.into(),
+            scale: vec3_to_mint(self.scale),
+            rotation: quat_to_mint(self.rotation),
+            translation: vec3_to_mint(self.translation),
         }
     }
 }
 
+/// Checks that the view matrix has an orthonormal rotation and no projection.
+/// A transposed view matrix has its translation on the bottom row instead.
+fn debug_assert_view_matrix(view_matrix: mint::RowMatrix4<f64>) {
+    if cfg!(debug_assertions) {
+        let matrix = DMat4::from(view_matrix);
+        let rotation = DMat3::from_mat4(matrix);
+
+        assert!(
+            matrix.row(3).abs_diff_eq(DVec4::W, 1e-6),
+            "view matrix has a non-affine bottom row {}, it may be transposed",
+            matrix.row(3)
+        );
+        assert!(
+            (rotation * rotation.transpose()).abs_diff_eq(DMat3::IDENTITY, 1e-4),
+            "view matrix rotation is not orthonormal"
+        );
+    }
+}
+
+/// Checks that the bottom row of the projection matrix is that of
+/// a perspective or an orthographic projection.
+fn debug_assert_projection_matrix(projection_matrix: mint::RowMatrix4<f64>) {
+    if cfg!(debug_assertions) {
+        let bottom_row = DMat4::from(projection_matrix).row(3);
+
+        assert!(
+            bottom_row.abs_diff_eq(DVec4::W, 1e-6)
+                || bottom_row.abs_diff_eq(DVec4::Z, 1e-6)
+                || bottom_row.abs_diff_eq(DVec4::NEG_Z, 1e-6),
+            "projection matrix has an unexpected bottom row {bottom_row}, it may be transposed"
+        );
+    }
+}
+
 /// Operation mode of a gizmo.
 #[derive(Debug, EnumSetType, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 pub enum GizmoMode {
     /// Rotate around the X axis
     RotateX,
@@ -284,12 +752,29 @@ pub enum GizmoMode {
     ScaleUniform,
     /// Rotate using an arcball (trackball)
     Arcball,
+    /// Stretch the bounding box of the targets using handles on its faces and corners.
+    /// Only shown when bounds are given with [`Gizmo::update_with_bounds`](crate::Gizmo::update_with_bounds).
+    Bounds,
+    /// Translate along the axis plane that faces the camera the most.
+    /// The plane is chosen again whenever the gizmo is not being dragged, so that
+    /// top, front and side views always offer a planar drag.
+    /// See [`Gizmo::best_translate_plane_normal`](crate::Gizmo::best_translate_plane_normal).
+    ///
+    /// No extra handle is shown if the mode of the chosen plane is enabled as well.
+    TranslateBestPlane,
+    /// Edit a unit direction, such as the direction of a light or a face normal, by dragging
+    /// the tip of an arrow around the gizmo. The direction is the local Z axis of the targets,
+    /// which are only rotated, around their own origins.
+    ///
+    /// Meant to be used on its own or with rotation modes. Translation
+    /// and scaling handles are not shown while this mode is enabled.
+    Direction,
 }
 
 impl GizmoMode {
-    /// All modes
+    /// All modes, except [`GizmoMode::Direction`] which replaces the translation and scaling modes
     pub fn all() -> EnumSet<Self> {
-        EnumSet::all()
+        EnumSet::all() - Self::Direction
     }
 
     /// All rotation modes
@@ -307,6 +792,7 @@ impl GizmoMode {
                 | Self::TranslateXZ
                 | Self::TranslateYZ
                 | Self::TranslateView
+                | Self::TranslateBestPlane
         )
     }
 
@@ -323,6 +809,66 @@ impl GizmoMode {
         )
     }
 
+    /// Modes resembling the transform gizmo of Blender.
+    ///
+    /// Includes all translation and rotation modes, the arcball
+    /// and scaling along the axes.
+    pub fn preset_blender() -> EnumSet<Self> {
+        Self::all_translate()
+            | Self::all_rotate()
+            | enum_set!(Self::Arcball | Self::ScaleX | Self::ScaleY | Self::ScaleZ)
+    }
+
+    /// Modes resembling the transform tool of Unity.
+    ///
+    /// Includes translation along the axes and planes, all rotation modes,
+    /// the arcball and scaling along the axes and uniformly.
+    pub fn preset_unity() -> EnumSet<Self> {
+        Self::all_rotate()
+            | enum_set!(
+                Self::TranslateX
+                    | Self::TranslateY
+                    | Self::TranslateZ
+                    | Self::TranslateXY
+                    | Self::TranslateXZ
+                    | Self::TranslateYZ
+                    | Self::Arcball
+                    | Self::ScaleX
+                    | Self::ScaleY
+                    | Self::ScaleZ
+                    | Self::ScaleUniform
+            )
+    }
+
+    /// Modes that keep the targets on the XY plane, for 2D applications.
+    /// See [`Gizmo::update_2d`](crate::Gizmo::update_2d).
+    ///
+    /// Includes translation along the X and Y axes and the XY plane, rotation around
+    /// the Z axis and scaling along the X and Y axes and the XY plane.
+    pub const fn preset_2d() -> EnumSet<Self> {
+        enum_set!(
+            Self::TranslateX
+                | Self::TranslateY
+                | Self::TranslateXY
+                | Self::RotateZ
+                | Self::ScaleX
+                | Self::ScaleY
+                | Self::ScaleXY
+        )
+    }
+
+    /// Modes resembling the universal manipulator of Maya.
+    ///
+    /// Includes all translation and rotation modes, the arcball
+    /// and scaling along the axes and uniformly.
+    pub fn preset_maya() -> EnumSet<Self> {
+        Self::all_translate()
+            | Self::all_rotate()
+            | enum_set!(
+                Self::Arcball | Self::ScaleX | Self::ScaleY | Self::ScaleZ | Self::ScaleUniform
+            )
+    }
+
     /// Is this mode for rotation
     pub fn is_rotate(&self) -> bool {
         self.kind() == GizmoModeKind::Rotate
@@ -353,7 +899,7 @@ impl GizmoMode {
             Self::RotateView | Self::TranslateView => {
                 enum_set!(GizmoDirection::View)
             }
-            Self::ScaleUniform | Self::Arcball => {
+            Self::ScaleUniform | Self::Arcball | Self::Bounds => {
                 enum_set!(GizmoDirection::X | GizmoDirection::Y | GizmoDirection::Z)
             }
             Self::TranslateXY | Self::ScaleXY => {
@@ -365,6 +911,9 @@ impl GizmoMode {
             Self::TranslateYZ | Self::ScaleYZ => {
                 enum_set!(GizmoDirection::Y | GizmoDirection::Z)
             }
+            Self::Direction => enum_set!(GizmoDirection::Z),
+            // Depends on the view direction
+            Self::TranslateBestPlane => EnumSet::empty(),
         }
     }
 
@@ -376,6 +925,45 @@ impl GizmoMode {
             .collect()
     }
 
+    /// Returns the mode of given kind that matches to given axes exactly, if any
+    pub fn from_kind_and_axes(kind: GizmoModeKind, axes: EnumSet<GizmoDirection>) -> Option<Self> {
+        Self::all_from_axes(axes)
+            .iter()
+            .find(|mode| mode.kind() == kind)
+    }
+
+    /// Rotation mode for given axes, if any
+    pub fn rotate(axes: EnumSet<GizmoDirection>) -> Option<Self> {
+        Self::from_kind_and_axes(GizmoModeKind::Rotate, axes)
+    }
+
+    /// Translation mode for given axes, if any
+    pub fn translate(axes: EnumSet<GizmoDirection>) -> Option<Self> {
+        Self::from_kind_and_axes(GizmoModeKind::Translate, axes)
+    }
+
+    /// Scaling mode for given axes, if any
+    pub fn scale(axes: EnumSet<GizmoDirection>) -> Option<Self> {
+        Self::from_kind_and_axes(GizmoModeKind::Scale, axes)
+    }
+
+    /// Whether the handles of the two modes share an axis, such as the X axis arrow
+    /// and the XY and XZ planes of the same kind. Used with [`GizmoVisuals::highlight_axis_family`].
+    pub(crate) fn is_in_axis_family_of(&self, other: &Self) -> bool {
+        let is_partial = |axes: EnumSet<GizmoDirection>| {
+            !axes.contains(GizmoDirection::View) && (1..=2).contains(&axes.len())
+        };
+
+        let axes = self.axes();
+        let other_axes = other.axes();
+
+        self != other
+            && self.kind() == other.kind()
+            && is_partial(axes)
+            && is_partial(other_axes)
+            && (axes.is_subset(other_axes) || axes.is_superset(other_axes))
+    }
+
     pub fn kind(&self) -> GizmoModeKind {
         match self {
             Self::RotateX | Self::RotateY | Self::RotateZ | Self::RotateView => {
@@ -387,7 +975,8 @@ impl GizmoMode {
             | Self::TranslateXY
             | Self::TranslateXZ
             | Self::TranslateYZ
-            | Self::TranslateView => GizmoModeKind::Translate,
+            | Self::TranslateView
+            | Self::TranslateBestPlane => GizmoModeKind::Translate,
             Self::ScaleX
             | Self::ScaleY
             | Self::ScaleZ
@@ -396,6 +985,8 @@ impl GizmoMode {
             | Self::ScaleYZ
             | Self::ScaleUniform => GizmoModeKind::Scale,
             Self::Arcball => GizmoModeKind::Arcball,
+            Self::Bounds => GizmoModeKind::Bounds,
+            Self::Direction => GizmoModeKind::Direction,
         }
     }
 }
@@ -406,10 +997,45 @@ pub enum GizmoModeKind {
     Translate,
     Scale,
     Arcball,
+    Bounds,
+    Direction,
+}
+
+impl GizmoModeKind {
+    /// All modes of this kind
+    pub const fn modes(self) -> EnumSet<GizmoMode> {
+        match self {
+            Self::Rotate => GizmoMode::all_rotate(),
+            Self::Translate => GizmoMode::all_translate(),
+            Self::Scale => GizmoMode::all_scale(),
+            Self::Arcball => enum_set!(GizmoMode::Arcball),
+            Self::Bounds => enum_set!(GizmoMode::Bounds),
+            Self::Direction => enum_set!(GizmoMode::Direction),
+        }
+    }
+
+    /// Mode of this kind that matches to given axes exactly, if any
+    pub fn with_axes(self, axes: EnumSet<GizmoDirection>) -> Option<GizmoMode> {
+        GizmoMode::from_kind_and_axes(self, axes)
+    }
+
+    /// Mode of this kind that is not bound to any specific axis
+    pub const fn default_mode(self) -> GizmoMode {
+        match self {
+            Self::Rotate => GizmoMode::RotateView,
+            Self::Translate => GizmoMode::TranslateView,
+            Self::Scale => GizmoMode::ScaleUniform,
+            Self::Arcball => GizmoMode::Arcball,
+            Self::Bounds => GizmoMode::Bounds,
+            Self::Direction => GizmoMode::Direction,
+        }
+    }
 }
 
 /// The point in space around which all rotations are centered.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 pub enum TransformPivotPoint {
     /// Pivot around the median point of targets
     #[default]
@@ -418,8 +1044,212 @@ pub enum TransformPivotPoint {
     IndividualOrigins,
 }
 
+/// Determines how scaling behaves when [`GizmoOrientation::Global`] is used.
+///
+/// Scaling a rotated object along an arbitrary global axis cannot be represented
+/// with a scale, rotation and translation alone, as it would introduce shear.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum GlobalScalePolicy {
+    /// Scaling always uses local orientation, regardless of the configured orientation.
+    #[default]
+    ForceLocal,
+    /// Scaling uses global axes. Each global axis is mapped to the local axis of
+    /// the target that it is most aligned with, and the scale is applied along that local axis.
+    ///
+    /// This never introduces shear. The result is exact when the target's local axes
+    /// are aligned with the global axes, and an approximation otherwise.
+    ClosestAxis,
+}
+
+/// Inclusive range of allowed values for a transformation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub struct TransformLimit {
+    /// Smallest allowed value
+    pub min: f64,
+    /// Largest allowed value. Must not be smaller than `min`, see [`Self::is_valid`].
+    pub max: f64,
+}
+
+impl TransformLimit {
+    /// Creates a new limit from the smallest and largest allowed values
+    pub const fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+
+    /// Whether `min` is not larger than `max` and neither is NaN.
+    /// Invalid limits, such as reversed ones from a config file, are ignored.
+    pub fn is_valid(&self) -> bool {
+        self.min <= self.max
+    }
+
+    /// Clamps the value to the limit, or returns it as is if the limit is not valid
+    pub fn clamp(&self, value: f64) -> f64 {
+        if self.is_valid() {
+            value.clamp(self.min, self.max)
+        } else {
+            value
+        }
+    }
+}
+
+impl From<RangeInclusive<f64>> for TransformLimit {
+    fn from(range: RangeInclusive<f64>) -> Self {
+        Self::new(*range.start(), *range.end())
+    }
+}
+
+/// Determines how cursor movement is converted to scale.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum ScaleInteraction {
+    /// Scale is the ratio of the cursor's current and initial
+    /// distance from the gizmo center, in screen space.
+    #[default]
+    CursorDistance,
+    /// Cursor movement is projected onto the scaled axis or plane in world space,
+    /// similarly to translation. Moving the cursor by the size of the gizmo
+    /// increases the scale by one.
+    ///
+    /// Unlike [`ScaleInteraction::CursorDistance`], this works well even when
+    /// the gizmo center is near the screen edge or off-screen.
+    AxisProjection,
+}
+
+/// Determines how the targets are scaled along axes whose scale is zero or close to it,
+/// such as flattened objects.
+///
+/// Scaling multiplies the scale at the start of the drag, so a zero scale would otherwise
+/// never change.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum ZeroScalePolicy {
+    /// Scales are always multiplied, so zero scales stay zero.
+    Multiply,
+    /// Start scales closer to zero than the given epsilon are replaced with the epsilon,
+    /// keeping their sign, before they are multiplied.
+    Epsilon(f64),
+    /// Along axes whose start scale is zero, the scale of the interaction minus one
+    /// is added to the start scale instead of multiplying it. Other axes are multiplied.
+    #[default]
+    Additive,
+}
+
+impl ZeroScalePolicy {
+    /// Start scales closer to zero than this are considered zero by [`ZeroScalePolicy::Additive`].
+    const ZERO_EPSILON: f64 = 1e-9;
+
+    /// Scale of a target whose scale at the start of the interaction was `start_scale`,
+    /// after scaling it by `scale`.
+    pub fn apply(&self, start_scale: DVec3, scale: DVec3) -> DVec3 {
+        let apply_axis = |start_scale: f64, scale: f64| match *self {
+            Self::Multiply => start_scale * scale,
+            Self::Epsilon(epsilon) if start_scale.abs() < epsilon => {
+                epsilon.copysign(start_scale) * scale
+            }
+            Self::Epsilon(_) => start_scale * scale,
+            Self::Additive if start_scale.abs() < Self::ZERO_EPSILON => start_scale + scale - 1.0,
+            Self::Additive => start_scale * scale,
+        };
+
+        DVec3::new(
+            apply_axis(start_scale.x, scale.x),
+            apply_axis(start_scale.y, scale.y),
+            apply_axis(start_scale.z, scale.z),
+        )
+    }
+}
+
+/// Determines which axes the targets are rotated around when
+/// [`GizmoOrientation::Local`] is used with multiple targets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum LocalRotationAxes {
+    /// Each target is rotated around its own local axis. Combined with
+    /// [`TransformPivotPoint::IndividualOrigins`], this matches the
+    /// "Individual Origins" pivot of Blender.
+    #[default]
+    PerTarget,
+    /// All targets are rotated around the axis of the gizmo, i.e. the local
+    /// axis of the last target. Combined with [`TransformPivotPoint::MedianPoint`],
+    /// the targets rotate together as if they were a single rigid object.
+    Gizmo,
+}
+
+/// Determines when the results of a drag are applied to the targets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum ApplyMode {
+    /// Updated transforms are returned on every update while dragging.
+    #[default]
+    Continuous,
+    /// Updated transforms are returned only once, when the drag ends.
+    ///
+    /// While dragging, the gizmo itself moves with the cursor and the would-be
+    /// transforms are available from [`Gizmo::preview_transforms`](crate::Gizmo::preview_transforms).
+    /// Useful when applying the transforms triggers expensive recomputation.
+    ///
+    /// Has no effect while [`GizmoConfig::mode_override`] is set,
+    /// since such interactions have no clear end.
+    OnRelease,
+}
+
+/// Determines how the snapping distance of translations is chosen.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum SnapMode {
+    /// [`GizmoConfig::snap_distance`] is used as is.
+    #[default]
+    Fixed,
+    /// The snapping distance is derived from the world space size of a pixel at
+    /// the gizmo's position, so that one step is roughly `pixels_per_step` pixels
+    /// on screen. The distance is rounded to 1, 2 or 5 times a power of ten.
+    ///
+    /// Useful when the targets are very small or very large compared to
+    /// [`GizmoConfig::snap_distance`]. The distance is kept constant while dragging.
+    Adaptive {
+        /// Approximate length of one snapping step in pixels
+        pixels_per_step: f32,
+    },
+    /// The snapping distance along each axis is the size of the combined bounding box
+    /// of the targets along that axis, so that the targets move in whole-object increments.
+    /// Useful for placing tiles or bricks exactly next to each other.
+    ///
+    /// The bounding boxes are given with [`Gizmo::update_with_bounds`](crate::Gizmo::update_with_bounds).
+    /// [`GizmoConfig::snap_distance`] is used if the targets have no bounds,
+    /// or the bounds have no size along the axis.
+    BoundsSize,
+}
+
+/// Determines where snapping increments are counted from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum SnapOrigin {
+    /// Increments are counted from the state at the start of the drag.
+    /// For example, a rotation starting at 7° snaps to 22°, 37° and so on.
+    #[default]
+    DragStart,
+    /// Increments are counted from zero. For example, rotations snap to
+    /// 0°, 15°, 30° and so on, and translations to multiples of the
+    /// snapping distance along each axis.
+    ///
+    /// Rotations around the view axis are always snapped relative to the drag start.
+    Absolute,
+}
+
 /// Orientation of a gizmo.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 pub enum GizmoOrientation {
     /// Transformation axes are aligned to world space.
     #[default]
@@ -429,6 +1259,8 @@ pub enum GizmoOrientation {
 }
 
 #[derive(Debug, EnumSetType, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
 pub enum GizmoDirection {
     /// Gizmo points in the X-direction
     X,
@@ -440,27 +1272,135 @@ pub enum GizmoDirection {
     View,
 }
 
+/// A single component of a target's transformation.
+///
+/// Translation and rotation channels refer to global axes,
+/// while scale channels refer to the target's local axes.
+#[derive(Debug, EnumSetType, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum TransformChannel {
+    /// Translation along the X axis
+    TranslateX,
+    /// Translation along the Y axis
+    TranslateY,
+    /// Translation along the Z axis
+    TranslateZ,
+    /// Rotation around the X axis
+    RotateX,
+    /// Rotation around the Y axis
+    RotateY,
+    /// Rotation around the Z axis
+    RotateZ,
+    /// Scale along the X axis
+    ScaleX,
+    /// Scale along the Y axis
+    ScaleY,
+    /// Scale along the Z axis
+    ScaleZ,
+}
+
+impl TransformChannel {
+    /// All translation channels
+    pub const fn all_translate() -> EnumSet<Self> {
+        enum_set!(Self::TranslateX | Self::TranslateY | Self::TranslateZ)
+    }
+
+    /// All rotation channels
+    pub const fn all_rotate() -> EnumSet<Self> {
+        enum_set!(Self::RotateX | Self::RotateY | Self::RotateZ)
+    }
+
+    /// All scale channels
+    pub const fn all_scale() -> EnumSet<Self> {
+        enum_set!(Self::ScaleX | Self::ScaleY | Self::ScaleZ)
+    }
+}
+
 /// Controls the visual style of the gizmo
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(bevy_reflect::Reflect),
+    reflect(Default)
+)]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoVisuals {
     /// Color of the x axis
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
     pub x_color: Color32,
     /// Color of the y axis
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
     pub y_color: Color32,
     /// Color of the z axis
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
     pub z_color: Color32,
     /// Color of the forward axis
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
     pub s_color: Color32,
     /// Alpha of the gizmo color when inactive
     pub inactive_alpha: f32,
     /// Alpha of the gizmo color when highlighted/active
     pub highlight_alpha: f32,
     /// Color to use for highlighted and active axes. By default, the axis color is used with `highlight_alpha`
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
     pub highlight_color: Option<Color32>,
     /// Width (thickness) of the gizmo strokes
     pub stroke_width: f32,
     /// Gizmo size in pixels
     pub gizmo_size: f32,
+    /// Color used to indicate that a transformation has reached its limit
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
+    pub limit_color: Color32,
+    /// Distance in pixels by which the uniform scale circle is moved outwards from
+    /// the view rotation circle, when both [`GizmoMode::ScaleUniform`] and
+    /// [`GizmoMode::RotateView`] are enabled.
+    pub uniform_scale_offset: f32,
+    /// Style of the uniform scale circle. Using a different style than the
+    /// solid view rotation circle helps telling the two apart.
+    pub uniform_scale_style: CircleStyle,
+    /// How the handles other than the active one are drawn during a drag
+    pub inactive_during_drag: InactiveHandleStyle,
+    /// Multipliers of the X, Y and Z rotation ring radii.
+    /// Using slightly different radii renders the rings nested, which
+    /// reduces overlapping pick regions. See [`Self::NESTED_ROTATION_RINGS`].
+    pub rotation_radius_scale: [f32; 3],
+    /// Whether a faint grid of the snapping increments is drawn on the plane
+    /// while a plane translation handle is dragged with [`GizmoConfig::snapping`] enabled
+    pub snap_grid: bool,
+    /// Smallest distance in pixels between the lines of the snapping grid.
+    /// The grid is not drawn if its cells would be smaller than this on the screen.
+    pub snap_grid_min_spacing: f32,
+    /// Whether the handles sharing an axis with the hovered handle are drawn slightly
+    /// brighter. For example, hovering the X axis arrow brightens the XY and XZ planes.
+    pub highlight_axis_family: bool,
+    /// Whether the axis arrows and planes fade out when viewed edge-on.
+    /// Faded handles cannot be interacted with. If `false`, all handles
+    /// are always visible and clickable, at the cost of more clutter.
+    pub fade_occluded_handles: bool,
+    /// Whether the axis arrows are flipped to point towards the camera when their axis
+    /// points away from it, so that the arrows are not hidden behind the gizmo.
+    /// The results are still given along the actual axes.
+    pub flip_axes_toward_camera: bool,
+    /// Whether a tick is drawn on the rotation ring at the angle the rotation was started from,
+    /// for measuring how far the target has been rotated during the drag
+    pub rotation_start_tick: bool,
+    /// Whether a tick is drawn on the rotation ring at the zero angle of the rotation axis,
+    /// i.e. where the target would have no twist around the axis. Drawn in [`Self::s_color`].
+    /// Not drawn for [`GizmoMode::RotateView`].
+    pub rotation_zero_tick: bool,
+    /// Multiplier of the [`GizmoMode::RotateView`] circle radius
+    pub view_rotation_radius_factor: f32,
+    /// Multiplier of the [`GizmoMode::ScaleUniform`] circle radius,
+    /// applied before [`Self::uniform_scale_offset`]
+    pub uniform_scale_radius_factor: f32,
+}
+
+impl GizmoVisuals {
+    /// Rotation ring radius multipliers for a nested ring layout,
+    /// to be used with [`Self::rotation_radius_scale`].
+    pub const NESTED_ROTATION_RINGS: [f32; 3] = [1.0, 0.92, 0.84];
 }
 
 impl Default for GizmoVisuals {
@@ -475,6 +1415,113 @@ impl Default for GizmoVisuals {
             highlight_color: None,
             stroke_width: 4.0,
             gizmo_size: 75.0,
+            limit_color: Color32::from_rgb(255, 170, 0),
+            uniform_scale_offset: 15.0,
+            uniform_scale_style: CircleStyle::default(),
+            inactive_during_drag: InactiveHandleStyle::default(),
+            rotation_radius_scale: [1.0; 3],
+            snap_grid: false,
+            snap_grid_min_spacing: 8.0,
+            highlight_axis_family: false,
+            fade_occluded_handles: true,
+            flip_axes_toward_camera: false,
+            rotation_start_tick: true,
+            rotation_zero_tick: false,
+            view_rotation_radius_factor: 1.0,
+            uniform_scale_radius_factor: 1.0,
+        }
+    }
+}
+
+/// Ground plane used for the compass translation arrows,
+/// see [`GizmoConfig::ground_plane`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GroundPlane {
+    /// World space axis perpendicular to the plane.
+    /// [`GizmoDirection::View`] is treated as [`GizmoDirection::Y`].
+    pub normal: GizmoDirection,
+    /// Position of the plane along the normal axis.
+    /// If `None`, the plane passes through the gizmo position.
+    pub height: Option<f64>,
+}
+
+impl Default for GroundPlane {
+    fn default() -> Self {
+        Self {
+            normal: GizmoDirection::Y,
+            height: None,
+        }
+    }
+}
+
+impl GroundPlane {
+    /// World space axis perpendicular to the plane
+    pub(crate) fn normal_axis(&self) -> GizmoDirection {
+        match self.normal {
+            GizmoDirection::View => GizmoDirection::Y,
+            normal => normal,
         }
     }
 }
+
+/// World space geometry that translations are constrained to,
+/// see [`Gizmo::set_translation_constraint`](crate::Gizmo::set_translation_constraint).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TranslationConstraint {
+    /// Infinite line through `point` along `direction`.
+    /// The position along the line is `t` in `point + direction * t`.
+    Line {
+        point: mint::Vector3<f64>,
+        direction: mint::Vector3<f64>,
+    },
+    /// Path through the given points, such as a rail.
+    /// The position along the path is the index of the segment plus the fraction
+    /// of the segment, from 0 at the first point to `points.len() - 1` at the last.
+    Path(Vec<mint::Vector3<f64>>),
+}
+
+/// Style of a circular handle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum CircleStyle {
+    /// A single solid circle
+    #[default]
+    Solid,
+    /// A single circle made of dashes
+    Dashed,
+    /// Two thin concentric circles
+    Double,
+}
+
+/// How the inactive handles are drawn while another handle is being dragged,
+/// see [`GizmoVisuals::inactive_during_drag`].
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bevy_reflect", derive(bevy_reflect::Reflect))]
+pub enum InactiveHandleStyle {
+    /// Only the active handle is drawn
+    #[default]
+    Hide,
+    /// The other handles are drawn with their alpha multiplied by the given factor
+    Fade(f32),
+}
+
+/// Rounds the value up to the closest 1, 2 or 5 times a power of ten
+fn round_to_nice_step(value: f32) -> f32 {
+    if !value.is_finite() || value <= 0.0 {
+        return DEFAULT_SNAP_DISTANCE;
+    }
+
+    let magnitude = 10.0_f32.powf(value.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= value)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -0,0 +1,147 @@
+//! Loading gizmo visuals from configuration files.
+//!
+//! Supported formats are RON (`.ron`) and TOML (`.toml`), chosen based on
+//! the file extension. Fields missing from the file use their default values.
+//!
+//! [`VisualsWatcher`] can be used to reload the visuals whenever the file changes,
+//! allowing the look of the gizmo to be tweaked while the application is running.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use transform_gizmo::config_file::VisualsWatcher;
+//! use transform_gizmo::prelude::*;
+//!
+//! let mut config = GizmoConfig::default();
+//! let mut watcher = VisualsWatcher::new("gizmo_visuals.ron");
+//!
+//! // Once per frame
+//! match watcher.poll() {
+//!     Some(Ok(visuals)) => config.visuals = visuals,
+//!     Some(Err(err)) => eprintln!("Failed to load gizmo visuals: {err}"),
+//!     None => {}
+//! }
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::config::GizmoVisuals;
+
+/// Error that can occur when loading a configuration file.
+#[derive(Debug)]
+pub enum ConfigFileError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file is not valid RON
+    Ron(ron::error::SpannedError),
+    /// The file is not valid TOML
+    Toml(toml::de::Error),
+    /// The file extension is not `ron` or `toml`
+    UnsupportedExtension(PathBuf),
+}
+
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read config file: {err}"),
+            Self::Ron(err) => write!(f, "invalid RON config file: {err}"),
+            Self::Toml(err) => write!(f, "invalid TOML config file: {err}"),
+            Self::UnsupportedExtension(path) => write!(
+                f,
+                "unsupported config file extension, expected .ron or .toml: {}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Ron(err) => Some(err),
+            Self::Toml(err) => Some(err),
+            Self::UnsupportedExtension(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ConfigFileError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl From<toml::de::Error> for ConfigFileError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Toml(err)
+    }
+}
+
+impl GizmoVisuals {
+    /// Loads visuals from a RON or TOML file.
+    ///
+    /// The format is chosen based on the file extension.
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ron") => Ok(ron::from_str(&contents)?),
+            Some("toml") => Ok(toml::from_str(&contents)?),
+            _ => Err(ConfigFileError::UnsupportedExtension(path.to_path_buf())),
+        }
+    }
+}
+
+/// Reloads [`GizmoVisuals`] from a file whenever it is modified.
+///
+/// The modification time of the file is checked on each call to [`VisualsWatcher::poll`].
+#[derive(Debug, Clone)]
+pub struct VisualsWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl VisualsWatcher {
+    /// Creates a new watcher for the given file.
+    ///
+    /// The file is loaded on the first call to [`VisualsWatcher::poll`].
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+        }
+    }
+
+    /// Path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks whether the file has been modified since the last load.
+    ///
+    /// Returns the reloaded visuals if it has, or `None` if nothing has changed.
+    pub fn poll(&mut self) -> Option<Result<GizmoVisuals, ConfigFileError>> {
+        let modified = match std::fs::metadata(&self.path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            Err(err) => return Some(Err(err.into())),
+        };
+
+        if self.modified == Some(modified) {
+            return None;
+        }
+
+        self.modified = Some(modified);
+
+        Some(GizmoVisuals::load_from_path(&self.path))
+    }
+}
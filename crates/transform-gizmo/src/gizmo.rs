@@ -1,25 +1,54 @@
-use ecolor::Rgba;
-use emath::Pos2;
-use enumset::EnumSet;
+use enumset::{enum_set, EnumSet};
+use std::f64::consts::{PI, TAU};
 use std::ops::{Add, AddAssign, Sub};
+use std::time::Duration;
+use web_time::Instant;
 
+use crate::color::gamma_from_linear;
 use crate::config::{
-    GizmoConfig, GizmoDirection, GizmoMode, PreparedGizmoConfig, TransformPivotPoint,
+    ApplyMode, Color32, GizmoConfig, GizmoDirection, GizmoMode, GizmoModeKind, GizmoVisuals,
+    InactiveHandleStyle, LocalRotationAxes, PreparedGizmoConfig, TransformChannel,
+    TranslationConstraint,
 };
-use crate::math::{screen_to_world, Transform};
+use crate::math::{
+    closest_axis_scale, float_to_f64, full_revolutions, mat4_from_mint, screen_to_world,
+    vec3_from_mint, vec3_to_mint, Aabb, FMat4, FVec3, Float, Pos2, Rect, Transform, Transform2d,
+};
+use crate::shape::{Mesh, ShapeBuidler};
 use crate::GizmoOrientation;
-use epaint::Mesh;
-use glam::{DQuat, DVec3};
+use glam::{BVec3, DMat3, DMat4, DQuat, DVec3};
 
+#[cfg(feature = "scale")]
+use crate::subgizmo::bounds::BoundsParams;
+use crate::subgizmo::compass::CompassParams;
+#[cfg(feature = "rotate")]
 use crate::subgizmo::rotation::RotationParams;
+#[cfg(feature = "scale")]
 use crate::subgizmo::scale::ScaleParams;
 use crate::subgizmo::translation::TranslationParams;
+#[cfg(feature = "arcball")]
+use crate::subgizmo::ArcballSubGizmo;
+#[cfg(feature = "rotate")]
+use crate::subgizmo::RotationSubGizmo;
 use crate::subgizmo::{
-    common::TransformKind, ArcballSubGizmo, RotationSubGizmo, ScaleSubGizmo, SubGizmo,
-    SubGizmoControl, TranslationSubGizmo,
+    common::{view_plane_rotation, TransformKind},
+    CompassSubGizmo, ConstrainedSubGizmo, DirectionSubGizmo, SubGizmo, SubGizmoControl,
+    TranslationSubGizmo,
 };
+#[cfg(feature = "scale")]
+use crate::subgizmo::{BoundsSubGizmo, ScaleSubGizmo};
 
 /// A 3D transformation gizmo.
+///
+/// # Thread safety
+///
+/// A gizmo is plain data without interior mutability or shared ownership,
+/// so it is always [`Send`] and [`Sync`]. Gizmos can be stored in resources or
+/// components of an ECS and updated in parallel, for example one per camera.
+///
+/// All interaction state is mutated only through `&mut self` in [`Gizmo::update`]
+/// and its variants, while [`Gizmo::draw`] only needs `&self`. Drawing can therefore
+/// happen concurrently from multiple threads once the gizmo has been updated.
 #[derive(Clone, Debug, Default)]
 pub struct Gizmo {
     /// Prepared configuration of the gizmo.
@@ -30,10 +59,54 @@ pub struct Gizmo {
     /// Subgizmos used in the gizmo.
     subgizmos: Vec<SubGizmo>,
     active_subgizmo_id: Option<u64>,
+    /// Subgizmo that was most recently active, used when dragging anywhere.
+    last_active_subgizmo_id: Option<u64>,
+    /// Subgizmo that was focused in the latest update, kept focused near handle boundaries.
+    last_focused_subgizmo_id: Option<u64>,
+    /// Distance from the pointer ray origin to the focused subgizmo.
+    focused_distance: Option<Float>,
+    /// Handle that was focused when the latest [`GizmoEvent::FocusChanged`] was emitted.
+    event_focused_handle: Option<GizmoHandleId>,
+    /// Events of the latest update, see [`Gizmo::events`].
+    events: Vec<GizmoEvent>,
+    /// Modes changed during a drag, so the subgizmos are rebuilt once the drag ends.
+    rebuild_pending: bool,
+    /// Subgizmo and cursor position given to [`Gizmo::begin_interaction`],
+    /// activated in the next update.
+    requested_interaction: Option<(u64, Pos2)>,
+    /// Plane normal the [`GizmoMode::TranslateBestPlane`] subgizmo was built for, if enabled.
+    best_plane_normal: Option<GizmoDirection>,
+    /// Geometry that translations are constrained to, see [`Gizmo::set_translation_constraint`].
+    translation_constraint: Option<TranslationConstraint>,
+    /// Orientations that rotations snap to, see [`Gizmo::set_alignment_references`].
+    alignment_references: Vec<(u64, DQuat)>,
+    /// Orientation the gizmo would have without alignment snapping during the current drag.
+    unaligned_rotation: DQuat,
+    /// Latest result of the current drag, applied once the drag ends.
+    /// Only used with [`ApplyMode::OnRelease`].
+    pending_result: Option<(GizmoResult, Vec<Transform>)>,
+    /// Locked channels the pending result was computed with, see [`Gizmo::rebase_targets`].
+    pending_locked_channels: Vec<EnumSet<TransformChannel>>,
+    /// Latest result of the current drag, see [`Gizmo::latest_result`].
+    latest_result: Option<GizmoResult>,
+    /// Mode of the handles drawn as focused until the given instant.
+    highlight: Option<(GizmoMode, Instant)>,
+    /// Counters of how the gizmo has been interacted with.
+    #[cfg(feature = "analytics")]
+    stats: crate::stats::GizmoStats,
+    /// Inputs of the latest [`Gizmo::update_if_changed`] call,
+    /// cleared whenever the gizmo is changed in any other way.
+    last_inputs: Option<(GizmoConfig, GizmoInteraction, Vec<Transform>)>,
+    /// Output of the latest [`Gizmo::update_if_changed`] call
+    last_output: Option<(GizmoResult, Vec<Transform>)>,
+    /// Draw data returned by [`Gizmo::draw_cached`], cleared whenever the gizmo changes.
+    draw_cache: Option<GizmoDrawData>,
 
     target_start_transforms: Vec<Transform>,
 
     gizmo_start_transform: Transform,
+
+    last_update_status: UpdateStatus,
 }
 
 impl Gizmo {
@@ -50,26 +123,544 @@ impl Gizmo {
     }
 
     /// Updates the configuration used by the gizmo.
+    ///
+    /// If the enabled modes change while the gizmo is being dragged,
+    /// the drag continues with the previous modes and the new modes
+    /// take effect once the drag ends. This way toggling modes with
+    /// hotkeys does not interrupt the interaction.
     pub fn update_config(&mut self, config: GizmoConfig) {
+        self.invalidate_caches();
+
         if config.modes_changed(&self.config) {
-            self.subgizmos.clear();
-            self.active_subgizmo_id = None;
+            if self.active_subgizmo_id.is_some()
+                && config.mode_override == self.config.mode_override
+            {
+                self.rebuild_pending = true;
+            } else {
+                #[cfg(feature = "analytics")]
+                if self.active_subgizmo_id.is_some() {
+                    self.stats.record_drag_end();
+                }
+
+                self.subgizmos.clear();
+                self.active_subgizmo_id = None;
+                self.pending_result = None;
+                self.latest_result = None;
+            }
         }
 
         self.config.update_for_config(config);
 
         if self.subgizmos.is_empty() {
-            self.add_rotation();
-            self.add_translation();
-            self.add_scale();
+            self.rebuild_subgizmos();
+        }
+    }
+
+    /// Updates the configuration and then the gizmo, like calling [`Gizmo::update_config`]
+    /// and [`Gizmo::update`], unless the configuration, interaction and targets are
+    /// all equal to those of the previous call.
+    ///
+    /// Nothing is recomputed in that case, and the result and transforms of the
+    /// previous call are returned again. An unchanged drag keeps producing the same
+    /// result, so `None` is only returned when there is no result, like with
+    /// [`Gizmo::update`]. Useful for idle editors that still update every frame.
+    /// See also [`Gizmo::draw_cached`].
+    pub fn update_if_changed(
+        &mut self,
+        config: GizmoConfig,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.expire_highlight();
+
+        let unchanged = self.last_inputs.as_ref().is_some_and(
+            |(last_config, last_interaction, last_targets)| {
+                *last_config == config
+                    && *last_interaction == interaction
+                    && last_targets.as_slice() == targets
+            },
+        );
+
+        if unchanged {
+            self.events.clear();
+            return self.last_output.clone();
+        }
+
+        self.update_config(config);
+        let result = self.update(interaction, targets);
+
+        self.last_inputs = Some((config, interaction, targets.to_vec()));
+        self.last_output.clone_from(&result);
+
+        result
+    }
+
+    /// Forgets the inputs of [`Gizmo::update_if_changed`] and the cached draw data.
+    fn invalidate_caches(&mut self) {
+        self.last_inputs = None;
+        self.last_output = None;
+        self.draw_cache = None;
+    }
+
+    /// Recreates the subgizmos for the currently enabled modes.
+    fn rebuild_subgizmos(&mut self) {
+        self.subgizmos.clear();
+        self.rebuild_pending = false;
+        self.best_plane_normal = None;
+
+        #[cfg(feature = "rotate")]
+        self.add_rotation();
+        #[cfg(feature = "arcball")]
+        self.add_arcball();
+
+        if self.enabled_modes().contains(GizmoMode::Direction) {
+            // Directions are only rotated, so other handles would be misleading
+            self.add_direction();
+            return;
+        }
+
+        self.add_translation();
+        #[cfg(feature = "scale")]
+        self.add_scale();
+        #[cfg(feature = "scale")]
+        self.add_bounds();
+    }
+
+    /// Updates the viewport of the gizmo, keeping the rest of the configuration.
+    ///
+    /// Unlike [`Gizmo::update_config`], this only recalculates values that depend on the viewport.
+    pub fn set_viewport(&mut self, viewport: Rect) {
+        self.invalidate_caches();
+        self.config.viewport = viewport;
+        self.config.update_transform(self.config.as_transform());
+        self.update_subgizmo_configs();
+    }
+
+    /// Updates the view and projection matrices of the gizmo, keeping the rest of the configuration.
+    ///
+    /// Unlike [`Gizmo::update_config`], this only recalculates values that depend on the matrices.
+    /// Both row and column matrices are accepted, see [`GizmoConfig::set_view_matrix`].
+    pub fn set_view_projection(
+        &mut self,
+        view_matrix: impl Into<mint::RowMatrix4<f64>>,
+        projection_matrix: impl Into<mint::RowMatrix4<f64>>,
+    ) {
+        self.invalidate_caches();
+        self.config.set_view_matrix(view_matrix);
+        self.config.set_projection_matrix(projection_matrix);
+        self.config.update_view_projection();
+        self.update_subgizmo_configs();
+    }
+
+    /// Updates the current configuration to each subgizmo, so that they are drawn with it.
+    fn update_subgizmo_configs(&mut self) {
+        for subgizmo in &mut self.subgizmos {
+            subgizmo.update_config(self.config);
         }
     }
 
+    /// Enables or disables snapping, keeping the rest of the configuration.
+    pub fn set_snapping(&mut self, snapping: bool) {
+        self.invalidate_caches();
+        self.config.snapping = snapping;
+    }
+
+    /// Constrains translations to a world space line or path, such as a rail.
+    ///
+    /// While a constraint is set, the translation handles are replaced with a single
+    /// view plane translation handle, which moves the gizmo to the point of the constraint
+    /// closest to the cursor. The position along the constraint is reported in
+    /// [`GizmoResult::Translation`]. `None` removes the constraint.
+    ///
+    /// The translation modes still need to be enabled. If the gizmo is being dragged,
+    /// the new constraint takes effect once the drag ends.
+    pub fn set_translation_constraint(&mut self, constraint: Option<TranslationConstraint>) {
+        self.invalidate_caches();
+        self.translation_constraint = constraint;
+
+        if self.active_subgizmo_id.is_some() {
+            self.rebuild_pending = true;
+        } else {
+            self.rebuild_subgizmos();
+        }
+    }
+
+    /// Geometry that translations are constrained to, if any.
+    pub fn translation_constraint(&self) -> Option<&TranslationConstraint> {
+        self.translation_constraint.as_ref()
+    }
+
+    /// Sets the orientations that rotations snap to when [`GizmoConfig::alignment_snap_angle`]
+    /// is set, in addition to the world axes. Useful for aligning the targets with another object.
+    ///
+    /// Each reference is given with an id, reported in the results when snapped to it.
+    /// Any orientation where the axes of the gizmo are parallel with the axes of
+    /// a reference is snapped to, not only the orientation of the reference itself.
+    pub fn set_alignment_references(
+        &mut self,
+        references: impl IntoIterator<Item = (u64, mint::Quaternion<f64>)>,
+    ) {
+        self.invalidate_caches();
+        self.alignment_references = references
+            .into_iter()
+            .map(|(id, rotation)| (id, DQuat::from(rotation).normalize()))
+            .collect();
+    }
+
+    /// Updates the visuals of the gizmo, keeping the rest of the configuration.
+    pub fn set_visuals(&mut self, visuals: GizmoVisuals) {
+        self.invalidate_caches();
+        self.config.visuals = visuals;
+        self.config.update_focus_distance();
+    }
+
+    /// Status of the latest [`Gizmo::update`] call.
+    ///
+    /// Useful for finding out why the update did not produce a result.
+    pub fn last_update_status(&self) -> UpdateStatus {
+        self.last_update_status
+    }
+
+    /// Events that occurred during the latest [`Gizmo::update`] call.
+    ///
+    /// Useful for feedback that is not visible in the gizmo itself,
+    /// such as playing UI sounds or controller haptics at snapping increments.
+    pub fn events(&self) -> &[GizmoEvent] {
+        &self.events
+    }
+
     /// Was this gizmo focused after the latest [`Gizmo::update`] call.
     pub fn is_focused(&self) -> bool {
         self.subgizmos.iter().any(|subgizmo| subgizmo.is_focused())
     }
 
+    /// Transforms the targets would have if the current drag ended now.
+    ///
+    /// Only available while dragging with [`ApplyMode::OnRelease`],
+    /// in which case [`Gizmo::update`] returns the transforms only once the drag ends.
+    /// Can be used to draw a preview of the targets during the drag.
+    pub fn preview_transforms(&self) -> Option<&[Transform]> {
+        self.pending_result
+            .as_ref()
+            .map(|(_, transforms)| transforms.as_slice())
+    }
+
+    /// Latest result produced during the current drag.
+    ///
+    /// [`Gizmo::update`] returns [`None`] during a drag whenever the pointer has not
+    /// moved enough to change the result. This keeps returning the latest result
+    /// until the drag ends, so that the totals of the drag can be displayed without flickering.
+    /// Results of mouse wheel steps are not retained, as they do not start a drag.
+    pub fn latest_result(&self) -> Option<GizmoResult> {
+        self.latest_result
+    }
+
+    /// Counters of how the gizmo has been interacted with.
+    #[cfg(feature = "analytics")]
+    pub fn stats(&self) -> &crate::stats::GizmoStats {
+        &self.stats
+    }
+
+    /// Resets the counters returned by [`Gizmo::stats`].
+    #[cfg(feature = "analytics")]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Draws the handles of given mode as if they were focused, for the given duration.
+    ///
+    /// Useful for pointing out a handle to the user, for example in tutorials, or
+    /// flashing the axis corresponding to a value typed into an inspector.
+    /// Only affects how the gizmo is drawn, not how it can be interacted with.
+    /// A new highlight replaces the previous one. The highlight is removed by the
+    /// first update after the duration has passed, so that drawing does not depend
+    /// on the current time.
+    pub fn highlight_handle(&mut self, mode: GizmoMode, duration: Duration) {
+        self.invalidate_caches();
+        self.highlight = Some((mode, Instant::now() + duration));
+    }
+
+    /// Removes the highlight set with [`Gizmo::highlight_handle`].
+    pub fn clear_highlight(&mut self) {
+        self.invalidate_caches();
+        self.highlight = None;
+    }
+
+    /// Mode of the handles that are currently highlighted with [`Gizmo::highlight_handle`].
+    pub fn highlighted_mode(&self) -> Option<GizmoMode> {
+        self.highlight.map(|(mode, _)| mode)
+    }
+
+    /// Removes the highlight set with [`Gizmo::highlight_handle`] once its duration has passed.
+    fn expire_highlight(&mut self) {
+        if self
+            .highlight
+            .is_some_and(|(_, until)| Instant::now() >= until)
+        {
+            self.highlight = None;
+            self.invalidate_caches();
+        }
+    }
+
+    /// World space points where the current drag was started and where it currently is,
+    /// as `(start, current)`.
+    ///
+    /// For translations, the current point is where the grabbed point of the handle has moved to.
+    /// Useful for drawing, for example, a line from the grab point or measurements between the points.
+    ///
+    /// Returns `None` if the gizmo is not being dragged, or if the active handle has no such point.
+    pub fn interaction_points(&self) -> Option<(mint::Vector3<f64>, mint::Vector3<f64>)> {
+        let subgizmo = self
+            .subgizmos
+            .iter()
+            .find(|subgizmo| Some(subgizmo.id()) == self.active_subgizmo_id)?;
+
+        subgizmo
+            .interaction_points()
+            .map(|(start, current)| (vec3_to_mint(start), vec3_to_mint(current)))
+    }
+
+    /// Distance from the camera to the focused part of the gizmo along the
+    /// pointer ray, after the latest [`Gizmo::update`] call.
+    ///
+    /// Used to find the frontmost gizmo when the handles of multiple gizmos overlap.
+    pub fn focused_distance(&self) -> Option<f64> {
+        self.focused_distance.map(float_to_f64)
+    }
+
+    /// Depth of the gizmo center in normalized device coordinates.
+    ///
+    /// Gizmos with smaller depth are closer to the camera.
+    pub fn depth(&self) -> f64 {
+        float_to_f64(
+            self.config
+                .view_projection
+                .project_point3(self.config.translation)
+                .z,
+        )
+    }
+
+    /// Normal of the axis plane that faces the camera the most, in the orientation of the gizmo.
+    ///
+    /// This is the plane used by [`GizmoMode::TranslateBestPlane`]. For example, a camera
+    /// looking straight down gives [`GizmoDirection::Y`].
+    pub fn best_translate_plane_normal(&self) -> GizmoDirection {
+        self.config.best_plane_normal()
+    }
+
+    /// Mode of the subgizmo that was focused after the latest [`Gizmo::update`] call.
+    pub fn focused_mode(&self) -> Option<GizmoMode> {
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.is_focused())
+            .map(SubGizmoControl::mode)
+    }
+
+    /// Mode of the subgizmo that was being interacted with
+    /// after the latest [`Gizmo::update`] call.
+    pub fn active_mode(&self) -> Option<GizmoMode> {
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.is_active())
+            .map(SubGizmoControl::mode)
+    }
+
+    /// Handle that is currently being dragged, if any.
+    pub fn active_handle(&self) -> Option<GizmoHandleId> {
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| Some(subgizmo.id()) == self.active_subgizmo_id)
+            .map(GizmoHandleId::new)
+    }
+
+    /// First handle of the given mode, if the mode is enabled.
+    ///
+    /// Modes such as [`GizmoMode::Bounds`] have multiple handles,
+    /// all of which can be found with [`Gizmo::draw_handles`].
+    pub fn handle(&self, mode: GizmoMode) -> Option<GizmoHandleId> {
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.mode() == mode)
+            .map(GizmoHandleId::new)
+    }
+
+    /// Handle under the cursor at given position in window coordinates, if any.
+    ///
+    /// Uses the configuration and targets of the latest update, without changing the
+    /// state of the gizmo. Useful for routing input before calling [`Gizmo::update`],
+    /// such as deciding whether a click should go to the gizmo or to scene selection.
+    ///
+    /// During a drag, the handle being dragged is returned.
+    pub fn pick(&self, cursor_pos: (f32, f32)) -> Option<GizmoHandleId> {
+        if !self.config.viewport.is_finite() {
+            return None;
+        }
+
+        if self.active_subgizmo_id.is_some() {
+            return self.active_handle();
+        }
+
+        // Picking updates the state of the subgizmos, so it is done on copies of them
+        let mut scratch = Self {
+            config: self.config,
+            subgizmos: self.subgizmos.clone(),
+            last_focused_subgizmo_id: self.last_focused_subgizmo_id,
+            ..Default::default()
+        };
+
+        scratch
+            .pick_subgizmo(self.pointer_ray(cursor_pos))
+            .map(|subgizmo| GizmoHandleId::new(subgizmo))
+    }
+
+    /// Whether the handles of the given mode can currently be interacted with.
+    ///
+    /// Handles pointing almost directly towards the camera are faded out and cannot be picked,
+    /// see [`GizmoVisuals::fade_occluded_handles`]. Useful for greying out toolbar buttons of
+    /// axes that cannot be grabbed from the current camera angle. Uses the same visibility
+    /// calculations as picking, with the configuration and targets of the latest update.
+    ///
+    /// Modes with multiple handles, such as [`GizmoMode::Bounds`], are visible if any of their
+    /// handles is visible.
+    pub fn handle_usability(&self, mode: GizmoMode) -> HandleUsability {
+        let ray = self.pointer_ray(self.config.viewport.center().into());
+
+        self.subgizmos
+            .iter()
+            .filter(|subgizmo| subgizmo.mode() == mode)
+            .map(|subgizmo| {
+                // Picking updates the state of the subgizmos, so it is done on copies of them
+                let mut subgizmo = subgizmo.clone();
+                subgizmo.pick(ray);
+
+                if subgizmo.visibility() > 1e-4 {
+                    HandleUsability::Visible
+                } else {
+                    HandleUsability::FadedOut
+                }
+            })
+            .min()
+            .unwrap_or(HandleUsability::Disabled)
+    }
+
+    /// Pick state of every handle for the pointer at given position in window coordinates.
+    ///
+    /// Intended for debugging which handle gets picked, for example when the wrong handle
+    /// is grabbed. Like [`Gizmo::pick`], uses the configuration and targets of the latest
+    /// update without changing the state of the gizmo. The candidates can be visualized
+    /// with [`Gizmo::draw_pick_candidates`].
+    pub fn pick_candidates(&self, cursor_pos: (f32, f32)) -> Vec<PickCandidate> {
+        if !self.config.viewport.is_finite() {
+            return vec![];
+        }
+
+        let ray = self.pointer_ray(cursor_pos);
+        let chosen = self.pick(cursor_pos);
+
+        self.subgizmos
+            .iter()
+            .map(|subgizmo| {
+                // Picking updates the state of the subgizmos, so it is done on copies of them
+                let mut subgizmo = subgizmo.clone();
+                subgizmo.pick(ray);
+
+                let handle = GizmoHandleId::new(&subgizmo);
+                let last_pick = subgizmo.last_pick();
+
+                PickCandidate {
+                    handle,
+                    t: last_pick.map(|pick| float_to_f64(pick.t)),
+                    point: last_pick.map(|pick| vec3_to_mint(pick.subgizmo_point)),
+                    picked: last_pick.is_some_and(|pick| pick.picked),
+                    chosen: chosen == Some(handle),
+                    area: float_to_f64(subgizmo.pick_area()),
+                }
+            })
+            .collect()
+    }
+
+    /// Draws a marker at the point of each pick candidate that has one.
+    ///
+    /// The chosen handle is marked in green, other picked handles in yellow
+    /// and handles that are not under the pointer in red.
+    pub fn draw_pick_candidates(&self, candidates: &[PickCandidate]) -> GizmoDrawData {
+        let radius = Float::from(PICK_MARKER_RADIUS * self.config.scale_factor);
+        let rotation = view_plane_rotation(&self.config);
+
+        candidates
+            .iter()
+            .filter_map(|candidate| {
+                let point = vec3_from_mint(candidate.point?);
+                let color = if candidate.chosen {
+                    Color32::GREEN
+                } else if candidate.picked {
+                    Color32::YELLOW
+                } else {
+                    Color32::RED
+                };
+
+                let shape_builder = ShapeBuidler::new(
+                    self.config.view_projection * FMat4::from_rotation_translation(rotation, point),
+                    self.config.viewport,
+                    self.config.pixels_per_point,
+                );
+
+                Some(GizmoDrawData::from(shape_builder.filled_circle(
+                    radius,
+                    color,
+                    (0.0, Color32::TRANSPARENT),
+                )))
+            })
+            .fold(GizmoDrawData::default(), |draw_data, marker| {
+                draw_data + marker
+            })
+    }
+
+    /// Starts dragging the given handle as if the pointer was pressed on it at
+    /// `start_cursor`, in window coordinates. The handle does not need to be under the cursor.
+    ///
+    /// Useful for driving the gizmo from external UI, such as a toolbar button that starts
+    /// a rotation, or from tests without having to aim the pointer at the handles.
+    ///
+    /// The drag starts in the next [`Gizmo::update`] call. As with pointer interaction,
+    /// [`GizmoInteraction::dragging`] should be set for as long as the drag continues,
+    /// and the handle follows the cursor relative to `start_cursor`.
+    /// The drag ends when `dragging` is no longer set, or with [`Gizmo::end_interaction`].
+    ///
+    /// An ongoing drag is ended first. Returns `false` if the gizmo has no such handle,
+    /// for example because its mode is not enabled.
+    pub fn begin_interaction(&mut self, handle: GizmoHandleId, start_cursor: Pos2) -> bool {
+        self.end_interaction();
+
+        if !self
+            .subgizmos
+            .iter()
+            .any(|subgizmo| subgizmo.id() == handle.id)
+        {
+            return false;
+        }
+
+        self.requested_interaction = Some((handle.id, start_cursor));
+        true
+    }
+
+    /// Ends the current drag as if the pointer was released,
+    /// including a drag that has been requested with [`Gizmo::begin_interaction`].
+    ///
+    /// With [`ApplyMode::OnRelease`], returns the result and the transforms that were
+    /// deferred until the end of the drag. Otherwise the transforms have already been
+    /// returned by [`Gizmo::update`], and `None` is returned.
+    pub fn end_interaction(&mut self) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.invalidate_caches();
+        self.requested_interaction = None;
+
+        if !self.release_active_subgizmo() {
+            return None;
+        }
+
+        self.pending_result.take()
+    }
+
     /// Updates the gizmo based on given interaction information.
     ///
     /// # Examples
@@ -81,12 +672,18 @@ impl Gizmo {
     /// # let cursor_pos = Default::default();
     /// # let drag_started = true;
     /// # let dragging = true;
+    /// # let drag_anywhere = false;
+    /// # let wheel_delta = 0.0;
+    /// # let duplicate = false;
     /// # let mut transforms = vec![];
     ///
     /// let interaction = GizmoInteraction {
     ///     cursor_pos,
     ///     drag_started,
-    ///     dragging
+    ///     dragging,
+    ///     drag_anywhere,
+    ///     wheel_delta,
+    ///     duplicate,
     /// };
     ///
     /// if let Some((_result, new_transforms)) = gizmo.update(interaction, &transforms) {
@@ -107,14 +704,347 @@ impl Gizmo {
         interaction: GizmoInteraction,
         targets: &[Transform],
     ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.update_with_params(interaction, targets, UpdateParams::default())
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// using column-major `f32` matrices as targets.
+    ///
+    /// Returns the result of the interaction with the updated matrices.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_matrices(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[[[f32; 4]; 4]],
+    ) -> Option<(GizmoResult, Vec<[[f32; 4]; 4]>)> {
+        let targets = targets
+            .iter()
+            .copied()
+            .map(Transform::from_mat4_f32)
+            .collect::<Vec<_>>();
+
+        self.update(interaction, &targets)
+            .map(|(result, transforms)| {
+                let matrices = transforms.iter().map(Transform::to_mat4_f32).collect();
+                (result, matrices)
+            })
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// using 2D transforms on the XY plane as targets.
+    ///
+    /// Returns the result of the interaction with the updated 2D transforms.
+    ///
+    /// The targets are transformed as 3D transforms, with the channels that would move them
+    /// off the plane locked. The rotations are continued from the given targets without
+    /// wrapping around, so that a target can be rotated by more than a half turn over a drag.
+    /// Usually the camera looks along the Z axis and only the modes of
+    /// [`GizmoMode::preset_2d`] are enabled.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_2d(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform2d],
+    ) -> Option<(GizmoResult, Vec<Transform2d>)> {
+        let transforms = targets
+            .iter()
+            .map(Transform2d::to_transform)
+            .collect::<Vec<_>>();
+
+        let locked_channels = enum_set!(
+            TransformChannel::TranslateZ
+                | TransformChannel::RotateX
+                | TransformChannel::RotateY
+                | TransformChannel::ScaleZ
+        );
+
+        self.update_with_locked_channels(
+            interaction,
+            &transforms,
+            &vec![locked_channels; targets.len()],
+        )
+        .map(|(result, updated_transforms)| {
+            let updated_targets = updated_transforms
+                .iter()
+                .zip(targets.iter().zip(&transforms))
+                .map(|(updated, (target, transform))| {
+                    let delta =
+                        DQuat::from(updated.rotation) * DQuat::from(transform.rotation).inverse();
+
+                    Transform2d {
+                        rotation: target.rotation + angle_around_axis(delta, DVec3::Z),
+                        ..Transform2d::from_transform(updated)
+                    }
+                })
+                .collect();
+
+            (result, updated_targets)
+        })
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// with targets given relative to their parents.
+    ///
+    /// `parents` contains the world matrix of the parent of each target, in the same
+    /// order as `targets`. Targets without a corresponding entry have no parent.
+    /// The gizmo operates on the world transforms of the targets, and the updated
+    /// transforms are returned relative to the parents again. This way the changes
+    /// are correct even if the parents are rotated or scaled.
+    ///
+    /// Parents with non-uniform scale may introduce shear, which cannot be
+    /// represented by [`Transform`] and is discarded.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_with_parents(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        parents: &[mint::ColumnMatrix4<f64>],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        let parent = |i: usize| {
+            parents
+                .get(i)
+                .map_or(DMat4::IDENTITY, |&parent| parent.into())
+        };
+
+        let world_targets = targets
+            .iter()
+            .enumerate()
+            .map(|(i, target)| Transform::from_mat4(parent(i) * DMat4::from(target.to_mat4())))
+            .collect::<Vec<_>>();
+
+        self.update(interaction, &world_targets)
+            .map(|(result, transforms)| {
+                let local_transforms = transforms
+                    .iter()
+                    .enumerate()
+                    .map(|(i, transform)| {
+                        Transform::from_mat4(parent(i).inverse() * DMat4::from(transform.to_mat4()))
+                    })
+                    .collect();
+
+                (result, local_transforms)
+            })
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// while keeping the locked channels of each target unchanged.
+    ///
+    /// `locked_channels` is given in the same order as `targets`.
+    /// Targets without a corresponding entry have no locked channels.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_with_locked_channels(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        locked_channels: &[EnumSet<TransformChannel>],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.update_with_params(
+            interaction,
+            targets,
+            UpdateParams {
+                locked_channels,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// passing the result through a [`TransformFilter`] before
+    /// the updated transforms are produced.
+    ///
+    /// See [`Gizmo::update_with_locked_channels`].
+    pub fn update_with_filter(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        locked_channels: &[EnumSet<TransformChannel>],
+        filter: &mut dyn TransformFilter,
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.update_with_params(
+            interaction,
+            targets,
+            UpdateParams {
+                locked_channels,
+                filter: Some(filter),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// with optional bounding boxes of the targets for [`GizmoMode::Bounds`]
+    /// and [`SnapMode::BoundsSize`](crate::config::SnapMode::BoundsSize).
+    ///
+    /// `bounds` is given in the same order as `targets`, in the local space of each target.
+    /// Targets without a corresponding entry have no bounds.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_with_bounds(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        bounds: &[Option<Aabb>],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.update_with_params(
+            interaction,
+            targets,
+            UpdateParams {
+                bounds,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// with any combination of the optional inputs of the other update methods.
+    ///
+    /// See [`UpdateParams`] and [`Gizmo::update`].
+    pub fn update_with_params(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        params: UpdateParams,
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        let locked_channels = params.locked_channels;
+
+        match self.update_result(interaction, targets, params)? {
+            UpdateOutcome::Apply(result) => {
+                let updated_targets = self.update_transforms_with_result(
+                    result,
+                    targets,
+                    &self.target_start_transforms,
+                    locked_channels,
+                );
+                Some((result, updated_targets))
+            }
+            UpdateOutcome::Released(result, updated_targets) => Some((result, updated_targets)),
+        }
+    }
+
+    /// Continues the current drag on another set of targets, such as duplicates of the
+    /// original targets created when [`GizmoEvent::DragStarted`] requested duplication.
+    ///
+    /// `targets` are the transforms the new targets had when the drag was started.
+    /// The transformation of the drag so far is applied to them by the following
+    /// updates, which should be given the new targets from then on.
+    /// The gizmo itself is not moved. Has no effect unless a drag is in progress.
+    pub fn rebase_targets(&mut self, targets: &[Transform]) {
+        if self.active_subgizmo_id.is_none() {
+            return;
+        }
+
+        self.target_start_transforms.clear();
+        self.target_start_transforms.extend_from_slice(targets);
+
+        // A deferred result would otherwise still be applied to the previous targets
+        if let Some((result, _)) = self.pending_result.take() {
+            let updated_targets = self.update_transforms_with_result(
+                result,
+                targets,
+                &self.target_start_transforms,
+                &self.pending_locked_channels,
+            );
+            self.pending_result = Some((result, updated_targets));
+        }
+    }
+
+    /// Updates the gizmo based on given interaction information,
+    /// writing the updated transforms directly into `targets`.
+    ///
+    /// Unlike [`Gizmo::update`], no new transforms are allocated, and the buffer
+    /// for the transforms at the start of a drag is reused between drags.
+    /// This avoids allocations every frame when transforming large selections.
+    ///
+    /// Returns the result of the interaction. `targets` are only modified
+    /// when [`Some`] is returned.
+    ///
+    /// See [`Gizmo::update`].
+    pub fn update_in_place(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &mut [Transform],
+    ) -> Option<GizmoResult> {
+        self.update_in_place_with_params(interaction, targets, UpdateParams::default())
+    }
+
+    /// Updates the gizmo like [`Gizmo::update_in_place`],
+    /// with any combination of the optional inputs of the other update methods.
+    ///
+    /// See [`UpdateParams`].
+    pub fn update_in_place_with_params(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &mut [Transform],
+        params: UpdateParams,
+    ) -> Option<GizmoResult> {
+        let locked_channels = params.locked_channels;
+
+        match self.update_result(interaction, targets, params)? {
+            UpdateOutcome::Apply(result) => {
+                for (i, (target, start_transform)) in targets
+                    .iter_mut()
+                    .zip(&self.target_start_transforms)
+                    .enumerate()
+                {
+                    *target = self.transform_with_result(
+                        result,
+                        target,
+                        start_transform,
+                        locked_channels.get(i).copied().unwrap_or_default(),
+                    );
+                }
+                Some(result)
+            }
+            UpdateOutcome::Released(result, updated_targets) => {
+                for (target, updated_target) in targets.iter_mut().zip(updated_targets) {
+                    *target = updated_target;
+                }
+                Some(result)
+            }
+        }
+    }
+
+    /// Updates the gizmo and its subgizmos, without producing the updated transforms
+    /// unless they were deferred until the drag ends.
+    fn update_result(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        params: UpdateParams,
+    ) -> Option<UpdateOutcome> {
+        let UpdateParams {
+            bounds,
+            locked_channels,
+            mut filter,
+        } = params;
+
+        self.invalidate_caches();
+        self.events.clear();
+        self.expire_highlight();
+
         if !self.config.viewport.is_finite() {
+            self.last_update_status = UpdateStatus::NoViewport;
             return None;
         }
 
+        let mut status = UpdateStatus::NotHovered;
+
         // Update the gizmo based on the given target transforms,
         // unless the gizmo is currently being interacted with.
         if self.active_subgizmo_id.is_none() {
             self.config.update_for_targets(targets);
+            self.config.update_bounds(targets, bounds);
+
+            if self
+                .best_plane_normal
+                .is_some_and(|normal| normal != self.config.best_plane_normal())
+            {
+                self.rebuild_subgizmos();
+            }
         }
 
         for subgizmo in &mut self.subgizmos {
@@ -126,34 +1056,119 @@ impl Gizmo {
 
         let force_active = self.config.mode_override.is_some();
 
-        let pointer_ray = self.pointer_ray(Pos2::from(interaction.cursor_pos));
+        let pointer_ray = self.pointer_ray(interaction.cursor_pos);
+
+        // Start the drag requested with `begin_interaction` instead of picking
+        if let Some((id, start_cursor)) = self.requested_interaction.take() {
+            let start_ray = self.pointer_ray((start_cursor.x, start_cursor.y));
+
+            if let Some(subgizmo) = self
+                .subgizmos
+                .iter_mut()
+                .find(|subgizmo| subgizmo.id() == id)
+            {
+                // Picking initializes the drag state of the subgizmo
+                subgizmo.pick(start_ray);
+                subgizmo.set_focused(true);
+                self.activate_subgizmo(id, targets, interaction.duplicate);
+            }
+        }
+
+        // Result of scrolling the mouse wheel over a subgizmo, without dragging
+        let mut wheel_result = None;
 
         // If there is no active subgizmo, find which one of them
         // is under the mouse pointer, if any.
         if self.active_subgizmo_id.is_none() {
-            if let Some(subgizmo) = self.pick_subgizmo(pointer_ray) {
+            self.focused_distance = None;
+
+            let wheel_steps = Float::from(interaction.wheel_delta);
+            let wheel_enabled = self.config.wheel_interaction && wheel_steps != 0.0;
+
+            let picked = self.pick_subgizmo(pointer_ray);
+            let picked_id = picked.as_ref().map(|subgizmo| subgizmo.id());
+
+            if let Some(subgizmo) = picked {
                 subgizmo.set_focused(true);
 
                 // If we started dragging from one of the subgizmos, mark it as active.
                 if interaction.drag_started || force_active {
-                    self.active_subgizmo_id = Some(subgizmo.id());
-                    self.target_start_transforms = targets.to_vec();
-                    self.gizmo_start_transform = self.config.as_transform();
+                    let id = subgizmo.id();
+                    self.activate_subgizmo(id, targets, interaction.duplicate);
+                } else {
+                    status = UpdateStatus::Hovered;
+
+                    if wheel_enabled {
+                        wheel_result = subgizmo.wheel(wheel_steps);
+
+                        // Each wheel step is a snapping increment
+                        if wheel_result.is_some() {
+                            let handle = GizmoHandleId::new(subgizmo);
+                            self.events.push(GizmoEvent::SnapTick(handle));
+                        }
+                    }
+                }
+            } else if interaction.drag_started {
+                // Dragging was started outside of the subgizmos, which
+                // is allowed if drag anywhere mode is configured.
+                if let Some(id) = self
+                    .drag_anywhere_subgizmo_id()
+                    .filter(|_| interaction.drag_anywhere)
+                {
+                    self.activate_subgizmo(id, targets, interaction.duplicate);
+                } else {
+                    status = UpdateStatus::NothingPicked;
                 }
             }
+
+            self.last_focused_subgizmo_id = picked_id;
         }
 
-        let mut result = None;
+        if let Some(result) = wheel_result {
+            // Wheel steps are applied to the current transforms immediately
+            self.target_start_transforms.clear();
+            self.target_start_transforms.extend_from_slice(targets);
+            self.gizmo_start_transform = self.config.as_transform();
+            status = UpdateStatus::Produced(result.kind());
+        }
+
+        let mut result = wheel_result;
+        let mut released = false;
 
         if let Some(subgizmo) = self.active_subgizmo_mut() {
             if interaction.dragging || force_active {
                 subgizmo.set_active(true);
                 subgizmo.set_focused(true);
                 result = subgizmo.update(pointer_ray);
+                status = result.map_or(UpdateStatus::ActiveNoMotion, |result| {
+                    UpdateStatus::Produced(result.kind())
+                });
+
+                if result.is_some() && subgizmo.snap_crossed() {
+                    let handle = GizmoHandleId::new(subgizmo);
+                    self.events.push(GizmoEvent::SnapTick(handle));
+                }
             } else {
-                subgizmo.set_active(false);
-                subgizmo.set_focused(false);
-                self.active_subgizmo_id = None;
+                released = self.release_active_subgizmo();
+            }
+        }
+
+        self.last_update_status = status;
+        self.emit_focus_change();
+
+        if released {
+            if let Some((result, updated_targets)) = self.pending_result.take() {
+                // The drag ended, so the deferred transforms are finally applied.
+                self.last_update_status = UpdateStatus::Produced(result.kind());
+
+                self.config.update_for_targets(&updated_targets);
+                self.config.update_bounds(&updated_targets, bounds);
+
+                for subgizmo in &mut self.subgizmos {
+                    subgizmo.update_config(self.config);
+                }
+
+                return Some(UpdateOutcome::Released(result, updated_targets));
             }
         }
 
@@ -161,6 +1176,7 @@ impl Gizmo {
             // No interaction, no result.
 
             self.config.update_for_targets(targets);
+            self.config.update_bounds(targets, bounds);
 
             for subgizmo in &mut self.subgizmos {
                 subgizmo.update_config(self.config);
@@ -169,30 +1185,207 @@ impl Gizmo {
             return None;
         };
 
+        let result = if self.active_subgizmo_id.is_some() {
+            self.snap_to_alignment(result)
+        } else {
+            result
+        };
+
+        let result = match &mut filter {
+            Some(filter) => filter.filter(result, targets),
+            None => result,
+        };
+
+        #[cfg(feature = "analytics")]
+        self.stats.record_result(&result);
+
         self.update_config_with_result(result);
 
-        let updated_targets =
-            self.update_transforms_with_result(result, targets, &self.target_start_transforms);
+        if self.active_subgizmo_id.is_some() {
+            self.latest_result = Some(result);
+        }
 
-        Some((result, updated_targets))
+        if self.config.apply_mode == ApplyMode::OnRelease && !force_active && wheel_result.is_none()
+        {
+            let updated_targets = self.update_transforms_with_result(
+                result,
+                targets,
+                &self.target_start_transforms,
+                locked_channels,
+            );
+            self.pending_result = Some((result, updated_targets));
+            self.pending_locked_channels.clear();
+            self.pending_locked_channels
+                .extend_from_slice(locked_channels);
+            return None;
+        }
+
+        Some(UpdateOutcome::Apply(result))
+    }
+
+    /// Marks the subgizmo with given id as active, starting a new drag.
+    fn activate_subgizmo(&mut self, id: u64, targets: &[Transform], duplicate_requested: bool) {
+        #[cfg(feature = "analytics")]
+        if let Some(subgizmo) = self.subgizmos.iter().find(|subgizmo| subgizmo.id() == id) {
+            self.stats.record_drag_start(subgizmo.mode());
+        }
+
+        if let Some(subgizmo) = self.subgizmos.iter().find(|subgizmo| subgizmo.id() == id) {
+            let handle = GizmoHandleId::new(subgizmo);
+            self.events.push(GizmoEvent::DragStarted {
+                handle,
+                duplicate_requested,
+            });
+        }
+
+        self.active_subgizmo_id = Some(id);
+        self.last_active_subgizmo_id = Some(id);
+        // Reuse the buffer of the previous drag
+        self.target_start_transforms.clear();
+        self.target_start_transforms.extend_from_slice(targets);
+        self.pending_result = None;
+        self.latest_result = None;
+        self.gizmo_start_transform = self.config.as_transform();
+        self.unaligned_rotation = DQuat::from(self.gizmo_start_transform.rotation);
+    }
+
+    /// Emits [`GizmoEvent::FocusChanged`] if the focused handle differs from
+    /// the one of the previously emitted event.
+    fn emit_focus_change(&mut self) {
+        let focused = self
+            .subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.is_focused())
+            .map(GizmoHandleId::new);
+
+        if focused != self.event_focused_handle {
+            self.event_focused_handle = focused;
+            self.events.push(GizmoEvent::FocusChanged(focused));
+        }
+    }
+
+    /// Marks the active subgizmo as inactive, ending the current drag.
+    ///
+    /// Returns `false` if there was no active subgizmo.
+    fn release_active_subgizmo(&mut self) -> bool {
+        let Some(subgizmo) = self.active_subgizmo_mut() else {
+            return false;
+        };
+
+        subgizmo.set_active(false);
+        subgizmo.set_focused(false);
+        self.active_subgizmo_id = None;
+        self.latest_result = None;
+
+        #[cfg(feature = "analytics")]
+        self.stats.record_drag_end();
+
+        if self.rebuild_pending {
+            self.rebuild_subgizmos();
+        }
+
+        true
+    }
+
+    /// Subgizmo to use when dragging is started outside of the subgizmos.
+    ///
+    /// The most recently active subgizmo is preferred. If there is none,
+    /// the subgizmo of [`GizmoConfig::drag_anywhere_mode`] is used.
+    fn drag_anywhere_subgizmo_id(&self) -> Option<u64> {
+        let default_mode = self.config.drag_anywhere_mode?;
+
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| Some(subgizmo.id()) == self.last_active_subgizmo_id)
+            .or_else(|| {
+                self.subgizmos
+                    .iter()
+                    .find(|subgizmo| subgizmo.mode() == default_mode)
+            })
+            .map(|subgizmo| subgizmo.id())
     }
 
     /// Return all the necessary data to draw the latest gizmo interaction.
     ///
     /// The gizmo draw data consists of vertices in viewport coordinates.
+    /// Same as the flattened [`Gizmo::draw_handles`].
     pub fn draw(&self) -> GizmoDrawData {
+        GizmoDrawData::from_handles(self.draw_handles())
+    }
+
+    /// Like [`Gizmo::draw`], but reuses the draw data of the previous call
+    /// if the gizmo has not changed since.
+    ///
+    /// Together with [`Gizmo::update_if_changed`], this avoids recomputing
+    /// and tessellating an idle gizmo every frame.
+    pub fn draw_cached(&mut self) -> &GizmoDrawData {
+        let draw_data = match self.draw_cache.take() {
+            Some(draw_data) => draw_data,
+            None => self.draw(),
+        };
+
+        self.draw_cache.insert(draw_data)
+    }
+
+    /// Return the draw data of the latest gizmo interaction separately for each handle,
+    /// in the order they should be drawn.
+    ///
+    /// Useful for applying effects to specific handles, such as outlining the focused one.
+    /// Use [`GizmoDrawData::from_handles`] to combine them.
+    pub fn draw_handles(&self) -> Vec<GizmoHandleDrawData> {
         if !self.config.viewport.is_finite() {
-            return GizmoDrawData::default();
+            return vec![];
+        }
+
+        let highlighted_mode = self.highlighted_mode();
+
+        // Mode of the hovered handle, whose axis family is brightened
+        let family_mode = self
+            .subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.is_focused())
+            .map(SubGizmoControl::mode)
+            .filter(|_| {
+                self.config.visuals.highlight_axis_family && self.active_subgizmo_id.is_none()
+            });
+
+        let mut handles = vec![];
+
+        if self.active_subgizmo_id.is_some() {
+            if let InactiveHandleStyle::Fade(alpha) = self.config.visuals.inactive_during_drag {
+                for subgizmo in self.subgizmos.iter().filter(|s| !s.is_active()) {
+                    let mut faded = GizmoHandleDrawData::new(subgizmo);
+                    faded.draw_data.multiply_alpha(alpha);
+                    handles.push(faded);
+                }
+            }
         }
 
-        let mut draw_data = GizmoDrawData::default();
         for subgizmo in &self.subgizmos {
-            if self.active_subgizmo_id.is_none() || subgizmo.is_active() {
-                draw_data += subgizmo.draw();
+            if self.active_subgizmo_id.is_some() && !subgizmo.is_active() {
+                continue;
+            }
+
+            if !subgizmo.is_focused() && Some(subgizmo.mode()) == highlighted_mode {
+                // Drawn as if focused, without affecting the actual focus.
+                let mut subgizmo = subgizmo.clone();
+                subgizmo.set_focused(true);
+                handles.push(GizmoHandleDrawData::new(&subgizmo));
+            } else if family_mode.is_some_and(|mode| subgizmo.mode().is_in_axis_family_of(&mode)) {
+                // Drawn halfway between unfocused and focused.
+                let mut config = self.config;
+                config.visuals.inactive_alpha =
+                    (config.visuals.inactive_alpha + config.visuals.highlight_alpha) * 0.5;
+
+                let mut subgizmo = subgizmo.clone();
+                subgizmo.update_config(config);
+                handles.push(GizmoHandleDrawData::new(&subgizmo));
+            } else {
+                handles.push(GizmoHandleDrawData::new(subgizmo));
             }
         }
 
-        draw_data
+        handles
     }
 
     fn active_subgizmo_mut(&mut self) -> Option<&mut SubGizmo> {
@@ -203,60 +1396,199 @@ impl Gizmo {
         })
     }
 
+    /// Snaps the rotation of the result so that the gizmo orientation is aligned with
+    /// the closest reference, if one is within [`GizmoConfig::alignment_snap_angle`].
+    fn snap_to_alignment(&mut self, result: GizmoResult) -> GizmoResult {
+        let Some(max_angle) = self.config.alignment_snap_angle else {
+            return result;
+        };
+
+        let start_rotation = DQuat::from(self.gizmo_start_transform.rotation);
+        let current_rotation = DQuat::from(self.config.as_transform().rotation);
+
+        // World space axis of single axis rotations
+        let axis = match result {
+            GizmoResult::Rotation {
+                axis, is_view_axis, ..
+            } => Some(match self.config.orientation() {
+                GizmoOrientation::Local if !is_view_axis => start_rotation * DVec3::from(axis),
+                _ => DVec3::from(axis),
+            }),
+            GizmoResult::Arcball { .. } => None,
+            _ => return result,
+        };
+
+        let delta = match result {
+            GizmoResult::Rotation { delta, .. } => {
+                DQuat::from_axis_angle(axis.unwrap_or_default(), delta)
+            }
+            GizmoResult::Arcball { delta, .. } => DQuat::from(delta),
+            _ => return result,
+        };
+        self.unaligned_rotation = (delta * self.unaligned_rotation).normalize();
+
+        let references = std::iter::once((ReferenceId::World, DQuat::IDENTITY)).chain(
+            self.alignment_references
+                .iter()
+                .map(|&(id, rotation)| (ReferenceId::Custom(id), rotation)),
+        );
+
+        let snapped = references
+            .flat_map(|(id, reference)| {
+                axis_aligned_rotations().map(move |aligned| (id, reference * aligned))
+            })
+            .filter(|(_, aligned)| {
+                // Single axis rotations can only reach orientations around the axis
+                axis.map_or(true, |axis| {
+                    let rotation = *aligned * start_rotation.inverse();
+                    DVec3::new(rotation.x, rotation.y, rotation.z)
+                        .cross(axis)
+                        .length()
+                        < 1e-6
+                })
+            })
+            .map(|(id, aligned)| (id, aligned, aligned.angle_between(self.unaligned_rotation)))
+            .filter(|(_, _, angle)| *angle <= max_angle as f64)
+            .min_by(|(_, _, first), (_, _, second)| first.total_cmp(second));
+
+        let (snapped_to, rotation) = snapped
+            .map_or((None, self.unaligned_rotation), |(id, aligned, _)| {
+                (Some(id), aligned)
+            });
+        let delta = rotation * current_rotation.inverse();
+
+        match result {
+            GizmoResult::Rotation {
+                axis: local_axis,
+                total,
+                is_view_axis,
+                pivot,
+                ..
+            } => {
+                let axis = axis.unwrap_or_default();
+
+                // The total angle is counted in the opposite direction.
+                // Correcting it keeps the full revolutions of the drag.
+                let total =
+                    total - angle_around_axis(rotation * self.unaligned_rotation.inverse(), axis);
+
+                GizmoResult::Rotation {
+                    axis: local_axis,
+                    delta: angle_around_axis(delta, axis),
+                    total,
+                    revolutions: full_revolutions(total),
+                    is_view_axis,
+                    pivot,
+                    snapped_to,
+                }
+            }
+            GizmoResult::Arcball { pivot, .. } => GizmoResult::Arcball {
+                delta: delta.into(),
+                total: (rotation * start_rotation.inverse()).into(),
+                pivot,
+                snapped_to,
+            },
+            _ => result,
+        }
+    }
+
     fn update_transforms_with_result(
         &self,
         result: GizmoResult,
         transforms: &[Transform],
         start_transforms: &[Transform],
+        locked_channels: &[EnumSet<TransformChannel>],
     ) -> Vec<Transform> {
         transforms
             .iter()
             .zip(start_transforms)
-            .map(|(transform, start_transform)| match result {
-                GizmoResult::Rotation {
-                    axis,
-                    delta,
-                    total: _,
-                    is_view_axis,
-                } => self.update_rotation(transform, axis, delta, is_view_axis),
-                GizmoResult::Translation { delta, total: _ } => {
-                    self.update_translation(delta, transform, start_transform)
-                }
-                GizmoResult::Scale { total } => {
-                    Self::update_scale(transform, start_transform, total)
-                }
-                GizmoResult::Arcball { delta, total: _ } => {
-                    self.update_rotation_quat(transform, delta.into())
-                }
+            .enumerate()
+            .map(|(i, (transform, start_transform))| {
+                let locked_channels = locked_channels.get(i).copied().unwrap_or_default();
+
+                self.transform_with_result(result, transform, start_transform, locked_channels)
             })
             .collect()
     }
 
+    fn transform_with_result(
+        &self,
+        result: GizmoResult,
+        transform: &Transform,
+        start_transform: &Transform,
+        locked_channels: EnumSet<TransformChannel>,
+    ) -> Transform {
+        let new_transform = match result {
+            GizmoResult::Rotation {
+                axis,
+                delta,
+                is_view_axis,
+                pivot,
+                ..
+            } => self.update_rotation(transform, axis, delta, is_view_axis, pivot, locked_channels),
+            GizmoResult::Translation { delta, .. } => {
+                self.update_translation(delta, transform, start_transform)
+            }
+            GizmoResult::Scale { delta: _, total } => {
+                self.update_scale(transform, start_transform, total)
+            }
+            GizmoResult::Arcball { delta, pivot, .. } => {
+                Self::update_rotation_quat(transform, delta.into(), pivot, locked_channels)
+            }
+            GizmoResult::Direction { delta, .. } => {
+                Self::update_rotation_quat(transform, delta.into(), None, locked_channels)
+            }
+            GizmoResult::Bounds {
+                scale,
+                rotation,
+                anchor,
+            } => Self::update_bounds(start_transform, scale, rotation, anchor),
+        };
+
+        keep_locked_channels(transform, new_transform, locked_channels)
+    }
+
     fn update_rotation(
         &self,
         transform: &Transform,
         axis: mint::Vector3<f64>,
         delta: f64,
         is_view_axis: bool,
+        pivot: Option<mint::Vector3<f64>>,
+        locked_channels: EnumSet<TransformChannel>,
     ) -> Transform {
         let axis = match self.config.orientation() {
             GizmoOrientation::Local if !is_view_axis => {
-                DQuat::from(transform.rotation) * DVec3::from(axis)
+                let rotation = match self.config.local_rotation_axes {
+                    LocalRotationAxes::PerTarget => DQuat::from(transform.rotation),
+                    LocalRotationAxes::Gizmo => DQuat::from(self.gizmo_start_transform.rotation),
+                };
+                rotation * DVec3::from(axis)
             }
             _ => DVec3::from(axis),
         };
 
         let delta = DQuat::from_axis_angle(axis, delta);
 
-        self.update_rotation_quat(transform, delta)
+        Self::update_rotation_quat(transform, delta, pivot, locked_channels)
     }
 
-    fn update_rotation_quat(&self, transform: &Transform, delta: DQuat) -> Transform {
-        let translation = match self.config.pivot_point {
-            TransformPivotPoint::MedianPoint => (self.config.translation
-                + delta * (DVec3::from(transform.translation) - self.config.translation))
-                .into(),
-            TransformPivotPoint::IndividualOrigins => transform.translation,
+    fn update_rotation_quat(
+        transform: &Transform,
+        delta: DQuat,
+        pivot: Option<mint::Vector3<f64>>,
+        locked_channels: EnumSet<TransformChannel>,
+    ) -> Transform {
+        // Locked rotation is removed before orbiting around the pivot,
+        // so that the locked axes do not move the target either
+        let delta = without_locked_rotation(delta, locked_channels);
+
+        let translation = match pivot {
+            Some(pivot) => {
+                let pivot = DVec3::from(pivot);
+                (pivot + delta * (DVec3::from(transform.translation) - pivot)).into()
+            }
+            None => transform.translation,
         };
 
         Transform {
@@ -285,23 +1617,60 @@ impl Gizmo {
     }
 
     fn update_scale(
+        &self,
         transform: &Transform,
         start_transform: &Transform,
         scale: mint::Vector3<f64>,
     ) -> Transform {
+        let scale = match self.config.orientation() {
+            GizmoOrientation::Global => {
+                closest_axis_scale(DQuat::from(start_transform.rotation), DVec3::from(scale))
+            }
+            GizmoOrientation::Local => DVec3::from(scale),
+        };
+
         Transform {
-            scale: (DVec3::from(start_transform.scale) * DVec3::from(scale)).into(),
+            scale: self
+                .config
+                .zero_scale_policy
+                .apply(DVec3::from(start_transform.scale), scale)
+                .into(),
             rotation: transform.rotation,
             translation: transform.translation,
         }
     }
 
+    fn update_bounds(
+        start_transform: &Transform,
+        scale: mint::Vector3<f64>,
+        rotation: mint::Quaternion<f64>,
+        anchor: mint::Vector3<f64>,
+    ) -> Transform {
+        let scale = DVec3::from(scale);
+        let rotation = DQuat::from(rotation);
+        let anchor = DVec3::from(anchor);
+
+        let offset = rotation.inverse() * (DVec3::from(start_transform.translation) - anchor);
+        let translation = anchor + rotation * (offset * scale);
+
+        // Scale is given along the bounds axes, which may not match the target's local axes
+        let local_rotation = rotation.inverse() * DQuat::from(start_transform.rotation);
+
+        Transform {
+            scale: (DVec3::from(start_transform.scale) * closest_axis_scale(local_rotation, scale))
+                .into(),
+            rotation: start_transform.rotation,
+            translation: translation.into(),
+        }
+    }
+
     fn update_config_with_result(&mut self, result: GizmoResult) {
-        let new_config_transform = self.update_transforms_with_result(
+        let new_config_transform = self.transform_with_result(
             result,
-            &[self.config.as_transform()],
-            &[self.gizmo_start_transform],
-        )[0];
+            &self.config.as_transform(),
+            &self.gizmo_start_transform,
+            EnumSet::empty(),
+        );
 
         self.config.update_transform(new_config_transform);
     }
@@ -317,15 +1686,53 @@ impl Gizmo {
             });
         }
 
-        self.subgizmos
+        let mut candidates = self
+            .subgizmos
             .iter_mut()
-            .filter_map(|subgizmo| subgizmo.pick(ray).map(|t| (t, subgizmo)))
-            .min_by(|(first, _), (second, _)| {
-                first
-                    .partial_cmp(second)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+            .filter_map(|subgizmo| {
+                let t = subgizmo.pick(ray)?;
+                Some((subgizmo.pick_area(), t, subgizmo))
+            })
+            .collect::<Vec<_>>();
+
+        // Smaller subgizmos are preferred, since larger ones may overlap them.
+        // If the areas are equal, the subgizmo closest to the camera is chosen.
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(
+                |(_, (first_area, first_t, _)), (_, (second_area, second_t, _))| {
+                    first_area
+                        .partial_cmp(second_area)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(
+                            first_t
+                                .partial_cmp(second_t)
+                                .unwrap_or(std::cmp::Ordering::Equal),
+                        )
+                },
+            )
+            .map(|(i, _)| i)?;
+
+        // The previously focused subgizmo keeps the focus, unless
+        // the best candidate is better by more than the focus margin.
+        let keep_factor = 1.0 - Float::from(self.config.focus_margin.clamp(0.0, 1.0));
+        let index = candidates
+            .iter()
+            .position(|(_, _, subgizmo)| Some(subgizmo.id()) == self.last_focused_subgizmo_id)
+            .filter(|&focused| {
+                let (best_area, best_t, _) = candidates[best];
+                let (focused_area, focused_t, _) = candidates[focused];
+
+                best_area >= focused_area * keep_factor
+                    && (best_area < focused_area || best_t >= focused_t * keep_factor)
             })
-            .map(|(_, subgizmo)| subgizmo)
+            .unwrap_or(best);
+
+        let (_, t, subgizmo) = candidates.swap_remove(index);
+        self.focused_distance = Some(t);
+
+        Some(subgizmo)
     }
 
     /// Get all modes that are currently enabled
@@ -336,6 +1743,7 @@ impl Gizmo {
     }
 
     /// Adds rotation subgizmos
+    #[cfg(feature = "rotate")]
     fn add_rotation(&mut self) {
         let modes = self.enabled_modes();
 
@@ -386,18 +1794,41 @@ impl Gizmo {
                 .into(),
             );
         }
+    }
 
-        if modes.contains(GizmoMode::Arcball) {
+    /// Adds the arcball subgizmo
+    #[cfg(feature = "arcball")]
+    fn add_arcball(&mut self) {
+        if self.enabled_modes().contains(GizmoMode::Arcball) {
             self.subgizmos
                 .push(ArcballSubGizmo::new(self.config, ()).into());
         }
     }
 
+    /// Adds the direction subgizmo
+    fn add_direction(&mut self) {
+        self.subgizmos
+            .push(DirectionSubGizmo::new(self.config, ()).into());
+    }
+
     /// Adds translation subgizmos
     fn add_translation(&mut self) {
         let modes = self.enabled_modes();
 
-        if modes.contains(GizmoMode::TranslateX) {
+        if let Some(constraint) = &self.translation_constraint {
+            if modes
+                .iter()
+                .any(|mode| mode.kind() == GizmoModeKind::Translate)
+            {
+                self.subgizmos
+                    .push(ConstrainedSubGizmo::new(self.config, constraint).into());
+            }
+            return;
+        }
+
+        if modes.contains(GizmoMode::TranslateX)
+            && !self.add_compass_arrows(GizmoMode::TranslateX, GizmoDirection::X)
+        {
             self.subgizmos.push(
                 TranslationSubGizmo::new(
                     self.config,
@@ -411,7 +1842,9 @@ impl Gizmo {
             );
         }
 
-        if modes.contains(GizmoMode::TranslateY) {
+        if modes.contains(GizmoMode::TranslateY)
+            && !self.add_compass_arrows(GizmoMode::TranslateY, GizmoDirection::Y)
+        {
             self.subgizmos.push(
                 TranslationSubGizmo::new(
                     self.config,
@@ -425,7 +1858,9 @@ impl Gizmo {
             );
         }
 
-        if modes.contains(GizmoMode::TranslateZ) {
+        if modes.contains(GizmoMode::TranslateZ)
+            && !self.add_compass_arrows(GizmoMode::TranslateZ, GizmoDirection::Z)
+        {
             self.subgizmos.push(
                 TranslationSubGizmo::new(
                     self.config,
@@ -494,9 +1929,64 @@ impl Gizmo {
                 .into(),
             );
         }
+
+        if modes.contains(GizmoMode::TranslateBestPlane) {
+            let normal = self.config.best_plane_normal();
+            self.best_plane_normal = Some(normal);
+
+            // Plane handles are identified by their normal
+            let plane_mode = match normal {
+                GizmoDirection::X => GizmoMode::TranslateXY,
+                GizmoDirection::Y => GizmoMode::TranslateXZ,
+                _ => GizmoMode::TranslateYZ,
+            };
+
+            if !modes.contains(plane_mode) {
+                self.subgizmos.push(
+                    TranslationSubGizmo::new(
+                        self.config,
+                        TranslationParams {
+                            mode: GizmoMode::TranslateBestPlane,
+                            direction: normal,
+                            transform_kind: TransformKind::Plane,
+                        },
+                    )
+                    .into(),
+                );
+            }
+        }
+    }
+
+    /// Adds a pair of compass arrows along the given axis, if a ground plane
+    /// is configured and the axis lies on it. Returns whether the arrows were added.
+    fn add_compass_arrows(&mut self, mode: GizmoMode, direction: GizmoDirection) -> bool {
+        let Some(ground_plane) = self.config.ground_plane else {
+            return false;
+        };
+
+        if ground_plane.normal_axis() == direction {
+            return false;
+        }
+
+        for sign in [1, -1] {
+            self.subgizmos.push(
+                CompassSubGizmo::new(
+                    self.config,
+                    CompassParams {
+                        mode,
+                        direction,
+                        sign,
+                    },
+                )
+                .into(),
+            );
+        }
+
+        true
     }
 
     /// Adds scale subgizmos
+    #[cfg(feature = "scale")]
     fn add_scale(&mut self) {
         let modes = self.enabled_modes();
 
@@ -542,7 +2032,7 @@ impl Gizmo {
             );
         }
 
-        if modes.contains(GizmoMode::ScaleUniform) && !modes.contains(GizmoMode::RotateView) {
+        if modes.contains(GizmoMode::ScaleUniform) {
             self.subgizmos.push(
                 ScaleSubGizmo::new(
                     self.config,
@@ -554,6 +2044,22 @@ impl Gizmo {
                 )
                 .into(),
             );
+
+            if self.config.uniform_scale_corners {
+                for direction in [GizmoDirection::X, GizmoDirection::Y, GizmoDirection::Z] {
+                    self.subgizmos.push(
+                        ScaleSubGizmo::new(
+                            self.config,
+                            ScaleParams {
+                                mode: GizmoMode::ScaleUniform,
+                                direction,
+                                transform_kind: TransformKind::Corner,
+                            },
+                        )
+                        .into(),
+                    );
+                }
+            }
         }
 
         if modes.contains(GizmoMode::ScaleXY) && !modes.contains(GizmoMode::TranslateXY) {
@@ -599,24 +2105,121 @@ impl Gizmo {
         }
     }
 
-    /// Calculate a world space ray from given screen space position
-    fn pointer_ray(&self, screen_pos: Pos2) -> Ray {
-        let mat = self.config.view_projection.inverse();
-        let origin = screen_to_world(self.config.viewport, mat, screen_pos, -1.0);
-        let target = screen_to_world(self.config.viewport, mat, screen_pos, 1.0);
-
-        let direction = target.sub(origin).normalize();
+    /// Adds bounds subgizmos, one for each face and corner of the bounds
+    #[cfg(feature = "scale")]
+    fn add_bounds(&mut self) {
+        if !self.enabled_modes().contains(GizmoMode::Bounds) {
+            return;
+        }
 
-        Ray {
-            screen_pos,
-            origin,
-            direction,
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let handle = [x, y, z];
+                    let non_zero = handle.iter().filter(|side| **side != 0).count();
+
+                    // Edges are not handled
+                    if non_zero == 1 || non_zero == 3 {
+                        self.subgizmos
+                            .push(BoundsSubGizmo::new(self.config, BoundsParams { handle }).into());
+                    }
+                }
+            }
         }
     }
+
+    /// World space ray the gizmo uses for picking and dragging
+    /// when the cursor is at the given position in window coordinates.
+    ///
+    /// Uses the view, projection and viewport of the latest config.
+    /// The cursor position is mapped to the viewport with [`GizmoConfig::display_to_viewport`].
+    pub fn pointer_ray(&self, cursor_pos: (f32, f32)) -> Ray {
+        Ray::from_screen_pos_internal(
+            self.config.viewport,
+            self.config.inverse_view_projection,
+            self.config.display_to_viewport(Pos2::from(cursor_pos)),
+        )
+    }
+}
+
+/// Outcome of [`Gizmo::update_result`] that produced a result
+enum UpdateOutcome {
+    /// The result is to be applied to the targets
+    Apply(GizmoResult),
+    /// The drag ended, and the deferred transforms are to be applied to the targets
+    Released(GizmoResult, Vec<Transform>),
+}
+
+/// Reverts the locked channels of `new_transform` back to the values of `transform`.
+fn keep_locked_channels(
+    transform: &Transform,
+    new_transform: Transform,
+    locked_channels: EnumSet<TransformChannel>,
+) -> Transform {
+    if locked_channels.is_empty() {
+        return new_transform;
+    }
+
+    let is_locked = |x, y, z| {
+        BVec3::new(
+            locked_channels.contains(x),
+            locked_channels.contains(y),
+            locked_channels.contains(z),
+        )
+    };
+
+    let translation = DVec3::select(
+        is_locked(
+            TransformChannel::TranslateX,
+            TransformChannel::TranslateY,
+            TransformChannel::TranslateZ,
+        ),
+        transform.translation.into(),
+        new_transform.translation.into(),
+    );
+
+    let scale = DVec3::select(
+        is_locked(
+            TransformChannel::ScaleX,
+            TransformChannel::ScaleY,
+            TransformChannel::ScaleZ,
+        ),
+        transform.scale.into(),
+        new_transform.scale.into(),
+    );
+
+    let rotation = DQuat::from(transform.rotation);
+    let delta = DQuat::from(new_transform.rotation) * rotation.inverse();
+    let rotation = (without_locked_rotation(delta, locked_channels) * rotation).into();
+
+    Transform {
+        scale: scale.into(),
+        rotation,
+        translation: translation.into(),
+    }
+}
+
+/// Removes the components of the world space rotation that are around locked axes
+fn without_locked_rotation(delta: DQuat, locked_channels: EnumSet<TransformChannel>) -> DQuat {
+    let rotation_locked = BVec3::new(
+        locked_channels.contains(TransformChannel::RotateX),
+        locked_channels.contains(TransformChannel::RotateY),
+        locked_channels.contains(TransformChannel::RotateZ),
+    );
+
+    if rotation_locked.any() {
+        DQuat::from_scaled_axis(DVec3::select(
+            rotation_locked,
+            DVec3::ZERO,
+            delta.to_scaled_axis(),
+        ))
+    } else {
+        delta
+    }
 }
 
 /// Information needed for interacting with the gizmo.
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct GizmoInteraction {
     /// Current cursor position in window coordinates.
     pub cursor_pos: (f32, f32),
@@ -628,6 +2231,24 @@ pub struct GizmoInteraction {
     /// Usually this is set to true whenever the primary mouse
     /// button is being pressed.
     pub dragging: bool,
+    /// Whether dragging may be started anywhere in the viewport, instead of only on the gizmo.
+    /// Usually this is set to true while a modifier key is held.
+    ///
+    /// Has no effect unless [`GizmoConfig::drag_anywhere_mode`] is set.
+    pub drag_anywhere: bool,
+    /// Number of mouse wheel steps scrolled this frame, positive when scrolled up.
+    ///
+    /// Scrolling over an axis arrow translates along the axis by the snapping distance,
+    /// over a rotation handle rotates by [`GizmoConfig::snap_angle`], and over a scale handle
+    /// scales by [`GizmoConfig::snap_scale`], per step. Has no effect during a drag,
+    /// or unless [`GizmoConfig::wheel_interaction`] is enabled.
+    pub wheel_delta: f32,
+    /// Whether the targets should be duplicated when dragging is started this frame,
+    /// with the drag continuing on the duplicates.
+    /// Usually this is set to true while a modifier key is held.
+    ///
+    /// Reported with [`GizmoEvent::DragStarted`], see [`Gizmo::rebase_targets`].
+    pub duplicate: bool,
 }
 
 /// Result of a gizmo transformation
@@ -640,16 +2261,32 @@ pub enum GizmoResult {
         delta: f64,
         /// Total rotation angle of the gizmo interaction
         total: f64,
+        /// Number of full rotations in the total angle, with the same sign.
+        /// Useful for mapping rotations to parameters such as screw depth.
+        revolutions: i32,
         /// Whether we are rotating along the view axis
         is_view_axis: bool,
+        /// Point the targets are rotated around, in world space.
+        /// `None` if each target is rotated around its own origin.
+        /// See [`TransformPivotPoint`](crate::config::TransformPivotPoint).
+        pivot: Option<mint::Vector3<f64>>,
+        /// Reference the orientation of the gizmo was snapped to,
+        /// see [`GizmoConfig::alignment_snap_angle`]
+        snapped_to: Option<ReferenceId>,
     },
     Translation {
         /// The latest translation delta
         delta: mint::Vector3<f64>,
         /// Total translation of the gizmo interaction
         total: mint::Vector3<f64>,
+        /// Position along the [`TranslationConstraint`](crate::config::TranslationConstraint),
+        /// if the translation is constrained
+        constraint_position: Option<f64>,
     },
     Scale {
+        /// The latest scale delta. Multiplying the scale of the previous
+        /// update with it gives the total scale.
+        delta: mint::Vector3<f64>,
         /// Total scale of the gizmo interaction
         total: mint::Vector3<f64>,
     },
@@ -658,7 +2295,349 @@ pub enum GizmoResult {
         delta: mint::Quaternion<f64>,
         /// Total rotation of the gizmo interaction
         total: mint::Quaternion<f64>,
+        /// Point the targets are rotated around, in world space.
+        /// `None` if each target is rotated around its own origin.
+        /// See [`TransformPivotPoint`](crate::config::TransformPivotPoint).
+        pivot: Option<mint::Vector3<f64>>,
+        /// Reference the orientation of the gizmo was snapped to,
+        /// see [`GizmoConfig::alignment_snap_angle`]
+        snapped_to: Option<ReferenceId>,
+    },
+    Direction {
+        /// The edited unit direction, in world space
+        direction: mint::Vector3<f64>,
+        /// The latest rotation delta of the direction
+        delta: mint::Quaternion<f64>,
+        /// Total rotation of the direction during the gizmo interaction
+        total: mint::Quaternion<f64>,
+    },
+    Bounds {
+        /// Total scale of the bounds along the rotated axes
+        scale: mint::Vector3<f64>,
+        /// Rotation of the bounds axes
+        rotation: mint::Quaternion<f64>,
+        /// Point of the bounds that stays in place, in world space
+        anchor: mint::Vector3<f64>,
+    },
+}
+
+/// Optional inputs of [`Gizmo::update_with_params`] besides the targets.
+///
+/// The inputs of each target are given in the same order as the targets.
+/// Targets without a corresponding entry have no bounds and no locked channels.
+///
+/// # Examples
+///
+/// ```
+/// use transform_gizmo::config::TransformChannel;
+/// use transform_gizmo::math::{Aabb, Transform};
+/// use transform_gizmo::prelude::*;
+///
+/// let mut gizmo = Gizmo::default();
+/// let targets = [Transform::default()];
+/// let bounds = [Some(Aabb::new([-1.0; 3], [1.0; 3]))];
+///
+/// let result = gizmo.update_with_params(
+///     GizmoInteraction::default(),
+///     &targets,
+///     UpdateParams {
+///         bounds: &bounds,
+///         locked_channels: &[enum_set!(TransformChannel::TranslateZ)],
+///         ..Default::default()
+///     },
+/// );
+///
+/// assert!(result.is_none());
+/// ```
+#[derive(Default)]
+pub struct UpdateParams<'a> {
+    /// Bounding boxes of the targets, in the local space of each target.
+    /// See [`Gizmo::update_with_bounds`].
+    pub bounds: &'a [Option<Aabb>],
+    /// Channels of the targets that are kept unchanged.
+    /// See [`Gizmo::update_with_locked_channels`].
+    pub locked_channels: &'a [EnumSet<TransformChannel>],
+    /// Filter the result is passed through before the updated transforms are produced.
+    /// See [`Gizmo::update_with_filter`].
+    pub filter: Option<&'a mut dyn TransformFilter>,
+}
+
+/// Post-processes gizmo results before the updated transforms are produced.
+///
+/// Can be used for constraining the transformations, for example
+/// to prevent collisions. Implemented for closures with a matching signature.
+pub trait TransformFilter {
+    /// Returns the result to use for updating the targets,
+    /// given the result of the interaction and the targets.
+    fn filter(&mut self, result: GizmoResult, targets: &[Transform]) -> GizmoResult;
+}
+
+impl<F> TransformFilter for F
+where
+    F: FnMut(GizmoResult, &[Transform]) -> GizmoResult,
+{
+    fn filter(&mut self, result: GizmoResult, targets: &[Transform]) -> GizmoResult {
+        self(result, targets)
+    }
+}
+
+impl GizmoResult {
+    /// Kind of the transformation
+    pub fn kind(&self) -> GizmoModeKind {
+        match self {
+            Self::Rotation { .. } => GizmoModeKind::Rotate,
+            Self::Translation { .. } => GizmoModeKind::Translate,
+            Self::Scale { .. } => GizmoModeKind::Scale,
+            Self::Arcball { .. } => GizmoModeKind::Arcball,
+            Self::Bounds { .. } => GizmoModeKind::Bounds,
+            Self::Direction { .. } => GizmoModeKind::Direction,
+        }
+    }
+
+    /// Total rotation of a [`GizmoResult::Rotation`], [`GizmoResult::Arcball`] or
+    /// [`GizmoResult::Direction`] result as a unit axis and an angle in radians,
+    /// in the range [0, π]. `None` for the other kinds of results.
+    ///
+    /// The targets of a [`GizmoResult::Rotation`] are rotated by the negated `total`
+    /// around its `axis`, so the rotation is given in the same space as the `axis`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DQuat, DVec3};
+    /// # use transform_gizmo::GizmoResult;
+    /// let total = DQuat::from_rotation_z(-0.5);
+    /// let result = GizmoResult::Arcball {
+    ///     delta: total.into(),
+    ///     total: total.into(),
+    ///     pivot: None,
+    ///     snapped_to: None,
+    /// };
+    ///
+    /// let (axis, angle) = result.axis_angle().unwrap();
+    /// assert!(DVec3::from(axis).abs_diff_eq(DVec3::NEG_Z, 1e-9));
+    /// assert!((angle - 0.5).abs() < 1e-9);
+    /// ```
+    pub fn axis_angle(&self) -> Option<(mint::Vector3<f64>, f64)> {
+        let mut rotation = DQuat::from(self.total_rotation()?).normalize();
+
+        // Both quaternions represent the same rotation, the other one by the longer way around
+        if rotation.w < 0.0 {
+            rotation = -rotation;
+        }
+
+        let (axis, angle) = rotation.to_axis_angle();
+        Some((axis.into(), angle))
+    }
+
+    /// Total rotation of the result as Euler angles in radians,
+    /// see [`GizmoResult::axis_angle`]. `None` for results that are not rotations.
+    ///
+    /// The components of the returned vector are the angles around the X, Y and Z axes,
+    /// regardless of the order. See [`EulerOrder`] for how the angles are combined.
+    /// Use [`GizmoResult::euler_near`] for angles that do not wrap around during a drag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DQuat, DVec3};
+    /// # use transform_gizmo::{EulerOrder, GizmoResult};
+    /// // Yaw around Y, then pitch around the rotated X axis
+    /// let total = DQuat::from_rotation_y(0.5) * DQuat::from_rotation_x(0.25);
+    /// let result = GizmoResult::Arcball {
+    ///     delta: total.into(),
+    ///     total: total.into(),
+    ///     pivot: None,
+    ///     snapped_to: None,
+    /// };
+    ///
+    /// let angles = DVec3::from(result.euler(EulerOrder::YXZ).unwrap());
+    /// assert!(angles.abs_diff_eq(DVec3::new(0.25, 0.5, 0.0), 1e-9));
+    /// ```
+    pub fn euler(&self, order: EulerOrder) -> Option<mint::Vector3<f64>> {
+        let rotation = DQuat::from(self.total_rotation()?).normalize();
+        Some(euler_angles(rotation, order).into())
+    }
+
+    /// Same as [`GizmoResult::euler`], but picks the equivalent angles closest to `previous`,
+    /// without wrapping them around to the range [-π, π].
+    ///
+    /// Giving the angles of the previous update of a drag as `previous` keeps the angles
+    /// continuous during the drag, so that for example a rotation by one and a half turns
+    /// around a single axis gives an angle of 3π instead of π.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DQuat, DVec3};
+    /// # use transform_gizmo::{EulerOrder, GizmoResult};
+    /// let mut angles = DVec3::ZERO;
+    ///
+    /// for step in 1..=8 {
+    ///     let total = DQuat::from_rotation_z(step as f64 * 0.5);
+    ///     let result = GizmoResult::Arcball {
+    ///         delta: DQuat::from_rotation_z(0.5).into(),
+    ///         total: total.into(),
+    ///         pivot: None,
+    ///         snapped_to: None,
+    ///     };
+    ///
+    ///     angles = result.euler_near(EulerOrder::XYZ, angles.into()).unwrap().into();
+    /// }
+    ///
+    /// assert!(angles.abs_diff_eq(DVec3::new(0.0, 0.0, 4.0), 1e-9));
+    /// ```
+    pub fn euler_near(
+        &self,
+        order: EulerOrder,
+        previous: mint::Vector3<f64>,
+    ) -> Option<mint::Vector3<f64>> {
+        let rotation = DQuat::from(self.total_rotation()?).normalize();
+        let angles = euler_angles(rotation, order);
+        let previous = DVec3::from(previous);
+
+        // The same rotation is given by the angles with the middle angle mirrored
+        let [i, j, k] = order.axes();
+        let mut mirrored = angles;
+        mirrored[i] += PI;
+        mirrored[j] = PI - angles[j];
+        mirrored[k] += PI;
+
+        let unwrap = |angles: DVec3| {
+            let turns = ((previous - angles) / TAU).round();
+            angles + turns * TAU
+        };
+
+        let (angles, mirrored) = (unwrap(angles), unwrap(mirrored));
+        let closest = if mirrored.distance_squared(previous) < angles.distance_squared(previous) {
+            mirrored
+        } else {
+            angles
+        };
+
+        Some(closest.into())
+    }
+
+    fn total_rotation(&self) -> Option<mint::Quaternion<f64>> {
+        match *self {
+            Self::Rotation { axis, total, .. } => {
+                // Results rotate by the negated total angle
+                Some(DQuat::from_axis_angle(DVec3::from(axis).normalize(), -total).into())
+            }
+            Self::Arcball { total, .. } | Self::Direction { total, .. } => Some(total),
+            _ => None,
+        }
+    }
+}
+
+/// Order of the rotations of Euler angles, see [`GizmoResult::euler`].
+///
+/// The rotations are intrinsic, each one around the axes rotated by the previous ones.
+/// For example [`EulerOrder::YXZ`] is a rotation around Y, followed by a rotation
+/// around the rotated X axis, followed by a rotation around the twice rotated Z axis.
+/// The combined rotation is then `Ry * Rx * Rz`, with the rotations applied to
+/// vectors from right to left. The angle of the middle rotation is in the range [-π/2, π/2]
+/// and the other angles are in the range [-π, π].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum EulerOrder {
+    /// `Rx * Ry * Rz`
+    #[default]
+    XYZ,
+    /// `Rx * Rz * Ry`
+    XZY,
+    /// `Ry * Rx * Rz`, commonly used for yaw, pitch and roll
+    YXZ,
+    /// `Ry * Rz * Rx`
+    YZX,
+    /// `Rz * Rx * Ry`
+    ZXY,
+    /// `Rz * Ry * Rx`
+    ZYX,
+}
+
+impl EulerOrder {
+    /// Indices of the axes in the order of the rotations
+    fn axes(self) -> [usize; 3] {
+        match self {
+            Self::XYZ => [0, 1, 2],
+            Self::XZY => [0, 2, 1],
+            Self::YXZ => [1, 0, 2],
+            Self::YZX => [1, 2, 0],
+            Self::ZXY => [2, 0, 1],
+            Self::ZYX => [2, 1, 0],
+        }
+    }
+}
+
+/// Angles around the X, Y and Z axes of the rotation, combined in given order
+fn euler_angles(rotation: DQuat, order: EulerOrder) -> DVec3 {
+    let matrix = DMat3::from_quat(rotation);
+    let m = |row: usize, col: usize| matrix.col(col)[row];
+
+    let [i, j, k] = order.axes();
+    // Orders that are cyclic permutations of XYZ have no sign flips
+    let sign = if (j + 3 - i) % 3 == 1 { 1.0 } else { -1.0 };
+
+    let sin_middle = (sign * m(i, k)).clamp(-1.0, 1.0);
+    let middle = sin_middle.asin();
+
+    let (first, last) = if sin_middle.abs() < 1.0 - 1e-9 {
+        (
+            f64::atan2(-sign * m(j, k), m(k, k)),
+            f64::atan2(-sign * m(i, j), m(i, i)),
+        )
+    } else {
+        // Gimbal lock, where the first and last axes coincide. The whole rotation
+        // around them is given to the first axis.
+        (f64::atan2(sign * m(k, j), m(j, j)), 0.0)
+    };
+
+    let mut angles = DVec3::ZERO;
+    angles[i] = first;
+    angles[j] = middle;
+    angles[k] = last;
+    angles
+}
+
+/// Status of the latest gizmo update.
+///
+/// Describes why [`Gizmo::update`] did or did not produce a result.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum UpdateStatus {
+    /// The viewport is not set, so the gizmo cannot be interacted with.
+    #[default]
+    NoViewport,
+    /// The cursor is not over any part of the gizmo.
+    NotHovered,
+    /// The cursor is over the gizmo, but it is not being dragged.
+    Hovered,
+    /// Dragging was started, but the cursor was not over any part of the gizmo.
+    NothingPicked,
+    /// The gizmo is being dragged, but the cursor position did not produce a transformation.
+    ActiveNoMotion,
+    /// The gizmo was interacted with and produced a result of given kind.
+    Produced(GizmoModeKind),
+}
+
+/// Event that occurred during a gizmo update, see [`Gizmo::events`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GizmoEvent {
+    /// A different handle became focused, or `None` if no handle is focused anymore.
+    /// A handle stays focused while it is dragged.
+    FocusChanged(Option<GizmoHandleId>),
+    /// Dragging of a handle was started.
+    DragStarted {
+        /// The dragged handle
+        handle: GizmoHandleId,
+        /// Whether [`GizmoInteraction::duplicate`] was set when the drag was started.
+        /// The application may then duplicate the targets and continue the drag
+        /// on the duplicates with [`Gizmo::rebase_targets`].
+        duplicate_requested: bool,
     },
+    /// The transformation of the given handle moved to another snapping increment,
+    /// either during a drag with snapping enabled or by scrolling the mouse wheel.
+    SnapTick(GizmoHandleId),
 }
 
 /// Data used to draw [`Gizmo`].
@@ -666,22 +2645,297 @@ pub enum GizmoResult {
 pub struct GizmoDrawData {
     /// Vertices in viewport space.
     pub vertices: Vec<[f32; 2]>,
-    /// Linear RGBA colors.
+    /// Linear RGBA colors with premultiplied alpha,
+    /// unless converted with [`GizmoDrawData::convert_colors`].
     pub colors: Vec<[f32; 4]>,
     /// Indices to the vertex data.
     pub indices: Vec<u32>,
+    /// Bounding box of the vertices in viewport space, or `None` if there are no vertices.
+    ///
+    /// Useful for limiting redraws to the region covered by the gizmo.
+    pub bounds: Option<Rect>,
+}
+
+impl GizmoDrawData {
+    /// Layout of the vertex data, matching the inputs of [`reference_wgsl`].
+    ///
+    /// The first attribute is [`GizmoDrawData::vertices`] and the second
+    /// is [`GizmoDrawData::colors`]. Each attribute is stored in its own buffer.
+    pub const VERTEX_LAYOUT: [VertexAttributeLayout; 2] = [
+        VertexAttributeLayout {
+            name: "position",
+            location: 0,
+            components: 2,
+        },
+        VertexAttributeLayout {
+            name: "color",
+            location: 1,
+            components: 4,
+        },
+    ];
+
+    /// Appends the draw data of another gizmo, moving its vertices by given offset.
+    ///
+    /// This allows batching gizmos of multiple viewports into a single draw call.
+    /// The appended data is drawn on top of the existing data.
+    pub fn append_with_offset(&mut self, other: Self, viewport_offset: [f32; 2]) {
+        let index_offset = self.vertices.len() as u32;
+        self.vertices.extend(
+            other
+                .vertices
+                .into_iter()
+                .map(|[x, y]| [x + viewport_offset[0], y + viewport_offset[1]]),
+        );
+        self.colors.extend(other.colors);
+        self.indices
+            .extend(other.indices.into_iter().map(|idx| index_offset + idx));
+
+        if let Some(other_bounds) = other.bounds {
+            let other_bounds = other_bounds.translate(viewport_offset.into());
+            self.bounds = Some(
+                self.bounds
+                    .map_or(other_bounds, |bounds| bounds.union(other_bounds)),
+            );
+        }
+    }
+
+    /// Combines the draw data of handles into a single draw data.
+    /// Later handles are drawn on top of the earlier ones.
+    pub fn from_handles(handles: impl IntoIterator<Item = GizmoHandleDrawData>) -> Self {
+        let mut draw_data = Self::default();
+        for handle in handles {
+            draw_data += handle.draw_data;
+        }
+        draw_data
+    }
+
+    /// Converts [`GizmoDrawData::colors`] from linear RGB with premultiplied alpha to
+    /// the given format.
+    ///
+    /// Useful for renderers that expect colors in another format. Converting the colors
+    /// here instead of in a shader avoids converting them twice, which makes the
+    /// gizmo look washed out. Should be called only once for the same draw data.
+    ///
+    /// ```
+    /// use transform_gizmo::{ColorFormat, GizmoDrawData};
+    ///
+    /// let mut draw_data = GizmoDrawData {
+    ///     colors: vec![[0.25, 0.0, 0.0, 0.5]],
+    ///     ..Default::default()
+    /// };
+    /// draw_data.convert_colors(ColorFormat::LinearUnmultiplied);
+    ///
+    /// assert_eq!(draw_data.colors, vec![[0.5, 0.0, 0.0, 0.5]]);
+    /// ```
+    pub fn convert_colors(&mut self, format: ColorFormat) {
+        if format == ColorFormat::LinearPremultiplied {
+            return;
+        }
+
+        for color in &mut self.colors {
+            let [red, green, blue, alpha] = *color;
+
+            let mut rgb = if alpha > 0.0 {
+                [red / alpha, green / alpha, blue / alpha]
+            } else {
+                [0.0; 3]
+            };
+
+            if matches!(
+                format,
+                ColorFormat::SrgbPremultiplied | ColorFormat::SrgbUnmultiplied
+            ) {
+                rgb = rgb.map(gamma_from_linear);
+            }
+
+            if format == ColorFormat::SrgbPremultiplied {
+                rgb = rgb.map(|component| component * alpha);
+            }
+
+            *color = [rgb[0], rgb[1], rgb[2], alpha];
+        }
+    }
+
+    /// Multiplies the alpha of all colors by the given factor.
+    fn multiply_alpha(&mut self, factor: f32) {
+        let factor = factor.clamp(0.0, 1.0);
+        // Colors have premultiplied alpha, so every component is scaled.
+        for color in &mut self.colors {
+            for component in color.iter_mut() {
+                *component *= factor;
+            }
+        }
+    }
+}
+
+/// Format of the colors of [`GizmoDrawData`], see [`GizmoDrawData::convert_colors`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum ColorFormat {
+    /// Linear RGB with premultiplied alpha. Colors of the gizmo are in this format.
+    #[default]
+    LinearPremultiplied,
+    /// Linear RGB without premultiplied alpha
+    LinearUnmultiplied,
+    /// Gamma encoded sRGB, premultiplied with alpha after the encoding, like the colors of egui
+    SrgbPremultiplied,
+    /// Gamma encoded sRGB without premultiplied alpha
+    SrgbUnmultiplied,
+}
+
+/// Signed angle of a rotation around the given axis, in the range [-π, π]
+fn angle_around_axis(rotation: DQuat, axis: DVec3) -> f64 {
+    let rotation = if rotation.w < 0.0 {
+        -rotation
+    } else {
+        rotation
+    };
+    2.0 * DVec3::new(rotation.x, rotation.y, rotation.z)
+        .dot(axis)
+        .atan2(rotation.w)
+}
+
+/// The 24 rotations that map the coordinate axes to themselves, possibly negated
+fn axis_aligned_rotations() -> impl Iterator<Item = DQuat> {
+    let axes = [
+        DVec3::X,
+        DVec3::NEG_X,
+        DVec3::Y,
+        DVec3::NEG_Y,
+        DVec3::Z,
+        DVec3::NEG_Z,
+    ];
+
+    axes.into_iter().flat_map(move |x| {
+        axes.into_iter()
+            .filter(move |y| x.dot(*y) == 0.0)
+            .map(move |y| DQuat::from_mat3(&DMat3::from_cols(x, y, x.cross(y))))
+    })
+}
+
+/// Whether the handles of a mode can currently be interacted with, see [`Gizmo::handle_usability`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandleUsability {
+    /// The handles are visible and can be picked
+    Visible,
+    /// The handles are faded out from the current camera angle and cannot be picked
+    FadedOut,
+    /// The mode is not enabled, so there are no handles
+    Disabled,
+}
+
+/// Radius of the markers drawn by [`Gizmo::draw_pick_candidates`], in points
+const PICK_MARKER_RADIUS: f32 = 4.0;
+
+/// Pick state of a single handle, see [`Gizmo::pick_candidates`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PickCandidate {
+    /// The handle
+    pub handle: GizmoHandleId,
+    /// Depth of the handle along the pointer ray, if known. Of picked handles with
+    /// equal areas, the one with the smallest depth is preferred.
+    pub t: Option<f64>,
+    /// Point of the handle closest to the pointer ray, in world space, if known
+    pub point: Option<mint::Vector3<f64>>,
+    /// Whether the pointer is close enough to the handle to pick it
+    pub picked: bool,
+    /// Whether this is the handle that gets focused. Of the picked handles,
+    /// the one with the smallest area is chosen, then the one closest to the camera.
+    pub chosen: bool,
+    /// Approximate projected area of the pickable part of the handle
+    pub area: f64,
+}
+
+/// Orientation that a rotation was snapped to, see [`GizmoConfig::alignment_snap_angle`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceId {
+    /// The world axes
+    World,
+    /// Reference with the given id, see [`Gizmo::set_alignment_references`]
+    Custom(u64),
+}
+
+/// Identifies a single handle of a [`Gizmo`], such as the X axis translation arrow.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GizmoHandleId {
+    /// Mode of the handle
+    pub mode: GizmoMode,
+    /// Identifier of the handle, unique within the gizmo and
+    /// stable as long as the enabled modes do not change
+    pub id: u64,
+}
+
+/// Data used to draw a single handle of a [`Gizmo`], see [`Gizmo::draw_handles`].
+#[derive(Clone, Debug)]
+pub struct GizmoHandleDrawData {
+    /// The handle this data belongs to
+    pub handle: GizmoHandleId,
+    /// Whether the handle is focused, i.e. hovered or highlighted
+    pub focused: bool,
+    /// Whether the handle is being dragged
+    pub active: bool,
+    /// Draw data of the handle
+    pub draw_data: GizmoDrawData,
+}
+
+impl GizmoHandleId {
+    fn new(subgizmo: &SubGizmo) -> Self {
+        Self {
+            mode: subgizmo.mode(),
+            id: subgizmo.id(),
+        }
+    }
+}
+
+impl GizmoHandleDrawData {
+    fn new(subgizmo: &SubGizmo) -> Self {
+        Self {
+            handle: GizmoHandleId::new(subgizmo),
+            focused: subgizmo.is_focused(),
+            active: subgizmo.is_active(),
+            draw_data: subgizmo.draw(),
+        }
+    }
+}
+
+/// Layout of a single vertex attribute of [`GizmoDrawData`].
+///
+/// Attribute data is tightly packed `f32` components, one element per vertex.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VertexAttributeLayout {
+    /// Name of the attribute in the reference shader
+    pub name: &'static str,
+    /// Shader location of the attribute in the reference shader
+    pub location: u32,
+    /// Number of `f32` components per vertex
+    pub components: u32,
+}
+
+impl VertexAttributeLayout {
+    /// Size of a single element of the attribute in bytes
+    pub const fn size(&self) -> u64 {
+        self.components as u64 * std::mem::size_of::<f32>() as u64
+    }
+}
+
+/// Source of the WGSL shader used by the bevy integration to draw [`GizmoDrawData`].
+///
+/// The vertex entry point is `vertex` and the fragment entry point is `fragment`.
+/// Vertex positions must be converted from viewport coordinates to normalized device
+/// coordinates with the y axis pointing down. Colors are premultiplied,
+/// so the pipeline must use premultiplied alpha blending.
+pub const fn reference_wgsl() -> &'static str {
+    include_str!("gizmo.wgsl")
 }
 
 impl From<Mesh> for GizmoDrawData {
     fn from(mesh: Mesh) -> Self {
+        let mut bounds = Rect::NOTHING;
         let (vertices, colors): (Vec<_>, Vec<_>) = mesh
             .vertices
             .iter()
             .map(|vertex| {
-                (
-                    [vertex.pos.x, vertex.pos.y],
-                    Rgba::from(vertex.color).to_array(),
-                )
+                bounds.extend_with(vertex.pos);
+                ([vertex.pos.x, vertex.pos.y], vertex.color.to_linear())
             })
             .unzip();
 
@@ -689,17 +2943,14 @@ impl From<Mesh> for GizmoDrawData {
             vertices,
             colors,
             indices: mesh.indices,
+            bounds: (!mesh.vertices.is_empty()).then_some(bounds),
         }
     }
 }
 
 impl AddAssign for GizmoDrawData {
     fn add_assign(&mut self, rhs: Self) {
-        let index_offset = self.vertices.len() as u32;
-        self.vertices.extend(rhs.vertices);
-        self.colors.extend(rhs.colors);
-        self.indices
-            .extend(rhs.indices.into_iter().map(|idx| index_offset + idx));
+        self.append_with_offset(rhs, [0.0, 0.0]);
     }
 }
 
@@ -712,9 +2963,85 @@ impl Add for GizmoDrawData {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub(crate) struct Ray {
+// Gizmos are guaranteed to be thread safe. Fail to compile if that ever changes.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Gizmo>();
+    assert_send_sync::<GizmoConfig>();
+    assert_send_sync::<GizmoResult>();
+    assert_send_sync::<GizmoDrawData>();
+    assert_send_sync::<crate::GizmoGroup<u64>>();
+};
+
+/// World space ray cast from the camera through a point on the screen.
+///
+/// The gizmo is picked and dragged with a ray built from
+/// [`GizmoInteraction::cursor_pos`]. See [`Gizmo::pointer_ray`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray {
     pub(crate) screen_pos: Pos2,
-    pub(crate) origin: DVec3,
-    pub(crate) direction: DVec3,
+    pub(crate) origin: FVec3,
+    pub(crate) direction: FVec3,
+}
+
+impl Ray {
+    /// Creates a ray from the near plane towards the far plane
+    /// at the given screen position.
+    ///
+    /// `view_projection_inverse` is the inverse of `projection_matrix * view_matrix`,
+    /// and `screen_pos` is in the same window coordinates as `viewport`.
+    pub fn from_screen_pos(
+        viewport: Rect,
+        view_projection_inverse: mint::RowMatrix4<f64>,
+        screen_pos: (f32, f32),
+    ) -> Self {
+        Self::from_screen_pos_internal(
+            viewport,
+            mat4_from_mint(view_projection_inverse),
+            Pos2::from(screen_pos),
+        )
+    }
+
+    pub(crate) fn from_screen_pos_internal(viewport: Rect, mat: FMat4, screen_pos: Pos2) -> Self {
+        let origin = screen_to_world(viewport, mat, screen_pos, -1.0);
+        let target = screen_to_world(viewport, mat, screen_pos, 1.0);
+
+        let direction = target.sub(origin).normalize();
+
+        Self {
+            screen_pos,
+            origin,
+            direction,
+        }
+    }
+
+    /// Transforms the ray with an affine transformation matrix,
+    /// for example to move it into the local space of an object.
+    ///
+    /// The screen position of the ray is left unchanged.
+    pub fn transform(&self, mat: mint::RowMatrix4<f64>) -> Self {
+        let mat = mat4_from_mint(mat);
+
+        Self {
+            screen_pos: self.screen_pos,
+            origin: mat.transform_point3(self.origin),
+            direction: mat.transform_vector3(self.direction).normalize_or_zero(),
+        }
+    }
+
+    /// Screen position the ray was cast from.
+    pub fn screen_pos(&self) -> Pos2 {
+        self.screen_pos
+    }
+
+    /// Origin of the ray, on the near plane of the camera.
+    pub fn origin(&self) -> mint::Vector3<f64> {
+        vec3_to_mint(self.origin)
+    }
+
+    /// Normalized direction of the ray.
+    pub fn direction(&self) -> mint::Vector3<f64> {
+        vec3_to_mint(self.direction)
+    }
 }
@@ -1,3 +1,9 @@
+// Reference shader for drawing `GizmoDrawData`.
+//
+// Vertex positions are expected in normalized device coordinates with the y axis
+// pointing down, converted from the viewport coordinates of the draw data.
+// Colors are linear and premultiplied, so premultiplied alpha blending must be used.
+
 struct VertexInput {
     @location(0) position: vec2<f32>,
     @location(1) color: vec4<f32>,
@@ -0,0 +1,207 @@
+//! Shapes drawn in the same projected and anti-aliased style as the gizmo handles.
+//!
+//! Useful for drawing auxiliary markers in the viewport, such as pivot crosshairs,
+//! bounding boxes or handles of custom manipulators, that should look like a part of the gizmo.
+//! The shapes are returned as [`GizmoDrawData`], so they can be drawn with the same renderer.
+//!
+//! ```
+//! use transform_gizmo::gizmo_shapes::GizmoShapeBuilder;
+//! use transform_gizmo::math::{DMat4, DVec3, Pos2, Rect, Vec2};
+//! use transform_gizmo::{Color32, GizmoConfig};
+//!
+//! let config = GizmoConfig {
+//!     view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y).into(),
+//!     projection_matrix: DMat4::perspective_rh_gl(1.0, 1.0, 0.1, 100.0).into(),
+//!     viewport: Rect::from_min_size(Pos2::ZERO, Vec2::splat(800.0)),
+//!     ..Default::default()
+//! };
+//!
+//! let shapes = GizmoShapeBuilder::new(&config);
+//! let mut draw_data = shapes.line_segment([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], 2.0, Color32::WHITE);
+//! draw_data += shapes.line_segment([0.0, -1.0, 0.0], [0.0, 1.0, 0.0], 2.0, Color32::WHITE);
+//!
+//! assert!(!draw_data.vertices.is_empty());
+//! ```
+
+use crate::config::Color32;
+
+use crate::config::GizmoConfig;
+use crate::math::{
+    float_from_f64, mat4_from_mint, quat_from_mint, vec3_from_mint, FMat4, FVec3, Float, Rect,
+    Transform,
+};
+use crate::shape::ShapeBuidler;
+use crate::GizmoDrawData;
+
+/// Builds shapes projected from 3D to the viewport of a [`GizmoConfig`].
+///
+/// Points are given in world space, or in the local space of the transform given
+/// with [`GizmoShapeBuilder::with_transform`]. Arcs, circles and sectors are centered
+/// at the local origin, on the local XZ plane, with angles measured from the local X axis
+/// towards the local Z axis.
+///
+/// Stroke widths are in points, and tessellated with the
+/// [`GizmoConfig::pixels_per_point`] of the config, like the gizmo handles.
+#[derive(Debug, Copy, Clone)]
+pub struct GizmoShapeBuilder {
+    view_projection: FMat4,
+    model: FMat4,
+    viewport: Rect,
+    pixels_per_point: f32,
+}
+
+impl GizmoShapeBuilder {
+    /// Shape builder using the matrices and viewport of the config
+    pub fn new(config: &GizmoConfig) -> Self {
+        Self {
+            view_projection: mat4_from_mint(config.projection_matrix)
+                * mat4_from_mint(config.view_matrix),
+            model: FMat4::IDENTITY,
+            viewport: config.viewport,
+            pixels_per_point: config.pixels_per_point,
+        }
+    }
+
+    /// Shape builder whose points are in the local space of the transform
+    pub fn with_transform(&self, transform: Transform) -> Self {
+        Self {
+            model: FMat4::from_scale_rotation_translation(
+                vec3_from_mint(transform.scale),
+                quat_from_mint(transform.rotation),
+                vec3_from_mint(transform.translation),
+            ),
+            ..*self
+        }
+    }
+
+    /// Line between two points
+    pub fn line_segment(
+        &self,
+        from: impl Into<mint::Vector3<f64>>,
+        to: impl Into<mint::Vector3<f64>>,
+        width: f32,
+        color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .line_segment(point(from), point(to), (width, color))
+            .into()
+    }
+
+    /// Line through the points
+    pub fn polyline(
+        &self,
+        points: &[mint::Vector3<f64>],
+        width: f32,
+        color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .polyline(&points_from_mint(points), (width, color))
+            .into()
+    }
+
+    /// Convex polygon with an optional outline of given width
+    pub fn polygon(
+        &self,
+        points: &[mint::Vector3<f64>],
+        fill: Color32,
+        stroke_width: f32,
+        stroke_color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .polygon(
+                &points_from_mint(points),
+                fill,
+                (stroke_width, stroke_color),
+            )
+            .into()
+    }
+
+    /// Triangular arrow head from its base to its tip, `width` wide at the base
+    pub fn arrow(
+        &self,
+        from: impl Into<mint::Vector3<f64>>,
+        to: impl Into<mint::Vector3<f64>>,
+        width: f32,
+        color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .arrow(point(from), point(to), (width, color))
+            .into()
+    }
+
+    /// Arc between the angles, in radians
+    pub fn arc(
+        &self,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        width: f32,
+        color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .arc(
+                float_from_f64(radius),
+                float_from_f64(start_angle),
+                float_from_f64(end_angle),
+                (width, color),
+            )
+            .into()
+    }
+
+    /// Outline of a circle
+    pub fn circle(&self, radius: f64, width: f32, color: Color32) -> GizmoDrawData {
+        self.builder()
+            .circle(float_from_f64(radius), (width, color))
+            .into()
+    }
+
+    /// Filled circle with an optional outline of given width
+    pub fn filled_circle(
+        &self,
+        radius: f64,
+        fill: Color32,
+        stroke_width: f32,
+        stroke_color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .filled_circle(float_from_f64(radius), fill, (stroke_width, stroke_color))
+            .into()
+    }
+
+    /// Filled circular sector between the angles, in radians
+    pub fn sector(
+        &self,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        fill: Color32,
+        stroke_width: f32,
+        stroke_color: Color32,
+    ) -> GizmoDrawData {
+        self.builder()
+            .sector(
+                float_from_f64(radius),
+                float_from_f64(start_angle),
+                float_from_f64(end_angle),
+                fill,
+                (stroke_width, stroke_color),
+            )
+            .into()
+    }
+
+    fn builder(&self) -> ShapeBuidler {
+        ShapeBuidler::new(
+            self.view_projection * self.model,
+            self.viewport,
+            self.pixels_per_point,
+        )
+    }
+}
+
+fn point(point: impl Into<mint::Vector3<f64>>) -> FVec3 {
+    vec3_from_mint(point.into())
+}
+
+fn points_from_mint(points: &[mint::Vector3<f64>]) -> Vec<FVec3> {
+    points.iter().copied().map(vec3_from_mint).collect()
+}
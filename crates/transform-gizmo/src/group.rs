@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use enumset::EnumSet;
+
+use crate::config::{GizmoConfig, TransformChannel};
+use crate::gizmo::{Gizmo, GizmoDrawData, GizmoInteraction, GizmoResult};
+use crate::math::Transform;
+
+/// A collection of gizmos sharing the same configuration.
+///
+/// Each gizmo is identified by a key of type `K`. Only one gizmo of the
+/// group can be interacted with at a time. While one of the gizmos is being
+/// dragged, the other gizmos ignore dragging.
+///
+/// When the handles of multiple gizmos overlap on screen, the gizmo whose
+/// handle is closest to the camera is hovered and can be dragged.
+///
+/// The gizmos are iterated and drawn in a stable order that only depends on
+/// the order they were added in and their depth, never on the hashes of the keys.
+///
+/// # Examples
+///
+/// ```
+/// use transform_gizmo::prelude::*;
+/// use transform_gizmo::math::Transform;
+///
+/// let mut group = GizmoGroup::new(GizmoConfig::default());
+///
+/// let targets = [Transform::default()];
+/// let result = group.update(1, GizmoInteraction::default(), &targets, &[]);
+///
+/// assert!(result.is_none());
+/// assert_eq!(group.len(), 1);
+/// assert_eq!(group.active(), None);
+///
+/// // Remove gizmos that are no longer needed.
+/// group.retain(|key| *key != 1);
+/// assert!(group.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GizmoGroup<K> {
+    config: GizmoConfig,
+    gizmos: HashMap<K, Gizmo>,
+    /// Keys of the gizmos in the order they were added
+    order: Vec<K>,
+    /// Gizmo that is currently being interacted with
+    active: Option<K>,
+}
+
+impl<K> Default for GizmoGroup<K> {
+    fn default() -> Self {
+        Self {
+            config: GizmoConfig::default(),
+            gizmos: HashMap::default(),
+            order: Vec::new(),
+            active: None,
+        }
+    }
+}
+
+impl<K> GizmoGroup<K>
+where
+    K: Copy + Eq + Hash,
+{
+    /// Creates a new empty group with given configuration
+    pub fn new(config: GizmoConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Current configuration used by the gizmos of the group.
+    pub fn config(&self) -> &GizmoConfig {
+        &self.config
+    }
+
+    /// Updates the configuration used by all gizmos of the group.
+    pub fn update_config(&mut self, config: GizmoConfig) {
+        self.config = config;
+
+        for gizmo in self.gizmos.values_mut() {
+            gizmo.update_config(config);
+        }
+    }
+
+    /// Updates the gizmo with given key, creating it if it does not exist.
+    ///
+    /// Dragging is ignored if another gizmo of the group is being interacted with.
+    /// Starting a drag is also ignored if another gizmo was hovered in front of
+    /// this one in the latest update, so that occluded handles cannot be picked.
+    ///
+    /// See [`Gizmo::update_with_locked_channels`].
+    pub fn update(
+        &mut self,
+        key: K,
+        mut interaction: GizmoInteraction,
+        targets: &[Transform],
+        locked_channels: &[EnumSet<TransformChannel>],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        if self.active.is_some_and(|active| active != key) {
+            // Another gizmo is being interacted with.
+            interaction.drag_started = false;
+            interaction.dragging = false;
+        } else if self.active.is_none() && self.hovered().is_some_and(|hovered| hovered != key) {
+            // Another gizmo is in front of this one.
+            interaction.drag_started = false;
+        }
+
+        let config = self.config;
+        let gizmo = self.gizmos.entry(key).or_insert_with(|| {
+            self.order.push(key);
+            Gizmo::new(config)
+        });
+
+        let result = gizmo.update_with_locked_channels(interaction, targets, locked_channels);
+
+        if gizmo.active_mode().is_some() {
+            self.active = Some(key);
+        } else if self.active == Some(key) {
+            self.active = None;
+        }
+
+        result
+    }
+
+    /// Key of the gizmo that is currently being interacted with, if any.
+    pub fn active(&self) -> Option<K> {
+        self.active
+    }
+
+    /// Key of the gizmo that is currently hovered or being interacted with, if any.
+    ///
+    /// If multiple gizmos are hovered, the one whose focused handle is
+    /// closest to the camera is chosen.
+    pub fn hovered(&self) -> Option<K> {
+        self.active.or_else(|| {
+            self.order
+                .iter()
+                .filter_map(|key| {
+                    let distance = self.gizmos.get(key)?.focused_distance()?;
+                    Some((*key, distance))
+                })
+                .min_by(|(_, first), (_, second)| first.total_cmp(second))
+                .map(|(key, _)| key)
+        })
+    }
+
+    /// Gizmo with given key, if it exists.
+    pub fn get(&self, key: K) -> Option<&Gizmo> {
+        self.gizmos.get(&key)
+    }
+
+    /// Mutable gizmo with given key, if it exists.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut Gizmo> {
+        self.gizmos.get_mut(&key)
+    }
+
+    /// Removes the gizmo with given key, returning it if it existed.
+    pub fn remove(&mut self, key: K) -> Option<Gizmo> {
+        if self.active == Some(key) {
+            self.active = None;
+        }
+
+        self.order.retain(|k| *k != key);
+        self.gizmos.remove(&key)
+    }
+
+    /// Keeps only the gizmos whose keys match the predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&K) -> bool) {
+        self.gizmos.retain(|key, _| predicate(key));
+        self.order.retain(|key| self.gizmos.contains_key(key));
+
+        if let Some(active) = self.active {
+            if !self.gizmos.contains_key(&active) {
+                self.active = None;
+            }
+        }
+    }
+
+    /// Iterates over all gizmos of the group and their keys, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Gizmo)> {
+        self.order
+            .iter()
+            .filter_map(|key| Some((key, self.gizmos.get(key)?)))
+    }
+
+    /// Number of gizmos in the group.
+    pub fn len(&self) -> usize {
+        self.gizmos.len()
+    }
+
+    /// Whether the group has no gizmos.
+    pub fn is_empty(&self) -> bool {
+        self.gizmos.is_empty()
+    }
+
+    /// Keys of the gizmos in the order they are drawn.
+    ///
+    /// Gizmos are drawn from the farthest to the closest to the camera, except
+    /// that the hovered or active gizmo is drawn last, so that it appears on top
+    /// of the others. Gizmos at equal depth are drawn in the order they were added.
+    pub fn draw_order(&self) -> impl Iterator<Item = K> + '_ {
+        let hovered = self.hovered();
+
+        let mut keys = self
+            .order
+            .iter()
+            .copied()
+            .filter(|key| Some(*key) != hovered)
+            .filter_map(|key| Some((key, self.gizmos.get(&key)?.depth())))
+            .collect::<Vec<_>>();
+
+        keys.sort_by(|(_, first), (_, second)| second.total_cmp(first));
+
+        keys.into_iter().map(|(key, _)| key).chain(hovered)
+    }
+
+    /// Draw data of all gizmos in the group combined.
+    ///
+    /// See [`Gizmo::draw`] and [`GizmoGroup::draw_order`].
+    pub fn draw(&self) -> GizmoDrawData {
+        self.draw_order()
+            .filter_map(|key| self.gizmos.get(&key))
+            .fold(GizmoDrawData::default(), |draw_data, gizmo| {
+                draw_data + gizmo.draw()
+            })
+    }
+}
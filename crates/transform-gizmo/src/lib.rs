@@ -17,13 +17,57 @@
 //!
 //! For a more complete example, see the online demo at <https://urholaukkarinen.github.io/transform-gizmo/>.
 //! The demo sources can be found at <https://github.com/urholaukkarinen/transform-gizmo/blob/main/examples/bevy/src/main.rs>.
+//!
+//! # Determinism
+//!
+//! Given the same sequence of configurations, interactions and targets, the gizmo produces
+//! the same results and draw data on every run. No random state or hash map iteration order
+//! affects the output, and the ids of the handles, such as [`GizmoHandleId`], are the same
+//! on every platform. This makes the gizmo suitable for replay tests and for editors that
+//! synchronize interactions over the network. Floating point results may still differ
+//! between platforms whose math functions are implemented differently.
+//!
+//! # Features
+//!
+//! - `f32-math`: Use `f32` instead of `f64` for the internal gizmo calculations. This reduces binary size
+//!   and improves performance, at the cost of precision when the gizmo is very far away from the world origin.
+//!   The public API uses `f64` regardless of this feature.
+//! - `serde`: Serialization support for [`GizmoVisuals`] and [`GizmoConfig`].
+//!   The matrices and viewport of the config are not serialized.
+//! - `bevy_reflect`: Derives `Reflect` for [`GizmoVisuals`] and the configuration types used in it
+//!   and in [`GizmoConfig`], so that they can be edited in inspectors. The colors of the visuals
+//!   are not reflected, as the color type does not implement `Reflect`.
+//! - `config-file`: Loading [`GizmoVisuals`] from RON or TOML files, with optional hot-reloading.
+//! - `nalgebra`: Conversions between [`math::Transform`] and [nalgebra](https://nalgebra.org/) types,
+//!   such as `Isometry3`, `Similarity3` and `Matrix4`.
+//! - `analytics`: Counters of how the gizmo is interacted with, such as the number of drags per mode,
+//!   retrievable with `Gizmo::stats`.
+//! - `epaint`: Tessellate the gizmo shapes with [epaint](https://docs.rs/epaint), and convert
+//!   [`Color32`] and the screen space types of [`math`], such as [`Rect`], to and from their
+//!   `ecolor` and `emath` equivalents. Without this feature, a minimal built-in tessellator is
+//!   used, and the gizmo does not depend on any egui crates or their versions.
+//! - `rotate`, `scale`, `arcball` (enabled by default): The rotation, scale and bounds, and arcball handles.
+//!   Disabling them with `default-features = false` gives a smaller, translation-only gizmo.
+//!   [`GizmoMode`]s of a disabled feature are accepted but ignored, so no handles are created for them.
 
+mod color;
+mod rect;
 mod shape;
 mod subgizmo;
+#[cfg(not(feature = "epaint"))]
+mod tessellator;
 
 pub mod config;
+#[cfg(feature = "config-file")]
+pub mod config_file;
 pub mod gizmo;
+pub mod gizmo_shapes;
+pub mod group;
 pub mod math;
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra_interop;
+#[cfg(feature = "analytics")]
+pub mod stats;
 
 pub mod prelude;
 
@@ -1,6 +1,115 @@
-pub use emath::{Pos2, Rect, Vec2};
+pub use crate::rect::{Pos2, Rect, Vec2};
 pub use glam::{DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, Mat4, Quat, Vec3, Vec4Swizzles};
 
+pub(crate) use precision::*;
+
+/// Math types used internally for the gizmo calculations.
+///
+/// By default, all calculations are done with `f64` precision.
+/// The public API always uses `f64`, regardless of the internal precision.
+#[cfg(not(feature = "f32-math"))]
+mod precision {
+    pub(crate) use glam::{
+        DMat3 as FMat3, DMat4 as FMat4, DQuat as FQuat, DVec2 as FVec2, DVec3 as FVec3,
+        DVec4 as FVec4,
+    };
+    pub(crate) use std::f64::consts;
+
+    /// Floating point type used for the gizmo calculations.
+    pub(crate) type Float = f64;
+
+    /// Length of the rays used for picking subgizmos.
+    pub(crate) const RAY_LENGTH: Float = 1e+14;
+
+    pub(crate) fn vec3_from_mint(vec: mint::Vector3<f64>) -> FVec3 {
+        vec.into()
+    }
+
+    pub(crate) fn vec3_to_mint(vec: FVec3) -> mint::Vector3<f64> {
+        vec.into()
+    }
+
+    pub(crate) fn quat_from_mint(quat: mint::Quaternion<f64>) -> FQuat {
+        quat.into()
+    }
+
+    pub(crate) fn quat_to_mint(quat: FQuat) -> mint::Quaternion<f64> {
+        quat.into()
+    }
+
+    pub(crate) fn mat4_from_mint(mat: mint::RowMatrix4<f64>) -> FMat4 {
+        mat.into()
+    }
+
+    pub(crate) fn float_to_f64(value: Float) -> f64 {
+        value
+    }
+
+    pub(crate) fn float_from_f64(value: f64) -> Float {
+        value
+    }
+
+    pub(crate) fn float_to_f32(value: Float) -> f32 {
+        value as f32
+    }
+}
+
+/// Math types used internally for the gizmo calculations.
+///
+/// With the `f32-math` feature, all calculations are done with `f32` precision.
+/// This reduces binary size and improves performance, especially on wasm, but
+/// the gizmo may jitter when it is very far away from the world origin or very
+/// close to the camera. The public API always uses `f64`, regardless of the internal precision.
+#[cfg(feature = "f32-math")]
+mod precision {
+    use glam::{DMat4, DQuat, DVec3};
+
+    pub(crate) use glam::{
+        Mat3 as FMat3, Mat4 as FMat4, Quat as FQuat, Vec2 as FVec2, Vec3 as FVec3, Vec4 as FVec4,
+    };
+    pub(crate) use std::f32::consts;
+
+    /// Floating point type used for the gizmo calculations.
+    pub(crate) type Float = f32;
+
+    /// Length of the rays used for picking subgizmos.
+    /// Shorter than with `f64`, to avoid losing all precision in segment distance calculations.
+    pub(crate) const RAY_LENGTH: Float = 1e+6;
+
+    pub(crate) fn vec3_from_mint(vec: mint::Vector3<f64>) -> FVec3 {
+        DVec3::from(vec).as_vec3()
+    }
+
+    pub(crate) fn vec3_to_mint(vec: FVec3) -> mint::Vector3<f64> {
+        vec.as_dvec3().into()
+    }
+
+    pub(crate) fn quat_from_mint(quat: mint::Quaternion<f64>) -> FQuat {
+        let quat = DQuat::from(quat);
+        FQuat::from_xyzw(quat.x as f32, quat.y as f32, quat.z as f32, quat.w as f32)
+    }
+
+    pub(crate) fn quat_to_mint(quat: FQuat) -> mint::Quaternion<f64> {
+        DQuat::from_xyzw(quat.x.into(), quat.y.into(), quat.z.into(), quat.w.into()).into()
+    }
+
+    pub(crate) fn mat4_from_mint(mat: mint::RowMatrix4<f64>) -> FMat4 {
+        FMat4::from_cols_array(&DMat4::from(mat).to_cols_array().map(|value| value as f32))
+    }
+
+    pub(crate) fn float_to_f64(value: Float) -> f64 {
+        value.into()
+    }
+
+    pub(crate) fn float_from_f64(value: f64) -> Float {
+        value as f32
+    }
+
+    pub(crate) fn float_to_f32(value: Float) -> f32 {
+        value
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct Transform {
     pub scale: mint::Vector3<f64>,
@@ -30,17 +139,182 @@ impl Transform {
             translation: translation.into(),
         }
     }
+
+    /// Creates a transform from a matrix. The matrix must not contain shear.
+    pub fn from_mat4(matrix: impl Into<mint::ColumnMatrix4<f64>>) -> Self {
+        let (scale, rotation, translation) =
+            DMat4::from(matrix.into()).to_scale_rotation_translation();
+
+        Self::from_scale_rotation_translation(scale, rotation, translation)
+    }
+
+    /// Creates a transform from a column-major `f32` matrix. The matrix must not contain shear.
+    pub fn from_mat4_f32(matrix: [[f32; 4]; 4]) -> Self {
+        Self::from_mat4(Mat4::from_cols_array_2d(&matrix).as_dmat4())
+    }
+
+    /// Matrix of the transform
+    pub fn to_mat4(&self) -> mint::ColumnMatrix4<f64> {
+        DMat4::from_scale_rotation_translation(
+            self.scale.into(),
+            self.rotation.into(),
+            self.translation.into(),
+        )
+        .into()
+    }
+
+    /// Column-major `f32` matrix of the transform
+    pub fn to_mat4_f32(&self) -> [[f32; 4]; 4] {
+        DMat4::from(self.to_mat4()).as_mat4().to_cols_array_2d()
+    }
+}
+
+/// Transform on the XY plane, for 2D applications.
+/// See [`Gizmo::update_2d`](crate::Gizmo::update_2d).
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Transform2d {
+    pub translation: mint::Vector2<f64>,
+    /// Counterclockwise rotation around the Z axis, in radians
+    pub rotation: f64,
+    pub scale: mint::Vector2<f64>,
+}
+
+impl Default for Transform2d {
+    fn default() -> Self {
+        Self {
+            translation: DVec2::ZERO.into(),
+            rotation: 0.0,
+            scale: DVec2::ONE.into(),
+        }
+    }
+}
+
+impl Transform2d {
+    pub fn new(
+        translation: impl Into<mint::Vector2<f64>>,
+        rotation: f64,
+        scale: impl Into<mint::Vector2<f64>>,
+    ) -> Self {
+        Self {
+            translation: translation.into(),
+            rotation,
+            scale: scale.into(),
+        }
+    }
+
+    /// The 3D transform on the XY plane, with unit scale along the Z axis
+    pub fn to_transform(&self) -> Transform {
+        let translation = DVec2::from(self.translation);
+        let scale = DVec2::from(self.scale);
+
+        Transform::from_scale_rotation_translation(
+            scale.extend(1.0),
+            DQuat::from_rotation_z(self.rotation),
+            translation.extend(0.0),
+        )
+    }
+
+    /// Projects the 3D transform onto the XY plane.
+    /// Only the rotation around the Z axis is kept, in the range [-π, π].
+    pub fn from_transform(transform: &Transform) -> Self {
+        let rotation = DQuat::from(transform.rotation);
+        let mut angle = 2.0 * f64::atan2(rotation.z, rotation.w);
+        if angle > std::f64::consts::PI {
+            angle -= std::f64::consts::TAU;
+        } else if angle < -std::f64::consts::PI {
+            angle += std::f64::consts::TAU;
+        }
+
+        Self::new(
+            DVec3::from(transform.translation).truncate(),
+            angle,
+            DVec3::from(transform.scale).truncate(),
+        )
+    }
+}
+
+impl From<Transform2d> for Transform {
+    fn from(transform: Transform2d) -> Self {
+        transform.to_transform()
+    }
+}
+
+impl From<Transform> for Transform2d {
+    fn from(transform: Transform) -> Self {
+        Self::from_transform(&transform)
+    }
+}
+
+/// Axis-aligned bounding box in the local space of a target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// Minimum corner of the box
+    pub min: mint::Vector3<f64>,
+    /// Maximum corner of the box
+    pub max: mint::Vector3<f64>,
+}
+
+impl Aabb {
+    /// Creates a new bounding box from its minimum and maximum corners
+    pub fn new(min: impl Into<mint::Vector3<f64>>, max: impl Into<mint::Vector3<f64>>) -> Self {
+        Self {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+
+    /// All eight corners of the box
+    pub fn corners(&self) -> [DVec3; 8] {
+        let min = DVec3::from(self.min);
+        let max = DVec3::from(self.max);
+
+        [
+            DVec3::new(min.x, min.y, min.z),
+            DVec3::new(max.x, min.y, min.z),
+            DVec3::new(min.x, max.y, min.z),
+            DVec3::new(max.x, max.y, min.z),
+            DVec3::new(min.x, min.y, max.z),
+            DVec3::new(max.x, min.y, max.z),
+            DVec3::new(min.x, max.y, max.z),
+            DVec3::new(max.x, max.y, max.z),
+        ]
+    }
+}
+
+/// Maps a scale along global axes to the local axes of an object with given rotation.
+///
+/// Each local axis receives the scale of the global axis it is most aligned with.
+pub(crate) fn closest_axis_scale(rotation: DQuat, global_scale: DVec3) -> DVec3 {
+    let rotation = DMat3::from_quat(rotation.normalize());
+
+    let scale_for_axis = |local_axis: DVec3| {
+        let alignment = local_axis.abs();
+        if alignment.x >= alignment.y && alignment.x >= alignment.z {
+            global_scale.x
+        } else if alignment.y >= alignment.z {
+            global_scale.y
+        } else {
+            global_scale.z
+        }
+    };
+
+    DVec3::new(
+        scale_for_axis(rotation.x_axis),
+        scale_for_axis(rotation.y_axis),
+        scale_for_axis(rotation.z_axis),
+    )
 }
 
 /// Creates a matrix that represents rotation between two 3d vectors
 ///
 /// Credit: <https://www.iquilezles.org/www/articles/noacos/noacos.htm>
-pub(crate) fn rotation_align(from: DVec3, to: DVec3) -> DMat3 {
+#[cfg(feature = "rotate")]
+pub(crate) fn rotation_align(from: FVec3, to: FVec3) -> FMat3 {
     let v = from.cross(to);
     let c = from.dot(to);
     let k = 1.0 / (1.0 + c);
 
-    DMat3::from_cols_array(&[
+    FMat3::from_cols_array(&[
         v.x * v.x * k + c,
         v.x * v.y * k + v.z,
         v.x * v.z * k - v.y,
@@ -57,7 +331,7 @@ pub(crate) fn rotation_align(from: DVec3, to: DVec3) -> DMat3 {
 /// This can be used to determine the shortest distance between those two rays.
 ///
 /// Credit: Practical Geometry Algorithms by Daniel Sunday: <http://geomalgorithms.com/code.html>
-pub(crate) fn ray_to_ray(a1: DVec3, adir: DVec3, b1: DVec3, bdir: DVec3) -> (f64, f64) {
+pub(crate) fn ray_to_ray(a1: FVec3, adir: FVec3, b1: FVec3, bdir: FVec3) -> (Float, Float) {
     let b = adir.dot(bdir);
     let w = a1 - b1;
     let d = adir.dot(w);
@@ -81,7 +355,7 @@ pub(crate) fn ray_to_ray(a1: DVec3, adir: DVec3, b1: DVec3, bdir: DVec3) -> (f64
 /// This can be used to determine the shortest distance between those two segments.
 ///
 /// Credit: Practical Geometry Algorithms by Daniel Sunday: <http://geomalgorithms.com/code.html>
-pub(crate) fn segment_to_segment(a1: DVec3, a2: DVec3, b1: DVec3, b2: DVec3) -> (f64, f64) {
+pub(crate) fn segment_to_segment(a1: FVec3, a2: FVec3, b1: FVec3, b2: FVec3) -> (Float, Float) {
     let da = a2 - a1;
     let db = b2 - b1;
     let la = da.length_squared();
@@ -146,11 +420,11 @@ pub(crate) fn segment_to_segment(a1: DVec3, a2: DVec3, b1: DVec3, b2: DVec3) ->
 
 /// Finds the intersection point of a ray and a plane
 pub(crate) fn intersect_plane(
-    plane_normal: DVec3,
-    plane_origin: DVec3,
-    ray_origin: DVec3,
-    ray_dir: DVec3,
-    t: &mut f64,
+    plane_normal: FVec3,
+    plane_origin: FVec3,
+    ray_origin: FVec3,
+    ray_dir: FVec3,
+    t: &mut Float,
 ) -> bool {
     let denom = plane_normal.dot(ray_dir);
 
@@ -165,30 +439,35 @@ pub(crate) fn intersect_plane(
 /// Finds the intersection point of a ray and a plane
 /// and distance from the intersection to the plane origin
 pub(crate) fn ray_to_plane_origin(
-    disc_normal: DVec3,
-    disc_origin: DVec3,
-    ray_origin: DVec3,
-    ray_dir: DVec3,
-) -> (f64, f64) {
+    disc_normal: FVec3,
+    disc_origin: FVec3,
+    ray_origin: FVec3,
+    ray_dir: FVec3,
+) -> (Float, Float) {
     let mut t = 0.0;
     if intersect_plane(disc_normal, disc_origin, ray_origin, ray_dir, &mut t) {
         let p = ray_origin + ray_dir * t;
         let v = p - disc_origin;
         let d2 = v.dot(v);
-        (t, f64::sqrt(d2))
+        (t, Float::sqrt(d2))
     } else {
-        (t, f64::MAX)
+        (t, Float::MAX)
     }
 }
 
+/// Number of full revolutions in the angle, rounded towards zero
+pub(crate) fn full_revolutions(angle: f64) -> i32 {
+    (angle / std::f64::consts::TAU).trunc() as i32
+}
+
 /// Rounds given value to the nearest interval
-pub(crate) fn round_to_interval(val: f64, interval: f64) -> f64 {
+pub(crate) fn round_to_interval(val: Float, interval: Float) -> Float {
     (val / interval).round() * interval
 }
 
 /// Calculates 2d screen coordinates from 3d world coordinates
-pub(crate) fn world_to_screen(viewport: Rect, mvp: DMat4, pos: DVec3) -> Option<Pos2> {
-    let mut pos = mvp * DVec4::from((pos, 1.0));
+pub(crate) fn world_to_screen(viewport: Rect, mvp: FMat4, pos: FVec3) -> Option<Pos2> {
+    let mut pos = mvp * FVec4::from((pos, 1.0));
 
     if pos.w < 1e-10 {
         return None;
@@ -200,17 +479,17 @@ pub(crate) fn world_to_screen(viewport: Rect, mvp: DMat4, pos: DVec3) -> Option<
     let center = viewport.center();
 
     Some(Pos2::new(
-        (center.x as f64 + pos.x * viewport.width() as f64 / 2.0) as f32,
-        (center.y as f64 + pos.y * viewport.height() as f64 / 2.0) as f32,
+        float_to_f32(Float::from(center.x) + pos.x * Float::from(viewport.width()) / 2.0),
+        float_to_f32(Float::from(center.y) + pos.y * Float::from(viewport.height()) / 2.0),
     ))
 }
 
 /// Calculates 3d world coordinates from 2d screen coordinates
-pub(crate) fn screen_to_world(viewport: Rect, mat: DMat4, pos: Pos2, z: f64) -> DVec3 {
-    let x = (((pos.x - viewport.min.x) / viewport.width()) * 2.0 - 1.0) as f64;
-    let y = (((pos.y - viewport.min.y) / viewport.height()) * 2.0 - 1.0) as f64;
+pub(crate) fn screen_to_world(viewport: Rect, mat: FMat4, pos: Pos2, z: Float) -> FVec3 {
+    let x = Float::from(((pos.x - viewport.min.x) / viewport.width()) * 2.0 - 1.0);
+    let y = Float::from(((pos.y - viewport.min.y) / viewport.height()) * 2.0 - 1.0);
 
-    let mut world_pos = mat * DVec4::new(x, -y, z, 1.0);
+    let mut world_pos = mat * FVec4::new(x, -y, z, 1.0);
 
     // w is zero when far plane is set to infinity
     if world_pos.w.abs() < 1e-7 {
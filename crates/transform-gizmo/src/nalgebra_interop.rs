@@ -0,0 +1,126 @@
+//! Conversions between the gizmo types and [`nalgebra`] types.
+//!
+//! [`Transform`] can be converted from [`Isometry3`], [`Similarity3`] and [`Matrix4`],
+//! and back to each of them. Matrices of [`GizmoConfig`](crate::GizmoConfig) can be
+//! converted with [`matrix4_from_mint`] and [`matrix4_to_mint`].
+//!
+//! # Examples
+//!
+//! ```
+//! use nalgebra::{Isometry3, Vector3};
+//! use transform_gizmo::math::Transform;
+//!
+//! let isometry = Isometry3::translation(1.0, 2.0, 3.0);
+//! let transform = Transform::from(isometry);
+//!
+//! let isometry: Isometry3<f64> = transform.into();
+//! assert_eq!(isometry.translation.vector, Vector3::new(1.0, 2.0, 3.0));
+//! ```
+
+use std::fmt;
+
+use glam::{DMat4, DQuat, DVec3};
+use nalgebra::{Isometry3, Matrix4, Quaternion, Similarity3, Translation3, UnitQuaternion};
+
+use crate::math::Transform;
+
+/// Relative tolerance used when checking whether a scale is uniform
+const UNIFORM_SCALE_TOLERANCE: f64 = 1e-9;
+
+/// Error returned when converting a [`Transform`] with non-uniform
+/// or zero scale to a [`Similarity3`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NonUniformScaleError;
+
+impl fmt::Display for NonUniformScaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transform scale is not uniform and non-zero")
+    }
+}
+
+impl std::error::Error for NonUniformScaleError {}
+
+/// Converts a row-major mint matrix, such as [`GizmoConfig::view_matrix`](crate::GizmoConfig::view_matrix),
+/// to a nalgebra matrix.
+pub fn matrix4_from_mint(matrix: mint::RowMatrix4<f64>) -> Matrix4<f64> {
+    Matrix4::from_column_slice(&DMat4::from(matrix).to_cols_array())
+}
+
+/// Converts a nalgebra matrix to a row-major mint matrix, such as
+/// [`GizmoConfig::view_matrix`](crate::GizmoConfig::view_matrix).
+pub fn matrix4_to_mint(matrix: &Matrix4<f64>) -> mint::RowMatrix4<f64> {
+    DMat4::from_cols_slice(matrix.as_slice()).into()
+}
+
+fn quat_from_nalgebra(rotation: &UnitQuaternion<f64>) -> mint::Quaternion<f64> {
+    let quat = rotation.quaternion();
+    DQuat::from_xyzw(quat.i, quat.j, quat.k, quat.w).into()
+}
+
+fn quat_to_nalgebra(rotation: mint::Quaternion<f64>) -> UnitQuaternion<f64> {
+    let quat = DQuat::from(rotation);
+    UnitQuaternion::from_quaternion(Quaternion::new(quat.w, quat.x, quat.y, quat.z))
+}
+
+impl From<Isometry3<f64>> for Transform {
+    fn from(isometry: Isometry3<f64>) -> Self {
+        let translation = isometry.translation.vector;
+
+        Self {
+            scale: DVec3::ONE.into(),
+            rotation: quat_from_nalgebra(&isometry.rotation),
+            translation: DVec3::new(translation.x, translation.y, translation.z).into(),
+        }
+    }
+}
+
+impl From<Similarity3<f64>> for Transform {
+    fn from(similarity: Similarity3<f64>) -> Self {
+        Self {
+            scale: DVec3::splat(similarity.scaling()).into(),
+            ..Self::from(similarity.isometry)
+        }
+    }
+}
+
+impl From<Matrix4<f64>> for Transform {
+    /// The matrix must not contain shear.
+    fn from(matrix: Matrix4<f64>) -> Self {
+        Self::from_mat4(DMat4::from_cols_slice(matrix.as_slice()))
+    }
+}
+
+impl From<Transform> for Isometry3<f64> {
+    /// The scale of the transform is discarded.
+    fn from(transform: Transform) -> Self {
+        let translation = DVec3::from(transform.translation);
+
+        Self::from_parts(
+            Translation3::new(translation.x, translation.y, translation.z),
+            quat_to_nalgebra(transform.rotation),
+        )
+    }
+}
+
+impl TryFrom<Transform> for Similarity3<f64> {
+    type Error = NonUniformScaleError;
+
+    /// Fails if the scale of the transform is not the same along all axes, or if it is zero.
+    fn try_from(transform: Transform) -> Result<Self, Self::Error> {
+        let scale = DVec3::from(transform.scale);
+        let scaling = scale.x;
+
+        let tolerance = scaling.abs() * UNIFORM_SCALE_TOLERANCE;
+        if scaling.abs() <= f64::EPSILON || !scale.abs_diff_eq(DVec3::splat(scaling), tolerance) {
+            return Err(NonUniformScaleError);
+        }
+
+        Ok(Self::from_isometry(transform.into(), scaling))
+    }
+}
+
+impl From<Transform> for Matrix4<f64> {
+    fn from(transform: Transform) -> Self {
+        Self::from_column_slice(&DMat4::from(transform.to_mat4()).to_cols_array())
+    }
+}
@@ -1,9 +1,16 @@
-pub use crate::config::{GizmoConfig, GizmoDirection, GizmoMode, GizmoOrientation, GizmoVisuals};
-pub use crate::gizmo::{Gizmo, GizmoDrawData, GizmoInteraction, GizmoResult};
+pub use crate::config::{
+    GizmoConfig, GizmoDirection, GizmoMode, GizmoOrientation, GizmoVisuals, SnapMode,
+};
+pub use crate::gizmo::{
+    reference_wgsl, ColorFormat, EulerOrder, Gizmo, GizmoDrawData, GizmoEvent, GizmoHandleDrawData,
+    GizmoHandleId, GizmoInteraction, GizmoResult, HandleUsability, PickCandidate, Ray, ReferenceId,
+    TransformFilter, UpdateParams, UpdateStatus, VertexAttributeLayout,
+};
+pub use crate::group::GizmoGroup;
 
 pub use enumset::{enum_set, EnumSet};
 
 pub use mint;
 
-pub use ecolor::Color32;
-pub use emath::Rect;
+pub use crate::config::Color32;
+pub use crate::math::Rect;
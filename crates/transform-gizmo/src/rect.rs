@@ -0,0 +1,420 @@
+//! Screen space positions, vectors and rectangles used in the API of the gizmo.
+//!
+//! With the `epaint` feature, these convert to and from the equivalent `emath` types.
+
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Position on the screen, in pixels
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Pos2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Pos2 {
+    pub const ZERO: Self = Self::new(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Vector from the origin to this position
+    pub const fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+
+    pub fn distance_sq(self, other: Self) -> f32 {
+        (self - other).length_sq()
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+}
+
+impl From<(f32, f32)> for Pos2 {
+    fn from((x, y): (f32, f32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<[f32; 2]> for Pos2 {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Pos2> for (f32, f32) {
+    fn from(pos: Pos2) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+impl From<Pos2> for [f32; 2] {
+    fn from(pos: Pos2) -> Self {
+        [pos.x, pos.y]
+    }
+}
+
+impl Add<Vec2> for Pos2 {
+    type Output = Self;
+
+    fn add(self, rhs: Vec2) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vec2> for Pos2 {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vec2> for Pos2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Vec2) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vec2> for Pos2 {
+    fn sub_assign(&mut self, rhs: Vec2) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Pos2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Self) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+/// Vector on the screen, in pixels
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self::splat(0.0);
+    pub const ONE: Self = Self::splat(1.0);
+    pub const X: Self = Self::new(1.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn splat(value: f32) -> Self {
+        Self::new(value, value)
+    }
+
+    /// Position at the end of this vector, starting from the origin
+    pub const fn to_pos2(self) -> Pos2 {
+        Pos2::new(self.x, self.y)
+    }
+
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn length_sq(self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// Vector of unit length in the same direction, or zero for a zero vector
+    pub fn normalized(self) -> Self {
+        let length = self.length();
+        if length > 0.0 {
+            self / length
+        } else {
+            self
+        }
+    }
+
+    /// Rotated 90 degrees clockwise on the screen, where the y axis points down
+    pub fn rot90(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+}
+
+impl From<(f32, f32)> for Vec2 {
+    fn from((x, y): (f32, f32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<[f32; 2]> for Vec2 {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Vec2> for [f32; 2] {
+    fn from(vec: Vec2) -> Self {
+        [vec.x, vec.y]
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Mul<Vec2> for f32 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: Vec2) -> Vec2 {
+        rhs * self
+    }
+}
+
+impl Mul for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.x * rhs.x, self.y * rhs.y)
+    }
+}
+
+impl Div<f32> for Vec2 {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl Div for Vec2 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self::new(self.x / rhs.x, self.y / rhs.y)
+    }
+}
+
+/// Axis-aligned rectangle on the screen, in pixels.
+///
+/// The y axis points down, so `min` is the top left corner and `max` is the bottom right corner.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Rect {
+    pub min: Pos2,
+    pub max: Pos2,
+}
+
+impl Rect {
+    /// Infinite rectangle that contains every point
+    pub const EVERYTHING: Self = Self::from_min_max(
+        Pos2::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        Pos2::new(f32::INFINITY, f32::INFINITY),
+    );
+
+    /// Inverted rectangle that contains nothing, and becomes the given point
+    /// when extended with [`Rect::extend_with`]
+    pub const NOTHING: Self = Self::from_min_max(
+        Pos2::new(f32::INFINITY, f32::INFINITY),
+        Pos2::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+    );
+
+    pub const fn from_min_max(min: Pos2, max: Pos2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_min_size(min: Pos2, size: Vec2) -> Self {
+        Self::from_min_max(min, min + size)
+    }
+
+    pub fn from_center_size(center: Pos2, size: Vec2) -> Self {
+        Self::from_min_max(center - size * 0.5, center + size * 0.5)
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Pos2 {
+        Pos2::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+        )
+    }
+
+    pub fn left(&self) -> f32 {
+        self.min.x
+    }
+
+    pub fn right(&self) -> f32 {
+        self.max.x
+    }
+
+    pub fn top(&self) -> f32 {
+        self.min.y
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.max.y
+    }
+
+    /// Whether the rectangle has a positive width and height
+    pub fn is_positive(&self) -> bool {
+        self.min.x < self.max.x && self.min.y < self.max.y
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+
+    /// Whether the point is inside the rectangle or on its edges
+    pub fn contains(&self, pos: Pos2) -> bool {
+        self.min.x <= pos.x && pos.x <= self.max.x && self.min.y <= pos.y && pos.y <= self.max.y
+    }
+
+    pub fn translate(self, offset: Vec2) -> Self {
+        Self::from_min_max(self.min + offset, self.max + offset)
+    }
+
+    /// Smallest rectangle containing both rectangles
+    pub fn union(self, other: Self) -> Self {
+        Self::from_min_max(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Overlapping area of both rectangles, which is not positive if they do not overlap
+    pub fn intersect(self, other: Self) -> Self {
+        Self::from_min_max(self.min.max(other.min), self.max.min(other.max))
+    }
+
+    /// Expands the rectangle to contain the point
+    pub fn extend_with(&mut self, pos: Pos2) {
+        self.min = self.min.min(pos);
+        self.max = self.max.max(pos);
+    }
+}
+
+/// Maps a position in the `from` rectangle to the same relative position in the `to` rectangle
+pub(crate) fn remap_pos(from: Rect, to: Rect, pos: Pos2) -> Pos2 {
+    Pos2::new(
+        to.min.x + (pos.x - from.min.x) / from.width() * to.width(),
+        to.min.y + (pos.y - from.min.y) / from.height() * to.height(),
+    )
+}
+
+#[cfg(feature = "epaint")]
+impl From<emath::Pos2> for Pos2 {
+    fn from(pos: emath::Pos2) -> Self {
+        Self::new(pos.x, pos.y)
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<Pos2> for emath::Pos2 {
+    fn from(pos: Pos2) -> Self {
+        Self::new(pos.x, pos.y)
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<emath::Vec2> for Vec2 {
+    fn from(vec: emath::Vec2) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<Vec2> for emath::Vec2 {
+    fn from(vec: Vec2) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<emath::Rect> for Rect {
+    fn from(rect: emath::Rect) -> Self {
+        Self::from_min_max(rect.min.into(), rect.max.into())
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<Rect> for emath::Rect {
+    fn from(rect: Rect) -> Self {
+        Self::from_min_max(rect.min.into(), rect.max.into())
+    }
+}
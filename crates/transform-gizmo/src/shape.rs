@@ -1,23 +1,103 @@
-use std::f64::consts::TAU;
+use crate::math::consts::TAU;
 
-use crate::math::{Pos2, Rect};
-use ecolor::Color32;
-use epaint::{Mesh, TessellationOptions, Tessellator, TextureId};
-pub(crate) use epaint::{Shape, Stroke};
-use glam::{DMat4, DVec3};
+use crate::config::Color32;
+use crate::math::{FMat4, FVec3, Float, Pos2, Rect};
+#[cfg(feature = "epaint")]
+use epaint::{TessellationOptions, Tessellator};
 
 use crate::math::world_to_screen;
 
-const STEPS_PER_RAD: f64 = 20.0;
+const STEPS_PER_RAD: Float = 20.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Stroke {
+    pub width: f32,
+    pub color: Color32,
+}
+
+impl Stroke {
+    pub const NONE: Self = Self {
+        width: 0.0,
+        color: Color32::TRANSPARENT,
+    };
+}
+
+impl<Color: Into<Color32>> From<(f32, Color)> for Stroke {
+    fn from((width, color): (f32, Color)) -> Self {
+        Self {
+            width,
+            color: color.into(),
+        }
+    }
+}
+
+pub(crate) enum Shape {
+    Noop,
+    LineSegment {
+        points: [Pos2; 2],
+        stroke: Stroke,
+    },
+    Path {
+        points: Vec<Pos2>,
+        closed: bool,
+        fill: Color32,
+        stroke: Stroke,
+    },
+}
+
+impl Shape {
+    pub(crate) fn line(points: Vec<Pos2>, stroke: impl Into<Stroke>) -> Self {
+        Self::Path {
+            points,
+            closed: false,
+            fill: Color32::TRANSPARENT,
+            stroke: stroke.into(),
+        }
+    }
+
+    pub(crate) fn closed_line(points: Vec<Pos2>, stroke: impl Into<Stroke>) -> Self {
+        Self::Path {
+            points,
+            closed: true,
+            fill: Color32::TRANSPARENT,
+            stroke: stroke.into(),
+        }
+    }
+
+    pub(crate) fn convex_polygon(
+        points: Vec<Pos2>,
+        fill: impl Into<Color32>,
+        stroke: impl Into<Stroke>,
+    ) -> Self {
+        Self::Path {
+            points,
+            closed: true,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Vertex {
+    pub pos: Pos2,
+    pub color: Color32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
 
 pub(crate) struct ShapeBuidler {
-    mvp: DMat4,
+    mvp: FMat4,
     viewport: Rect,
     pixels_per_point: f32,
 }
 
 impl ShapeBuidler {
-    pub(crate) fn new(mvp: DMat4, viewport: Rect, pixels_per_point: f32) -> Self {
+    pub(crate) fn new(mvp: FMat4, viewport: Rect, pixels_per_point: f32) -> Self {
         Self {
             mvp,
             viewport,
@@ -25,6 +105,7 @@ impl ShapeBuidler {
         }
     }
 
+    #[cfg(feature = "epaint")]
     fn tessellate_shape(&self, shape: Shape) -> Mesh {
         let mut tessellator = Tessellator::new(
             self.pixels_per_point,
@@ -36,26 +117,40 @@ impl ShapeBuidler {
             Default::default(),
         );
 
-        let mut mesh = Mesh::default();
-        tessellator.tessellate_shape(shape, &mut mesh);
+        let mut mesh = epaint::Mesh::default();
+        tessellator.tessellate_shape(shape.into(), &mut mesh);
+
+        Mesh {
+            vertices: mesh
+                .vertices
+                .into_iter()
+                .map(|vertex| Vertex {
+                    pos: vertex.pos.into(),
+                    color: vertex.color.into(),
+                })
+                .collect(),
+            indices: mesh.indices,
+        }
+    }
 
-        mesh.texture_id = TextureId::default();
-        mesh
+    #[cfg(not(feature = "epaint"))]
+    fn tessellate_shape(&self, shape: Shape) -> Mesh {
+        crate::tessellator::tessellate(shape, self.pixels_per_point)
     }
 
-    fn arc_points(&self, radius: f64, start_angle: f64, end_angle: f64) -> Vec<Pos2> {
-        let angle = f64::clamp(end_angle - start_angle, -TAU, TAU);
+    fn arc_points(&self, radius: Float, start_angle: Float, end_angle: Float) -> Vec<Pos2> {
+        let angle = Float::clamp(end_angle - start_angle, -TAU, TAU);
 
         let step_count = steps(angle);
         let mut points = Vec::with_capacity(step_count);
 
-        let step_size = angle / (step_count - 1) as f64;
+        let step_size = angle / (step_count - 1) as Float;
 
-        for step in (0..step_count).map(|i| step_size * i as f64) {
-            let x = f64::cos(start_angle + step) * radius;
-            let z = f64::sin(start_angle + step) * radius;
+        for step in (0..step_count).map(|i| step_size * i as Float) {
+            let x = Float::cos(start_angle + step) * radius;
+            let z = Float::sin(start_angle + step) * radius;
 
-            points.push(DVec3::new(x, 0.0, z));
+            points.push(FVec3::new(x, 0.0, z));
         }
 
         points
@@ -66,9 +161,9 @@ impl ShapeBuidler {
 
     pub(crate) fn arc(
         &self,
-        radius: f64,
-        start_angle: f64,
-        end_angle: f64,
+        radius: Float,
+        start_angle: Float,
+        end_angle: Float,
         stroke: impl Into<Stroke>,
     ) -> Mesh {
         let mut points = self.arc_points(radius, start_angle, end_angle);
@@ -87,13 +182,13 @@ impl ShapeBuidler {
         })
     }
 
-    pub(crate) fn circle(&self, radius: f64, stroke: impl Into<Stroke>) -> Mesh {
+    pub(crate) fn circle(&self, radius: Float, stroke: impl Into<Stroke>) -> Mesh {
         self.arc(radius, 0.0, TAU, stroke)
     }
 
     pub(crate) fn filled_circle(
         &self,
-        radius: f64,
+        radius: Float,
         color: Color32,
         stroke: impl Into<Stroke>,
     ) -> Mesh {
@@ -103,7 +198,7 @@ impl ShapeBuidler {
         self.tessellate_shape(Shape::convex_polygon(points, color, stroke.into()))
     }
 
-    pub(crate) fn line_segment(&self, from: DVec3, to: DVec3, stroke: impl Into<Stroke>) -> Mesh {
+    pub(crate) fn line_segment(&self, from: FVec3, to: FVec3, stroke: impl Into<Stroke>) -> Mesh {
         let mut points: [Pos2; 2] = Default::default();
 
         for (i, point) in points.iter_mut().enumerate() {
@@ -120,7 +215,7 @@ impl ShapeBuidler {
         })
     }
 
-    pub(crate) fn arrow(&self, from: DVec3, to: DVec3, stroke: impl Into<Stroke>) -> Mesh {
+    pub(crate) fn arrow(&self, from: FVec3, to: FVec3, stroke: impl Into<Stroke>) -> Mesh {
         let stroke = stroke.into();
         let arrow_start = world_to_screen(self.viewport, self.mvp, from);
         let arrow_end = world_to_screen(self.viewport, self.mvp, to);
@@ -140,7 +235,7 @@ impl ShapeBuidler {
 
     pub(crate) fn polygon(
         &self,
-        points: &[DVec3],
+        points: &[FVec3],
         fill: impl Into<Color32>,
         stroke: impl Into<Stroke>,
     ) -> Mesh {
@@ -156,7 +251,7 @@ impl ShapeBuidler {
         })
     }
 
-    pub(crate) fn polyline(&self, points: &[DVec3], stroke: impl Into<Stroke>) -> Mesh {
+    pub(crate) fn polyline(&self, points: &[FVec3], stroke: impl Into<Stroke>) -> Mesh {
         let points = points
             .iter()
             .filter_map(|pos| world_to_screen(self.viewport, self.mvp, *pos))
@@ -171,9 +266,9 @@ impl ShapeBuidler {
 
     pub(crate) fn sector(
         &self,
-        radius: f64,
-        start_angle: f64,
-        end_angle: f64,
+        radius: Float,
+        start_angle: Float,
+        end_angle: Float,
         fill: impl Into<Color32>,
         stroke: impl Into<Stroke>,
     ) -> Mesh {
@@ -186,13 +281,13 @@ impl ShapeBuidler {
 
         let mut points = Vec::with_capacity(step_count + 1);
 
-        let step_size = angle_delta / (step_count - 1) as f64;
+        let step_size = angle_delta / (step_count - 1) as Float;
 
         if ((start_angle - end_angle).abs() - TAU).abs() < step_size.abs() {
             return self.filled_circle(radius, fill.into(), stroke);
         }
 
-        points.push(DVec3::new(0.0, 0.0, 0.0));
+        points.push(FVec3::new(0.0, 0.0, 0.0));
 
         let (sin_step, cos_step) = step_size.sin_cos();
         let (mut sin_angle, mut cos_angle) = start_angle.sin_cos();
@@ -201,7 +296,7 @@ impl ShapeBuidler {
             let x = cos_angle * radius;
             let z = sin_angle * radius;
 
-            points.push(DVec3::new(x, 0.0, z));
+            points.push(FVec3::new(x, 0.0, z));
 
             let new_sin = sin_angle * cos_step + cos_angle * sin_step;
             let new_cos = cos_angle * cos_step - sin_angle * sin_step;
@@ -218,11 +313,42 @@ impl ShapeBuidler {
         self.tessellate_shape(Shape::convex_polygon(points, fill, stroke))
     }
 
-    fn vec3_to_pos2(&self, vec: DVec3) -> Option<Pos2> {
+    fn vec3_to_pos2(&self, vec: FVec3) -> Option<Pos2> {
         world_to_screen(self.viewport, self.mvp, vec)
     }
 }
 
-fn steps(angle: f64) -> usize {
+fn steps(angle: Float) -> usize {
     (STEPS_PER_RAD * angle.abs()).ceil().max(1.0) as usize
 }
+
+#[cfg(feature = "epaint")]
+impl From<Stroke> for epaint::Stroke {
+    fn from(stroke: Stroke) -> Self {
+        Self::new(stroke.width, stroke.color)
+    }
+}
+
+#[cfg(feature = "epaint")]
+impl From<Shape> for epaint::Shape {
+    fn from(shape: Shape) -> Self {
+        match shape {
+            Shape::Noop => Self::Noop,
+            Shape::LineSegment { points, stroke } => Self::LineSegment {
+                points: points.map(Into::into),
+                stroke: stroke.into(),
+            },
+            Shape::Path {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => Self::Path(epaint::PathShape {
+                points: points.into_iter().map(Into::into).collect(),
+                closed,
+                fill: fill.into(),
+                stroke: stroke.into(),
+            }),
+        }
+    }
+}
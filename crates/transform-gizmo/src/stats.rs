@@ -0,0 +1,122 @@
+//! Counters describing how a gizmo is used.
+//!
+//! Tool developers can use these to find out which handles of the gizmo are actually
+//! used and how often drags are abandoned without changing anything.
+//! Recording the counters does not allocate.
+//!
+//! # Examples
+//!
+//! ```
+//! use transform_gizmo::prelude::*;
+//!
+//! let mut gizmo = Gizmo::default();
+//!
+//! let stats = gizmo.stats();
+//! println!(
+//!     "X axis translated {} times, {:.0}% of drags cancelled",
+//!     stats.drag_count(GizmoMode::TranslateX),
+//!     stats.cancel_rate() * 100.0
+//! );
+//!
+//! gizmo.reset_stats();
+//! ```
+
+use enumset::EnumSet;
+use glam::{DQuat, DVec3};
+
+use crate::config::GizmoMode;
+use crate::gizmo::GizmoResult;
+
+/// Number of counters reserved for the drags of each mode.
+/// Must be at least the number of [`GizmoMode`] variants.
+const MODE_SLOTS: usize = 32;
+
+/// Interaction counters of a [`Gizmo`](crate::Gizmo).
+///
+/// Retrieved with [`Gizmo::stats`](crate::Gizmo::stats).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GizmoStats {
+    drags: [u32; MODE_SLOTS],
+    cancelled_drags: u32,
+    rotated_angle: f64,
+    translated_distance: f64,
+    /// Whether the current drag has produced a result
+    drag_produced_result: bool,
+}
+
+impl GizmoStats {
+    /// Number of drags started with given mode.
+    pub fn drag_count(&self, mode: GizmoMode) -> u32 {
+        self.drags[mode_index(mode)]
+    }
+
+    /// Number of drags started with any mode.
+    pub fn total_drag_count(&self) -> u32 {
+        self.drags.iter().sum()
+    }
+
+    /// Number of drags that ended without producing any result.
+    pub fn cancelled_drag_count(&self) -> u32 {
+        self.cancelled_drags
+    }
+
+    /// Ratio of cancelled drags to all drags, between 0 and 1.
+    pub fn cancel_rate(&self) -> f32 {
+        let total = self.total_drag_count();
+
+        if total == 0 {
+            0.0
+        } else {
+            self.cancelled_drags as f32 / total as f32
+        }
+    }
+
+    /// Total absolute angle of rotations, in radians.
+    /// Includes both axis rotations and arcball rotations.
+    pub fn rotated_angle(&self) -> f64 {
+        self.rotated_angle
+    }
+
+    /// Total absolute distance of translations, in world units.
+    pub fn translated_distance(&self) -> f64 {
+        self.translated_distance
+    }
+
+    /// Resets all counters to zero.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub(crate) fn record_drag_start(&mut self, mode: GizmoMode) {
+        self.drags[mode_index(mode)] += 1;
+        self.drag_produced_result = false;
+    }
+
+    pub(crate) fn record_drag_end(&mut self) {
+        if !self.drag_produced_result {
+            self.cancelled_drags += 1;
+        }
+    }
+
+    pub(crate) fn record_result(&mut self, result: &GizmoResult) {
+        self.drag_produced_result = true;
+
+        match result {
+            GizmoResult::Rotation { delta, .. } => {
+                self.rotated_angle += delta.abs();
+            }
+            GizmoResult::Arcball { delta, .. } | GizmoResult::Direction { delta, .. } => {
+                self.rotated_angle += DQuat::IDENTITY.angle_between(DQuat::from(*delta));
+            }
+            GizmoResult::Translation { delta, .. } => {
+                self.translated_distance += DVec3::from(*delta).length();
+            }
+            GizmoResult::Scale { .. } | GizmoResult::Bounds { .. } => {}
+        }
+    }
+}
+
+/// Index of the drag counter of given mode
+fn mode_index(mode: GizmoMode) -> usize {
+    EnumSet::only(mode).as_u32().trailing_zeros() as usize
+}
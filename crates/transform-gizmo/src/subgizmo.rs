@@ -1,20 +1,37 @@
-use std::any::Any;
 use std::fmt::Debug;
-use std::hash::{BuildHasher, Hash, Hasher};
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
 use enum_dispatch::enum_dispatch;
 
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoResult};
+use crate::math::{FVec3, Float};
+use crate::subgizmo::common::PickResult;
+use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoMode, GizmoResult};
 
+#[cfg(feature = "arcball")]
 pub(crate) use arcball::ArcballSubGizmo;
+#[cfg(feature = "scale")]
+pub(crate) use bounds::BoundsSubGizmo;
+pub(crate) use compass::CompassSubGizmo;
+pub(crate) use constrained::ConstrainedSubGizmo;
+pub(crate) use direction::DirectionSubGizmo;
+#[cfg(feature = "rotate")]
 pub(crate) use rotation::RotationSubGizmo;
+#[cfg(feature = "scale")]
 pub(crate) use scale::ScaleSubGizmo;
 pub(crate) use translation::TranslationSubGizmo;
 
+#[cfg(feature = "arcball")]
 pub(crate) mod arcball;
+#[cfg(feature = "scale")]
+pub(crate) mod bounds;
 pub(crate) mod common;
+pub(crate) mod compass;
+pub(crate) mod constrained;
+pub(crate) mod direction;
+#[cfg(feature = "rotate")]
 pub(crate) mod rotation;
+#[cfg(feature = "scale")]
 pub(crate) mod scale;
 pub(crate) mod translation;
 
@@ -22,16 +39,26 @@ pub(crate) mod translation;
 /// Enumeration of different subgizmo types.
 #[enum_dispatch(SubGizmoControl)]
 pub(crate) enum SubGizmo {
+    #[cfg(feature = "rotate")]
     Rotate(RotationSubGizmo),
     Translate(TranslationSubGizmo),
+    #[cfg(feature = "scale")]
     Scale(ScaleSubGizmo),
+    #[cfg(feature = "arcball")]
     Arcball(ArcballSubGizmo),
+    #[cfg(feature = "scale")]
+    Bounds(BoundsSubGizmo),
+    Compass(CompassSubGizmo),
+    Constrained(ConstrainedSubGizmo),
+    Direction(DirectionSubGizmo),
 }
 
 #[enum_dispatch]
 pub(crate) trait SubGizmoControl {
     /// Unique identifier for this subgizmo.
     fn id(&self) -> u64;
+    /// Mode of the subgizmo
+    fn mode(&self) -> GizmoMode;
     /// Update the configuration used by the gizmo.
     fn update_config(&mut self, config: PreparedGizmoConfig);
     /// Sets whether this subgizmo is currently focused.
@@ -44,9 +71,23 @@ pub(crate) trait SubGizmoControl {
     fn is_active(&self) -> bool;
     /// Pick the subgizmo based on pointer ray. If it is close enough to
     /// the mouse pointer, distance from camera to the subgizmo is returned.
-    fn pick(&mut self, ray: Ray) -> Option<f64>;
+    fn pick(&mut self, ray: Ray) -> Option<Float>;
+    /// Approximate projected area of the pickable part of the subgizmo.
+    /// When multiple subgizmos are picked, the smallest one is preferred.
+    fn pick_area(&self) -> Float;
+    /// Details of the latest pick, if known. Used for debugging picking.
+    fn last_pick(&self) -> Option<PickResult>;
+    /// Visibility of the subgizmo as of the latest pick, from 0 to 1.
+    /// Fully faded out subgizmos cannot be picked.
+    fn visibility(&self) -> Float;
     /// Update the subgizmo based on pointer ray and interaction.
     fn update(&mut self, ray: Ray) -> Option<GizmoResult>;
+    /// Whether the latest update moved the snapped transformation to another snapping increment.
+    fn snap_crossed(&self) -> bool;
+    /// Transform by the given number of mouse wheel steps, without dragging.
+    fn wheel(&self, steps: Float) -> Option<GizmoResult>;
+    /// World space points where the latest drag was started and where it currently is.
+    fn interaction_points(&self) -> Option<(FVec3, FVec3)>;
     /// Draw the subgizmo.
     fn draw(&self) -> GizmoDrawData;
 }
@@ -55,12 +96,36 @@ pub(crate) trait SubGizmoKind: 'static {
     type Params: Debug + Copy + Hash;
     type State: Debug + Copy + Clone + Send + Sync + Default + 'static;
 
-    fn pick(subgizmo: &mut SubGizmoConfig<Self>, ray: Ray) -> Option<f64>
+    fn pick(subgizmo: &mut SubGizmoConfig<Self>, ray: Ray) -> Option<Float>
+    where
+        Self: Sized;
+    fn pick_area(subgizmo: &SubGizmoConfig<Self>) -> Float
+    where
+        Self: Sized;
+    fn mode(subgizmo: &SubGizmoConfig<Self>) -> GizmoMode
     where
         Self: Sized;
     fn update(subgizmo: &mut SubGizmoConfig<Self>, ray: Ray) -> Option<GizmoResult>
     where
         Self: Sized;
+    fn wheel(_subgizmo: &SubGizmoConfig<Self>, _steps: Float) -> Option<GizmoResult>
+    where
+        Self: Sized,
+    {
+        None
+    }
+    fn visibility(subgizmo: &SubGizmoConfig<Self>) -> Float
+    where
+        Self: Sized,
+    {
+        subgizmo.last_pick.map_or(1.0, |pick| pick.visibility)
+    }
+    fn interaction_points(_subgizmo: &SubGizmoConfig<Self>) -> Option<(FVec3, FVec3)>
+    where
+        Self: Sized,
+    {
+        None
+    }
     fn draw(subgizmo: &SubGizmoConfig<Self>) -> GizmoDrawData
     where
         Self: Sized;
@@ -81,6 +146,12 @@ pub(crate) struct SubGizmoConfig<T: SubGizmoKind> {
     /// Opacity of the subgizmo for this frame.
     /// A fully invisible subgizmo cannot be interacted with.
     pub(crate) opacity: f32,
+    /// Whether the latest update crossed a snapping boundary.
+    /// Set by the subgizmo kinds that support snapping.
+    pub(crate) snap_crossed: bool,
+    /// Details of the latest pick. Set by the subgizmo kinds that pick with
+    /// the common picking functions, and otherwise derived from the picked distance.
+    pub(crate) last_pick: Option<PickResult>,
     /// Implementation-specific state of the subgizmo.
     pub(crate) state: T::State,
 }
@@ -98,8 +169,8 @@ where
     T: SubGizmoKind,
 {
     pub(crate) fn new(config: PreparedGizmoConfig, params: T::Params) -> Self {
-        let mut hasher = ahash::RandomState::with_seeds(1, 2, 3, 4).build_hasher();
-        params.type_id().hash(&mut hasher);
+        let mut hasher = StableHasher::default();
+        std::any::type_name::<T>().hash(&mut hasher);
         params.hash(&mut hasher);
         let id = hasher.finish();
 
@@ -110,11 +181,59 @@ where
             focused: false,
             active: false,
             opacity: 0.0,
+            snap_crossed: false,
+            last_pick: None,
             state: Default::default(),
         }
     }
 }
 
+/// FNV-1a hasher for the subgizmo ids. Unlike the hashers of the standard library
+/// and most hashing crates, its output does not depend on the platform, the compiler
+/// version or random seeds, so the ids are the same on every run.
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    // Integers are hashed in native byte order by default
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
 impl<T> SubGizmoControl for SubGizmoConfig<T>
 where
     T: SubGizmoKind,
@@ -122,6 +241,10 @@ where
     fn id(&self) -> u64 {
         self.id
     }
+
+    fn mode(&self) -> GizmoMode {
+        T::mode(self)
+    }
     fn update_config(&mut self, config: PreparedGizmoConfig) {
         self.config = config;
     }
@@ -142,14 +265,52 @@ where
         self.active
     }
 
-    fn pick(&mut self, ray: Ray) -> Option<f64> {
-        T::pick(self, ray)
+    fn pick(&mut self, ray: Ray) -> Option<Float> {
+        self.last_pick = None;
+        let t = T::pick(self, ray);
+
+        if self.last_pick.is_none() {
+            let visibility = T::visibility(self);
+            self.last_pick = t.map(|t| PickResult {
+                subgizmo_point: ray.origin + ray.direction * t,
+                visibility,
+                picked: true,
+                t,
+            });
+        }
+
+        t
+    }
+
+    fn pick_area(&self) -> Float {
+        T::pick_area(self)
+    }
+
+    fn last_pick(&self) -> Option<PickResult> {
+        self.last_pick
+    }
+
+    fn visibility(&self) -> Float {
+        T::visibility(self)
     }
 
     fn update(&mut self, ray: Ray) -> Option<GizmoResult> {
+        self.snap_crossed = false;
         T::update(self, ray)
     }
 
+    fn snap_crossed(&self) -> bool {
+        self.snap_crossed
+    }
+
+    fn wheel(&self, steps: Float) -> Option<GizmoResult> {
+        T::wheel(self, steps)
+    }
+
+    fn interaction_points(&self) -> Option<(FVec3, FVec3)> {
+        T::interaction_points(self)
+    }
+
     fn draw(&self) -> GizmoDrawData {
         T::draw(self)
     }
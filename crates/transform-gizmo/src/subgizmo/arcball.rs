@@ -1,15 +1,24 @@
-use crate::math::{screen_to_world, DQuat, Pos2};
-use crate::subgizmo::common::{draw_circle, pick_circle};
+use crate::config::Color32;
+use crate::math::consts::TAU;
+use crate::math::{
+    quat_to_mint, round_to_interval, screen_to_world, FMat4, FQuat, FVec3, Float, Pos2,
+};
+use crate::shape::ShapeBuidler;
+use crate::subgizmo::common::{
+    circle_area, draw_circle, pick_circle, rotation_pivot, view_plane_rotation,
+};
 use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoResult};
-use ecolor::Color32;
+use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoMode, GizmoResult};
 
 pub(crate) type ArcballSubGizmo = SubGizmoConfig<Arcball>;
 
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct ArcballState {
     last_pos: Pos2,
-    total_rotation: DQuat,
+    /// Total rotation, before snapping
+    total_rotation: FQuat,
+    /// Total rotation, after snapping
+    snapped_rotation: FQuat,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -19,7 +28,7 @@ impl SubGizmoKind for Arcball {
     type Params = ();
     type State = ArcballState;
 
-    fn pick(subgizmo: &mut ArcballSubGizmo, ray: Ray) -> Option<f64> {
+    fn pick(subgizmo: &mut ArcballSubGizmo, ray: Ray) -> Option<Float> {
         let pick_result = pick_circle(
             &subgizmo.config,
             ray,
@@ -28,19 +37,30 @@ impl SubGizmoKind for Arcball {
         );
 
         subgizmo.state.last_pos = ray.screen_pos;
+        subgizmo.state.total_rotation = FQuat::IDENTITY;
+        subgizmo.state.snapped_rotation = FQuat::IDENTITY;
+        subgizmo.last_pick = Some(pick_result);
 
         if !pick_result.picked {
             return None;
         }
 
-        Some(f64::MAX)
+        Some(Float::MAX)
+    }
+
+    fn mode(_subgizmo: &ArcballSubGizmo) -> GizmoMode {
+        GizmoMode::Arcball
+    }
+
+    fn pick_area(subgizmo: &ArcballSubGizmo) -> Float {
+        circle_area(&subgizmo.config, arcball_radius(&subgizmo.config), true)
     }
 
     fn update(subgizmo: &mut ArcballSubGizmo, ray: Ray) -> Option<GizmoResult> {
         let dir = ray.screen_pos - subgizmo.state.last_pos;
 
         let rotation_delta = if dir.length_sq() > f32::EPSILON {
-            let mat = subgizmo.config.view_projection.inverse();
+            let mat = subgizmo.config.inverse_view_projection;
             let a = screen_to_world(subgizmo.config.viewport, mat, ray.screen_pos, 0.0);
             let b = screen_to_world(subgizmo.config.viewport, mat, subgizmo.state.last_pos, 0.0);
 
@@ -48,31 +68,88 @@ impl SubGizmoKind for Arcball {
             let a = (a - origin).normalize();
             let b = (b - origin).normalize();
 
-            DQuat::from_axis_angle(a.cross(b).normalize(), a.dot(b).acos() * 10.0)
+            FQuat::from_axis_angle(a.cross(b).normalize(), a.dot(b).acos() * 10.0)
         } else {
-            DQuat::IDENTITY
+            FQuat::IDENTITY
         };
 
         subgizmo.state.last_pos = ray.screen_pos;
         subgizmo.state.total_rotation = rotation_delta.mul_quat(subgizmo.state.total_rotation);
 
+        let mut total_rotation = subgizmo.state.total_rotation;
+
+        if subgizmo.config.snapping {
+            let (axis, angle) = total_rotation.to_axis_angle();
+            let angle = round_to_interval(angle, Float::from(subgizmo.config.snap_angle));
+            total_rotation = FQuat::from_axis_angle(axis, angle);
+        }
+
+        let rotation_delta = total_rotation * subgizmo.state.snapped_rotation.inverse();
+        subgizmo.snap_crossed =
+            subgizmo.config.snapping && total_rotation != subgizmo.state.snapped_rotation;
+        subgizmo.state.snapped_rotation = total_rotation;
+
         Some(GizmoResult::Arcball {
-            delta: rotation_delta.into(),
-            total: subgizmo.state.total_rotation.into(),
+            delta: quat_to_mint(rotation_delta),
+            total: quat_to_mint(total_rotation),
+            pivot: rotation_pivot(&subgizmo.config),
+            snapped_to: None,
         })
     }
 
     fn draw(subgizmo: &ArcballSubGizmo) -> GizmoDrawData {
-        draw_circle(
+        let mut draw_data = draw_circle(
             &subgizmo.config,
             Color32::WHITE.gamma_multiply(if subgizmo.focused { 0.10 } else { 0.0 }),
             arcball_radius(&subgizmo.config),
             true,
-        )
+        );
+
+        if subgizmo.active && subgizmo.config.snapping && subgizmo.config.snap_angle > 0.0 {
+            draw_data += draw_snapping_ticks(subgizmo);
+        }
+
+        draw_data
     }
 }
 
+/// Draws ticks around the arcball at snapping intervals.
+/// The ticks are rotated by the snapped rotation angle.
+fn draw_snapping_ticks(subgizmo: &ArcballSubGizmo) -> GizmoDrawData {
+    let config = subgizmo.config;
+
+    let transform =
+        FMat4::from_rotation_translation(view_plane_rotation(&config), config.translation);
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection * transform,
+        config.viewport,
+        config.pixels_per_point,
+    );
+
+    let radius = arcball_radius(&config);
+    let snap_angle = Float::from(config.snap_angle);
+    let (_, start_angle) = subgizmo.state.snapped_rotation.to_axis_angle();
+    let stroke = (
+        config.visuals.stroke_width / 2.0,
+        Color32::WHITE.gamma_multiply(0.5),
+    );
+
+    let mut draw_data = GizmoDrawData::default();
+
+    for i in 0..((TAU / snap_angle) as usize) {
+        let angle = i as Float * snap_angle + start_angle;
+        let pos = FVec3::new(angle.cos(), 0.0, angle.sin());
+        draw_data += shape_builder
+            .line_segment(pos * radius * 1.05, pos * radius * 1.15, stroke)
+            .into();
+    }
+
+    draw_data
+}
+
 /// Radius to use for outer circle subgizmos
-pub(crate) fn arcball_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width - 5.0)) as f64
+pub(crate) fn arcball_radius(config: &PreparedGizmoConfig) -> Float {
+    Float::from(
+        config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width - 5.0),
+    )
 }
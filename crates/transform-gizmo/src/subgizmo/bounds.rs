@@ -0,0 +1,269 @@
+use crate::config::Color32;
+use crate::math::{
+    intersect_plane, quat_to_mint, ray_to_ray, round_to_interval, vec3_to_mint, world_to_screen,
+    FMat4, FQuat, FVec3, Float,
+};
+use crate::shape::ShapeBuidler;
+use crate::subgizmo::common::{gizmo_color, view_plane_rotation};
+use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
+use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoResult};
+
+pub(crate) type BoundsSubGizmo = SubGizmoConfig<Bounds>;
+
+/// Smallest allowed size of the bounds along an axis, relative to the initial size
+const MIN_RELATIVE_SIZE: Float = 1e-3;
+
+#[derive(Debug, Copy, Clone, Hash)]
+pub(crate) struct BoundsParams {
+    /// Side of the bounds the handle is on, for each axis.
+    /// Face handles have one non-zero component, corner handles have three.
+    pub handle: [i8; 3],
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct BoundsState {
+    /// Gizmo translation when the drag was started
+    start_translation: FVec3,
+    /// Gizmo rotation when the drag was started
+    start_rotation: FQuat,
+    /// Bounds when the drag was started, in the gizmo space
+    start_bounds: (FVec3, FVec3),
+    /// Current bounds during the drag, in the gizmo space
+    bounds: (FVec3, FVec3),
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct Bounds;
+
+impl SubGizmoKind for Bounds {
+    type Params = BoundsParams;
+    type State = BoundsState;
+
+    fn pick(subgizmo: &mut BoundsSubGizmo, ray: Ray) -> Option<Float> {
+        let bounds = subgizmo.config.bounds?;
+
+        subgizmo.state.start_translation = subgizmo.config.translation;
+        subgizmo.state.start_rotation = subgizmo.config.rotation;
+        subgizmo.state.start_bounds = bounds;
+        subgizmo.state.bounds = bounds;
+
+        let handle_pos = handle_world_pos(subgizmo);
+        let screen_pos = world_to_screen(
+            subgizmo.config.viewport,
+            subgizmo.config.view_projection,
+            handle_pos,
+        )?;
+
+        let pick_radius = handle_radius(subgizmo)
+            + subgizmo.config.visuals.stroke_width / 2.0
+            + subgizmo.config.pick_tolerance();
+
+        if screen_pos.distance(ray.screen_pos) <= pick_radius {
+            Some((handle_pos - ray.origin).length())
+        } else {
+            None
+        }
+    }
+
+    fn mode(_subgizmo: &BoundsSubGizmo) -> GizmoMode {
+        GizmoMode::Bounds
+    }
+
+    fn pick_area(subgizmo: &BoundsSubGizmo) -> Float {
+        let radius = Float::from(subgizmo.config.scale_factor * handle_radius(subgizmo));
+        radius * radius * 4.0
+    }
+
+    fn update(subgizmo: &mut BoundsSubGizmo, ray: Ray) -> Option<GizmoResult> {
+        let state = subgizmo.state;
+        let sides = subgizmo.handle;
+        let handle = handle_dir(subgizmo);
+        let (start_min, start_max) = state.start_bounds;
+        let start_size = start_max - start_min;
+
+        let handle_pos = state.start_translation
+            + state.start_rotation * handle_local_pos(handle, state.start_bounds);
+
+        // Point under the cursor, in the gizmo space
+        let point = if is_face_handle(sides) {
+            let axis = state.start_rotation * handle;
+            let (_ray_t, axis_t) = ray_to_ray(ray.origin, ray.direction, handle_pos, axis);
+            handle_pos + axis * axis_t
+        } else {
+            let normal = subgizmo.config.view_forward();
+            let mut t = 0.0;
+            if !intersect_plane(normal, handle_pos, ray.origin, ray.direction, &mut t) {
+                return None;
+            }
+            ray.origin + ray.direction * t
+        };
+        let point = state.start_rotation.inverse() * (point - state.start_translation);
+
+        let mut min = start_min;
+        let mut max = start_max;
+        let mut scale = FVec3::ONE;
+        let mut anchor = (start_min + start_max) / 2.0;
+
+        for (i, side) in sides.into_iter().enumerate() {
+            if side == 0 {
+                continue;
+            }
+
+            // The opposite side of the bounds stays in place
+            let opposite = if side > 0 { start_min[i] } else { start_max[i] };
+
+            let mut size = (point[i] - opposite) * handle[i];
+            if subgizmo.config.snapping {
+                size =
+                    round_to_interval(size, Float::from(subgizmo.config.translation_snap_distance));
+            }
+            let size = size.max(start_size[i] * MIN_RELATIVE_SIZE);
+
+            if side > 0 {
+                max[i] = opposite + size;
+            } else {
+                min[i] = opposite - size;
+            }
+
+            if start_size[i] > 0.0 {
+                scale[i] = size / start_size[i];
+            }
+            anchor[i] = opposite;
+        }
+
+        subgizmo.snap_crossed = subgizmo.config.snapping && subgizmo.state.bounds != (min, max);
+        subgizmo.state.bounds = (min, max);
+
+        Some(GizmoResult::Bounds {
+            scale: vec3_to_mint(scale),
+            rotation: quat_to_mint(state.start_rotation),
+            anchor: vec3_to_mint(state.start_translation + state.start_rotation * anchor),
+        })
+    }
+
+    fn draw(subgizmo: &BoundsSubGizmo) -> GizmoDrawData {
+        let config = subgizmo.config;
+
+        let (translation, rotation, bounds) = if subgizmo.active {
+            (
+                subgizmo.state.start_translation,
+                subgizmo.state.start_rotation,
+                subgizmo.state.bounds,
+            )
+        } else if let Some(bounds) = config.bounds {
+            (config.translation, config.rotation, bounds)
+        } else {
+            return GizmoDrawData::default();
+        };
+
+        let mut draw_data = GizmoDrawData::default();
+
+        // All handles share the same box, so it is only drawn by one of them,
+        // or by the active handle, since only the active subgizmo is drawn.
+        if subgizmo.active || subgizmo.handle == [1, 1, 1] {
+            let shape_builder = ShapeBuidler::new(
+                config.view_projection * FMat4::from_rotation_translation(rotation, translation),
+                config.viewport,
+                config.pixels_per_point,
+            );
+
+            let (min, max) = bounds;
+            let corner = |x: bool, y: bool, z: bool| {
+                FVec3::new(
+                    if x { max.x } else { min.x },
+                    if y { max.y } else { min.y },
+                    if z { max.z } else { min.z },
+                )
+            };
+
+            let stroke = (
+                config.visuals.stroke_width / 2.0,
+                gizmo_color(&config, false, GizmoDirection::View),
+            );
+
+            for a in [false, true] {
+                for b in [false, true] {
+                    draw_data += shape_builder
+                        .line_segment(corner(false, a, b), corner(true, a, b), stroke)
+                        .into();
+                    draw_data += shape_builder
+                        .line_segment(corner(a, false, b), corner(a, true, b), stroke)
+                        .into();
+                    draw_data += shape_builder
+                        .line_segment(corner(a, b, false), corner(a, b, true), stroke)
+                        .into();
+                }
+            }
+        }
+
+        let handle_pos = translation + rotation * handle_local_pos(handle_dir(subgizmo), bounds);
+
+        let shape_builder = ShapeBuidler::new(
+            config.view_projection
+                * FMat4::from_rotation_translation(view_plane_rotation(&config), handle_pos),
+            config.viewport,
+            config.pixels_per_point,
+        );
+
+        let color = gizmo_color(
+            &config,
+            subgizmo.focused || subgizmo.active,
+            handle_direction(subgizmo),
+        );
+
+        draw_data += shape_builder
+            .filled_circle(
+                Float::from(config.scale_factor * handle_radius(subgizmo)),
+                color,
+                (0.0, Color32::TRANSPARENT),
+            )
+            .into();
+
+        draw_data
+    }
+}
+
+/// Handle side of the bounds as a vector
+fn handle_dir(subgizmo: &BoundsSubGizmo) -> FVec3 {
+    let [x, y, z] = subgizmo.handle;
+    FVec3::new(x.into(), y.into(), z.into())
+}
+
+/// Whether the handle is on a face of the bounds, instead of a corner
+fn is_face_handle(sides: [i8; 3]) -> bool {
+    sides.iter().filter(|side| **side != 0).count() == 1
+}
+
+/// Position of the handle in the gizmo space
+fn handle_local_pos(handle: FVec3, (min, max): (FVec3, FVec3)) -> FVec3 {
+    (min + max) / 2.0 + (max - min) / 2.0 * handle
+}
+
+/// Position of the handle in world space
+fn handle_world_pos(subgizmo: &BoundsSubGizmo) -> FVec3 {
+    subgizmo.state.start_translation
+        + subgizmo.state.start_rotation
+            * handle_local_pos(handle_dir(subgizmo), subgizmo.state.start_bounds)
+}
+
+/// Radius of the handle in pixels
+fn handle_radius(subgizmo: &BoundsSubGizmo) -> f32 {
+    let radius = subgizmo.config.visuals.stroke_width * 1.5;
+
+    if is_face_handle(subgizmo.handle) {
+        radius
+    } else {
+        radius * 1.25
+    }
+}
+
+/// Direction used for the color of the handle.
+/// Face handles use the color of their axis.
+fn handle_direction(subgizmo: &BoundsSubGizmo) -> GizmoDirection {
+    match subgizmo.handle {
+        [_, 0, 0] => GizmoDirection::X,
+        [0, _, 0] => GizmoDirection::Y,
+        [0, 0, _] => GizmoDirection::Z,
+        _ => GizmoDirection::View,
+    }
+}
@@ -1,35 +1,46 @@
-use crate::math::{ray_to_plane_origin, segment_to_segment};
+use crate::config::Color32;
+use crate::math::consts::{PI, TAU};
+#[cfg(any(feature = "rotate", feature = "arcball"))]
+use crate::math::vec3_to_mint;
+use crate::math::{
+    ray_to_plane_origin, round_to_interval, segment_to_segment, FMat3, FMat4, FQuat, FVec3, Float,
+    RAY_LENGTH,
+};
 use crate::GizmoMode;
-use ecolor::Color32;
 use enumset::EnumSet;
 use std::ops::{Add, RangeInclusive};
 
+use crate::config::PreparedGizmoConfig;
+#[cfg(any(feature = "rotate", feature = "arcball"))]
+use crate::config::TransformPivotPoint;
 use crate::shape::ShapeBuidler;
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDirection, GizmoDrawData};
-use glam::{DMat3, DMat4, DQuat, DVec3};
+use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData};
 
-const ARROW_FADE: RangeInclusive<f64> = 0.95..=0.99;
-const PLANE_FADE: RangeInclusive<f64> = 0.70..=0.86;
+const ARROW_FADE: RangeInclusive<Float> = 0.95..=0.99;
+const PLANE_FADE: RangeInclusive<Float> = 0.70..=0.86;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub(crate) enum TransformKind {
     Axis,
     Plane,
+    /// Small handle at the outer corner of a plane handle, used for uniform scaling
+    #[cfg(feature = "scale")]
+    Corner,
 }
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct PickResult {
-    pub subgizmo_point: DVec3,
-    pub visibility: f64,
+    pub subgizmo_point: FVec3,
+    pub visibility: Float,
     pub picked: bool,
-    pub t: f64,
+    pub t: Float,
 }
 
 struct ArrowParams {
-    start: DVec3,
-    end: DVec3,
-    direction: DVec3,
-    length: f64,
+    start: FVec3,
+    end: FVec3,
+    direction: FVec3,
+    length: Float,
 }
 
 fn arrow_modes_overlapping(mode: GizmoMode, other_modes: EnumSet<GizmoMode>) -> bool {
@@ -41,12 +52,12 @@ fn arrow_modes_overlapping(mode: GizmoMode, other_modes: EnumSet<GizmoMode>) ->
         || (mode == GizmoMode::ScaleZ && other_modes.contains(GizmoMode::TranslateZ))
 }
 
-fn arrow_params(config: &PreparedGizmoConfig, direction: DVec3, mode: GizmoMode) -> ArrowParams {
-    let width = (config.scale_factor * config.visuals.stroke_width) as f64;
+fn arrow_params(config: &PreparedGizmoConfig, direction: FVec3, mode: GizmoMode) -> ArrowParams {
+    let width = Float::from(config.scale_factor * config.visuals.stroke_width);
 
     let (start, length) = if mode.is_translate() && arrow_modes_overlapping(mode, config.modes) {
         // Modes contain both translate and scale. Use a bit different translate arrow, so the modes do not overlap.
-        let length = (config.scale_factor * config.visuals.gizmo_size) as f64;
+        let length = Float::from(config.scale_factor * config.visuals.gizmo_size);
         let start = direction * (length + (width * 3.0));
 
         let length = length * 0.2 + width;
@@ -54,7 +65,8 @@ fn arrow_params(config: &PreparedGizmoConfig, direction: DVec3, mode: GizmoMode)
         (start, length)
     } else {
         let start = direction * (width * 0.5 + inner_circle_radius(config));
-        let mut length = (config.scale_factor * config.visuals.gizmo_size) as f64 - start.length();
+        let mut length =
+            Float::from(config.scale_factor * config.visuals.gizmo_size) - start.length();
 
         if config.modes.len() > 1 {
             length -= width * 2.0;
@@ -77,9 +89,7 @@ pub(crate) fn pick_arrow(
     direction: GizmoDirection,
     mode: GizmoMode,
 ) -> PickResult {
-    let ray_length = 1e+14;
-
-    let direction = gizmo_normal(config, direction);
+    let direction = arrow_direction(config, direction);
 
     let mut arrow_params = arrow_params(config, direction, mode);
     arrow_params.start += config.translation;
@@ -87,22 +97,25 @@ pub(crate) fn pick_arrow(
 
     let (ray_t, subgizmo_t) = segment_to_segment(
         ray.origin,
-        ray.origin + ray.direction * ray_length,
+        ray.origin + ray.direction * RAY_LENGTH,
         arrow_params.start,
         arrow_params.end,
     );
 
-    let ray_point = ray.origin + ray.direction * ray_length * ray_t;
+    let ray_point = ray.origin + ray.direction * RAY_LENGTH * ray_t;
     let subgizmo_point =
         arrow_params.start + arrow_params.direction * arrow_params.length * subgizmo_t;
     let dist = (ray_point - subgizmo_point).length();
 
-    let dot = config.eye_to_model_dir.dot(arrow_params.direction).abs();
+    let visibility = if config.visuals.fade_occluded_handles {
+        let dot = config.eye_to_model_dir.dot(arrow_params.direction).abs();
 
-    let visibility =
-        (1.0 - (dot - *ARROW_FADE.start()) / (*ARROW_FADE.end() - *ARROW_FADE.start())).min(1.0);
+        (1.0 - (dot - *ARROW_FADE.start()) / (*ARROW_FADE.end() - *ARROW_FADE.start())).min(1.0)
+    } else {
+        1.0
+    };
 
-    let picked = visibility > 0.0 && dist <= config.focus_distance as f64;
+    let picked = visibility > 0.0 && dist <= Float::from(config.focus_distance);
 
     PickResult {
         subgizmo_point,
@@ -117,23 +130,54 @@ pub(crate) fn pick_plane(
     ray: Ray,
     direction: GizmoDirection,
 ) -> PickResult {
-    let origin = plane_global_origin(config, direction);
+    pick_quad(
+        config,
+        ray,
+        direction,
+        plane_global_origin(config, direction),
+        plane_size(config),
+    )
+}
+
+#[cfg(feature = "scale")]
+pub(crate) fn pick_corner(
+    config: &PreparedGizmoConfig,
+    ray: Ray,
+    direction: GizmoDirection,
+) -> PickResult {
+    pick_quad(
+        config,
+        ray,
+        direction,
+        corner_global_origin(config, direction),
+        corner_size(config),
+    )
+}
 
+/// Picks a quad lying on the plane of given direction
+fn pick_quad(
+    config: &PreparedGizmoConfig,
+    ray: Ray,
+    direction: GizmoDirection,
+    origin: FVec3,
+    size: Float,
+) -> PickResult {
     let normal = gizmo_normal(config, direction);
 
     let (t, dist_from_origin) = ray_to_plane_origin(normal, origin, ray.origin, ray.direction);
 
     let ray_point = ray.origin + ray.direction * t;
 
-    let dot = config
-        .eye_to_model_dir
-        .dot(gizmo_normal(config, direction))
-        .abs();
-    let visibility = (1.0
-        - ((1.0 - dot) - *PLANE_FADE.start()) / (*PLANE_FADE.end() - *PLANE_FADE.start()))
-    .min(1.0);
+    let visibility = if config.visuals.fade_occluded_handles {
+        let dot = config.eye_to_model_dir.dot(normal).abs();
+
+        (1.0 - ((1.0 - dot) - *PLANE_FADE.start()) / (*PLANE_FADE.end() - *PLANE_FADE.start()))
+            .min(1.0)
+    } else {
+        1.0
+    };
 
-    let picked = visibility > 0.0 && dist_from_origin <= plane_size(config);
+    let picked = visibility > 0.0 && dist_from_origin <= size;
 
     PickResult {
         subgizmo_point: ray_point,
@@ -146,7 +190,7 @@ pub(crate) fn pick_plane(
 pub(crate) fn pick_circle(
     config: &PreparedGizmoConfig,
     ray: Ray,
-    radius: f64,
+    radius: Float,
     filled: bool,
 ) -> PickResult {
     let origin = config.translation;
@@ -158,9 +202,9 @@ pub(crate) fn pick_circle(
     let hit_pos = ray.origin + ray.direction * t;
 
     let picked = if filled {
-        dist_from_gizmo_origin <= radius + config.focus_distance as f64
+        dist_from_gizmo_origin <= radius + Float::from(config.focus_distance)
     } else {
-        (dist_from_gizmo_origin - radius).abs() <= config.focus_distance as f64
+        (dist_from_gizmo_origin - radius).abs() <= Float::from(config.focus_distance)
     };
 
     PickResult {
@@ -171,6 +215,52 @@ pub(crate) fn pick_circle(
     }
 }
 
+/// Approximate projected area of an arrow subgizmo
+pub(crate) fn arrow_area(
+    config: &PreparedGizmoConfig,
+    direction: GizmoDirection,
+    mode: GizmoMode,
+) -> Float {
+    let direction = arrow_direction(config, direction);
+    let arrow_params = arrow_params(config, direction, mode);
+    let width = Float::from(config.scale_factor * config.visuals.stroke_width);
+
+    // Arrows pointing towards the camera appear shorter
+    let dot = config.eye_to_model_dir.dot(direction);
+    let visible_length = arrow_params.length * (1.0 - dot * dot).max(0.0).sqrt();
+
+    visible_length * width
+}
+
+/// Approximate projected area of a plane subgizmo
+pub(crate) fn plane_area(config: &PreparedGizmoConfig, direction: GizmoDirection) -> Float {
+    let dot = config
+        .eye_to_model_dir
+        .dot(gizmo_normal(config, direction))
+        .abs();
+
+    plane_size(config).powi(2) * dot
+}
+
+#[cfg(feature = "scale")]
+pub(crate) fn corner_area(config: &PreparedGizmoConfig, direction: GizmoDirection) -> Float {
+    let dot = config
+        .eye_to_model_dir
+        .dot(gizmo_normal(config, direction))
+        .abs();
+
+    corner_size(config).powi(2) * dot
+}
+
+/// Approximate projected area of a circle subgizmo
+pub(crate) fn circle_area(config: &PreparedGizmoConfig, radius: Float, filled: bool) -> Float {
+    if filled {
+        PI * radius * radius
+    } else {
+        TAU * radius * Float::from(config.scale_factor * config.visuals.stroke_width)
+    }
+}
+
 pub(crate) fn draw_arrow(
     config: &PreparedGizmoConfig,
     opacity: f32,
@@ -185,9 +275,9 @@ pub(crate) fn draw_arrow(
     let color = gizmo_color(config, focused, direction).gamma_multiply(opacity);
 
     let transform = if config.local_space() {
-        DMat4::from_rotation_translation(config.rotation, config.translation)
+        FMat4::from_rotation_translation(config.rotation, config.translation)
     } else {
-        DMat4::from_translation(config.translation)
+        FMat4::from_translation(config.translation)
     };
 
     let shape_builder = ShapeBuidler::new(
@@ -196,12 +286,15 @@ pub(crate) fn draw_arrow(
         config.pixels_per_point,
     );
 
-    let direction = gizmo_local_normal(config, direction);
+    let mut local_direction = gizmo_local_normal(config, direction);
+    if arrow_flipped(config, direction) {
+        local_direction = -local_direction;
+    }
 
-    let arrow_params = arrow_params(config, direction, mode);
+    let arrow_params = arrow_params(config, local_direction, mode);
 
     let tip_stroke_width = 2.4 * config.visuals.stroke_width;
-    let tip_length = (tip_stroke_width * config.scale_factor) as f64;
+    let tip_length = Float::from(tip_stroke_width * config.scale_factor);
 
     let tip_start = arrow_params.end - arrow_params.direction * tip_length;
 
@@ -239,16 +332,48 @@ pub(crate) fn draw_plane(
     focused: bool,
     direction: GizmoDirection,
 ) -> GizmoDrawData {
-    if opacity <= 1e-4 {
+    draw_quad(
+        config,
+        gizmo_color(config, focused, direction).gamma_multiply(opacity),
+        direction,
+        plane_local_origin(config, direction),
+        plane_size(config),
+    )
+}
+
+/// Draws a corner handle, colored like the uniform scale handle.
+#[cfg(feature = "scale")]
+pub(crate) fn draw_corner(
+    config: &PreparedGizmoConfig,
+    opacity: f32,
+    focused: bool,
+    direction: GizmoDirection,
+) -> GizmoDrawData {
+    draw_quad(
+        config,
+        gizmo_color(config, focused, GizmoDirection::View).gamma_multiply(opacity),
+        direction,
+        corner_local_origin(config, direction),
+        corner_size(config),
+    )
+}
+
+/// Draws a quad lying on the plane of given direction
+fn draw_quad(
+    config: &PreparedGizmoConfig,
+    color: Color32,
+    direction: GizmoDirection,
+    origin: FVec3,
+    size: Float,
+) -> GizmoDrawData {
+    if color.a() == 0 {
         return GizmoDrawData::default();
     }
 
-    let color = gizmo_color(config, focused, direction).gamma_multiply(opacity);
-
     let transform = if config.local_space() {
-        DMat4::from_rotation_translation(config.rotation, config.translation)
+        FMat4::from_rotation_translation(config.rotation, config.translation)
     } else {
-        DMat4::from_translation(config.translation)
+        FMat4::from_translation(config.translation)
     };
 
     let shape_builder = ShapeBuidler::new(
@@ -257,10 +382,9 @@ pub(crate) fn draw_plane(
         config.pixels_per_point,
     );
 
-    let scale = plane_size(config) * 0.5;
+    let scale = size * 0.5;
     let a = plane_bitangent(direction) * scale;
     let b = plane_tangent(direction) * scale;
-    let origin = plane_local_origin(config, direction);
 
     let mut draw_data = GizmoDrawData::default();
     draw_data = draw_data.add(
@@ -283,22 +407,15 @@ pub(crate) fn draw_plane(
 pub(crate) fn draw_circle(
     config: &PreparedGizmoConfig,
     color: Color32,
-    radius: f64,
+    radius: Float,
     filled: bool,
 ) -> GizmoDrawData {
     if color.a() == 0 {
         return GizmoDrawData::default();
     }
 
-    let rotation = {
-        let forward = config.view_forward();
-        let right = config.view_right();
-        let up = config.view_up();
-
-        DQuat::from_mat3(&DMat3::from_cols(up, -forward, -right))
-    };
-
-    let transform = DMat4::from_rotation_translation(rotation, config.translation);
+    let transform =
+        FMat4::from_rotation_translation(view_plane_rotation(config), config.translation);
 
     let shape_builder = ShapeBuidler::new(
         config.view_projection * transform,
@@ -323,41 +440,98 @@ pub(crate) fn draw_circle(
     draw_data
 }
 
-pub(crate) const fn plane_bitangent(direction: GizmoDirection) -> DVec3 {
+/// Rotation that aligns the local XZ plane of a shape with the view plane
+pub(crate) fn view_plane_rotation(config: &PreparedGizmoConfig) -> FQuat {
+    let forward = config.view_forward();
+    let right = config.view_right();
+    let up = config.view_up();
+
+    FQuat::from_mat3(&FMat3::from_cols(up, -forward, -right))
+}
+
+pub(crate) const fn plane_bitangent(direction: GizmoDirection) -> FVec3 {
     match direction {
-        GizmoDirection::X => DVec3::Y,
-        GizmoDirection::Y => DVec3::Z,
-        GizmoDirection::Z => DVec3::X,
-        GizmoDirection::View => DVec3::ZERO, // Unused
+        GizmoDirection::X => FVec3::Y,
+        GizmoDirection::Y => FVec3::Z,
+        GizmoDirection::Z => FVec3::X,
+        GizmoDirection::View => FVec3::ZERO, // Unused
     }
 }
 
-pub(crate) const fn plane_tangent(direction: GizmoDirection) -> DVec3 {
+pub(crate) const fn plane_tangent(direction: GizmoDirection) -> FVec3 {
     match direction {
-        GizmoDirection::X => DVec3::Z,
-        GizmoDirection::Y => DVec3::X,
-        GizmoDirection::Z => DVec3::Y,
-        GizmoDirection::View => DVec3::ZERO, // Unused
+        GizmoDirection::X => FVec3::Z,
+        GizmoDirection::Y => FVec3::X,
+        GizmoDirection::Z => FVec3::Y,
+        GizmoDirection::View => FVec3::ZERO, // Unused
     }
 }
 
-pub(crate) fn plane_size(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size * 0.1 + config.visuals.stroke_width * 2.0))
-        as f64
+pub(crate) fn plane_size(config: &PreparedGizmoConfig) -> Float {
+    Float::from(
+        config.scale_factor * (config.visuals.gizmo_size * 0.1 + config.visuals.stroke_width * 2.0),
+    )
 }
 
-pub(crate) fn plane_local_origin(config: &PreparedGizmoConfig, direction: GizmoDirection) -> DVec3 {
+pub(crate) fn plane_local_origin(config: &PreparedGizmoConfig, direction: GizmoDirection) -> FVec3 {
     let offset = config.scale_factor * config.visuals.gizmo_size * 0.5;
 
-    let a = plane_bitangent(direction);
-    let b = plane_tangent(direction);
-    (a + b) * offset as f64
+    let mut a = plane_bitangent(direction);
+    let mut b = plane_tangent(direction);
+
+    if config.mirrored {
+        // Flip the plane axis that is closer to the horizontal screen axis
+        let (world_a, world_b) = if config.local_space() {
+            (config.rotation * a, config.rotation * b)
+        } else {
+            (a, b)
+        };
+        let right = config.view_right();
+        if world_a.dot(right).abs() > world_b.dot(right).abs() {
+            a = -a;
+        } else {
+            b = -b;
+        }
+    }
+
+    (a + b) * Float::from(offset)
+}
+
+/// Size of the corner handles
+#[cfg(feature = "scale")]
+pub(crate) fn corner_size(config: &PreparedGizmoConfig) -> Float {
+    plane_size(config) * 0.5
+}
+
+/// Center of the corner handle just outside the outer corner of the plane handle
+#[cfg(feature = "scale")]
+pub(crate) fn corner_local_origin(
+    config: &PreparedGizmoConfig,
+    direction: GizmoDirection,
+) -> FVec3 {
+    let plane_origin = plane_local_origin(config, direction);
+    let plane_offset = Float::from(config.scale_factor * config.visuals.gizmo_size * 0.5);
+
+    // The plane origin lies on the diagonal of the plane, so the corner is found by moving further along it
+    plane_origin * (1.0 + (plane_size(config) + corner_size(config)) * 0.5 / plane_offset)
+}
+
+#[cfg(feature = "scale")]
+pub(crate) fn corner_global_origin(
+    config: &PreparedGizmoConfig,
+    direction: GizmoDirection,
+) -> FVec3 {
+    let mut origin = corner_local_origin(config, direction);
+    if config.local_space() {
+        origin = config.rotation * origin;
+    }
+    origin + config.translation
 }
 
 pub(crate) fn plane_global_origin(
     config: &PreparedGizmoConfig,
     direction: GizmoDirection,
-) -> DVec3 {
+) -> FVec3 {
     let mut origin = plane_local_origin(config, direction);
     if config.local_space() {
         origin = config.rotation * origin;
@@ -365,26 +539,50 @@ pub(crate) fn plane_global_origin(
     origin + config.translation
 }
 
+/// Adjusts a translation delta along the given unit axis, so that the translated
+/// position is a multiple of the interval along that axis.
+pub(crate) fn snap_to_absolute_interval(
+    start: FVec3,
+    delta: FVec3,
+    axis: FVec3,
+    interval: Float,
+) -> FVec3 {
+    let position = (start + delta).dot(axis);
+    delta + axis * (round_to_interval(position, interval) - position)
+}
+
 /// Radius to use for inner circle subgizmos
-pub(crate) fn inner_circle_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * config.visuals.gizmo_size) as f64 * 0.2
+pub(crate) fn inner_circle_radius(config: &PreparedGizmoConfig) -> Float {
+    Float::from(config.scale_factor * config.visuals.gizmo_size) * 0.2
 }
 
 /// Radius to use for outer circle subgizmos
-pub(crate) fn outer_circle_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width + 5.0)) as f64
+#[cfg(any(feature = "rotate", feature = "scale"))]
+pub(crate) fn outer_circle_radius(config: &PreparedGizmoConfig) -> Float {
+    Float::from(
+        config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width + 5.0),
+    )
 }
 
-pub(crate) fn gizmo_local_normal(config: &PreparedGizmoConfig, direction: GizmoDirection) -> DVec3 {
+pub(crate) fn gizmo_local_normal(config: &PreparedGizmoConfig, direction: GizmoDirection) -> FVec3 {
     match direction {
-        GizmoDirection::X => DVec3::X,
-        GizmoDirection::Y => DVec3::Y,
-        GizmoDirection::Z => DVec3::Z,
+        GizmoDirection::X => FVec3::X,
+        GizmoDirection::Y => FVec3::Y,
+        GizmoDirection::Z => FVec3::Z,
         GizmoDirection::View => -config.view_forward(),
     }
 }
 
-pub(crate) fn gizmo_normal(config: &PreparedGizmoConfig, direction: GizmoDirection) -> DVec3 {
+/// Point the targets are rotated around, or `None` if they are rotated around their own origins
+#[cfg(any(feature = "rotate", feature = "arcball"))]
+pub(crate) fn rotation_pivot(config: &PreparedGizmoConfig) -> Option<mint::Vector3<f64>> {
+    match config.pivot_point {
+        TransformPivotPoint::MedianPoint => Some(vec3_to_mint(config.translation)),
+        TransformPivotPoint::IndividualOrigins => None,
+    }
+}
+
+pub(crate) fn gizmo_normal(config: &PreparedGizmoConfig, direction: GizmoDirection) -> FVec3 {
     let mut normal = gizmo_local_normal(config, direction);
 
     if config.local_space() && direction != GizmoDirection::View {
@@ -394,6 +592,24 @@ pub(crate) fn gizmo_normal(config: &PreparedGizmoConfig, direction: GizmoDirecti
     normal
 }
 
+/// Whether the arrow of the axis is drawn pointing in the negative direction of the axis,
+/// see [`GizmoVisuals::flip_axes_toward_camera`](crate::config::GizmoVisuals::flip_axes_toward_camera)
+fn arrow_flipped(config: &PreparedGizmoConfig, direction: GizmoDirection) -> bool {
+    config.visuals.flip_axes_toward_camera
+        && gizmo_normal(config, direction).dot(config.eye_to_model_dir) < 0.0
+}
+
+/// Direction of the arrow of the axis in world space
+pub(crate) fn arrow_direction(config: &PreparedGizmoConfig, direction: GizmoDirection) -> FVec3 {
+    let normal = gizmo_normal(config, direction);
+
+    if arrow_flipped(config, direction) {
+        -normal
+    } else {
+        normal
+    }
+}
+
 pub(crate) fn gizmo_color(
     config: &PreparedGizmoConfig,
     focused: bool,
@@ -0,0 +1,247 @@
+use crate::math::{
+    float_from_f64, float_to_f32, float_to_f64, intersect_plane, round_to_interval, vec3_from_mint,
+    vec3_to_mint, FMat4, FVec3, Float,
+};
+use crate::shape::ShapeBuidler;
+use crate::subgizmo::common::{gizmo_color, inner_circle_radius};
+use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
+use crate::{
+    config::{PreparedGizmoConfig, SnapOrigin},
+    gizmo::Ray,
+    GizmoDirection, GizmoDrawData, GizmoMode, GizmoOrientation, GizmoResult,
+};
+
+pub(crate) type CompassSubGizmo = SubGizmoConfig<Compass>;
+
+#[derive(Debug, Copy, Clone, Hash)]
+pub(crate) struct CompassParams {
+    pub mode: GizmoMode,
+    pub direction: GizmoDirection,
+    /// Whether the arrow points in the positive (1) or negative (-1) direction of the axis
+    pub sign: i8,
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct CompassState {
+    /// Point on the ground plane where the drag was started
+    start_point: FVec3,
+    /// Gizmo position when the drag was started
+    start_translation: FVec3,
+    /// Total translation during the latest update
+    last_total: FVec3,
+}
+
+/// Translation arrow lying on the ground plane, see [`GroundPlane`](crate::config::GroundPlane).
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct Compass;
+
+impl SubGizmoKind for Compass {
+    type Params = CompassParams;
+    type State = CompassState;
+
+    fn pick(subgizmo: &mut CompassSubGizmo, ray: Ray) -> Option<Float> {
+        let config = &subgizmo.config;
+        let center = ground_center(config);
+        let normal = ground_normal(config);
+        let direction = arrow_direction(subgizmo);
+
+        subgizmo.opacity = float_to_f32(ground_visibility(config));
+        if subgizmo.opacity <= 1e-4 {
+            return None;
+        }
+
+        let mut t = 0.0;
+        if !intersect_plane(normal, center, ray.origin, ray.direction, &mut t) {
+            return None;
+        }
+        let point = ray.origin + ray.direction * t;
+
+        subgizmo.state.start_point = point;
+        subgizmo.state.start_translation = config.translation;
+        subgizmo.state.last_total = FVec3::ZERO;
+
+        let (start, end) = arrow_extent(config);
+        let offset = point - center;
+        let along = offset.dot(direction);
+        let across = (offset - direction * along).length();
+        let tolerance = Float::from(config.focus_distance);
+
+        let picked = along >= start - tolerance
+            && along <= end + tolerance
+            && across <= tolerance + arrow_width(config);
+
+        if picked {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    fn visibility(subgizmo: &CompassSubGizmo) -> Float {
+        Float::from(subgizmo.opacity)
+    }
+
+    fn pick_area(subgizmo: &CompassSubGizmo) -> Float {
+        let (start, end) = arrow_extent(&subgizmo.config);
+        (end - start) * arrow_width(&subgizmo.config)
+    }
+
+    fn mode(subgizmo: &CompassSubGizmo) -> GizmoMode {
+        subgizmo.mode
+    }
+
+    fn update(subgizmo: &mut CompassSubGizmo, ray: Ray) -> Option<GizmoResult> {
+        let config = subgizmo.config;
+        let state = subgizmo.state;
+        let direction = arrow_direction(subgizmo);
+
+        let mut t = 0.0;
+        if !intersect_plane(
+            ground_normal(&config),
+            state.start_point,
+            ray.origin,
+            ray.direction,
+            &mut t,
+        ) {
+            return None;
+        }
+        let point = ray.origin + ray.direction * t;
+
+        let mut distance = (point - state.start_point).dot(direction);
+        if config.snapping {
+            let snap_distance = Float::from(config.translation_snap_distance);
+            distance = if config.translation_snap_origin == SnapOrigin::Absolute {
+                let start =
+                    (state.start_translation - vec3_from_mint(config.snap_origin)).dot(direction);
+                round_to_interval(start + distance, snap_distance) - start
+            } else {
+                round_to_interval(distance, snap_distance)
+            };
+        }
+
+        // Keep the gizmo position within the translation limits
+        let mut translation = state.start_translation + direction * distance;
+        for (i, limit) in config.translation_limits.iter().enumerate() {
+            if let Some(limit) = limit {
+                translation[i] = float_from_f64(limit.clamp(float_to_f64(translation[i])));
+            }
+        }
+
+        let total = translation - state.start_translation;
+        let delta = total - state.last_total;
+        subgizmo.snap_crossed = config.snapping && total != state.last_total;
+        subgizmo.state.last_total = total;
+
+        // Results are expected in the gizmo space
+        let (delta, total) = if config.orientation() == GizmoOrientation::Local {
+            let inverse_rotation = config.rotation.inverse();
+            (inverse_rotation * delta, inverse_rotation * total)
+        } else {
+            (delta, total)
+        };
+
+        Some(GizmoResult::Translation {
+            delta: vec3_to_mint(delta),
+            total: vec3_to_mint(total),
+            constraint_position: None,
+        })
+    }
+
+    fn interaction_points(subgizmo: &CompassSubGizmo) -> Option<(FVec3, FVec3)> {
+        let start_point = subgizmo.state.start_point;
+        Some((start_point, start_point + subgizmo.state.last_total))
+    }
+
+    fn draw(subgizmo: &CompassSubGizmo) -> GizmoDrawData {
+        let config = &subgizmo.config;
+
+        if subgizmo.opacity <= 1e-4 && !subgizmo.active {
+            return GizmoDrawData::default();
+        }
+
+        let color = gizmo_color(config, subgizmo.focused, subgizmo.direction).gamma_multiply(
+            if subgizmo.active {
+                1.0
+            } else {
+                subgizmo.opacity
+            },
+        );
+
+        let shape_builder = ShapeBuidler::new(
+            config.view_projection * FMat4::from_translation(ground_center(config)),
+            config.viewport,
+            config.pixels_per_point,
+        );
+
+        let direction = arrow_direction(subgizmo);
+        let (start, end) = arrow_extent(config);
+
+        let tip_stroke_width = 2.4 * config.visuals.stroke_width;
+        let tip_length = Float::from(tip_stroke_width * config.scale_factor);
+        let tip_start = direction * (end - tip_length);
+
+        let mut draw_data = GizmoDrawData::default();
+        draw_data += shape_builder
+            .line_segment(
+                direction * start,
+                tip_start,
+                (config.visuals.stroke_width, color),
+            )
+            .into();
+        draw_data += shape_builder
+            .arrow(tip_start, direction * end, (tip_stroke_width, color))
+            .into();
+
+        draw_data
+    }
+}
+
+/// Normal of the ground plane in world space
+fn ground_normal(config: &PreparedGizmoConfig) -> FVec3 {
+    axis_vector(config.ground_plane.unwrap_or_default().normal_axis())
+}
+
+/// Gizmo position projected onto the ground plane
+fn ground_center(config: &PreparedGizmoConfig) -> FVec3 {
+    let plane = config.ground_plane.unwrap_or_default();
+    let normal = ground_normal(config);
+
+    match plane.height {
+        Some(height) => {
+            config.translation - normal * (config.translation.dot(normal) - float_from_f64(height))
+        }
+        None => config.translation,
+    }
+}
+
+/// Fades the arrows out when the ground plane is viewed edge-on
+fn ground_visibility(config: &PreparedGizmoConfig) -> Float {
+    let dot = config.view_forward().dot(ground_normal(config)).abs();
+    ((dot - 0.1) / 0.1).clamp(0.0, 1.0)
+}
+
+/// World space direction the arrow points to
+fn arrow_direction(subgizmo: &CompassSubGizmo) -> FVec3 {
+    axis_vector(subgizmo.direction) * Float::from(subgizmo.sign)
+}
+
+/// World space unit vector of the axis
+fn axis_vector(direction: GizmoDirection) -> FVec3 {
+    match direction {
+        GizmoDirection::X => FVec3::X,
+        GizmoDirection::Y | GizmoDirection::View => FVec3::Y,
+        GizmoDirection::Z => FVec3::Z,
+    }
+}
+
+/// Distances of the start and the end of the arrows from the center
+fn arrow_extent(config: &PreparedGizmoConfig) -> (Float, Float) {
+    (
+        inner_circle_radius(config),
+        Float::from(config.scale_factor * config.visuals.gizmo_size),
+    )
+}
+
+fn arrow_width(config: &PreparedGizmoConfig) -> Float {
+    Float::from(config.scale_factor * config.visuals.stroke_width)
+}
@@ -0,0 +1,228 @@
+use crate::config::{PreparedGizmoConfig, TranslationConstraint};
+use crate::math::{float_to_f64, ray_to_ray, vec3_from_mint, vec3_to_mint, FVec3, Float};
+use crate::shape::ShapeBuidler;
+use crate::subgizmo::common::{gizmo_color, PickResult, TransformKind};
+use crate::subgizmo::translation::TranslationParams;
+use crate::subgizmo::{SubGizmoControl, TranslationSubGizmo};
+use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoOrientation, GizmoResult};
+
+/// Translation handle that moves the gizmo along a [`TranslationConstraint`].
+///
+/// Picked and drawn like the view plane translation handle. The constraint geometry
+/// is not `Copy`, so unlike the other subgizmos this is not a [`SubGizmoConfig`](crate::subgizmo::SubGizmoConfig).
+#[derive(Clone, Debug)]
+pub(crate) struct ConstrainedSubGizmo {
+    /// Handle used for picking and drawing
+    handle: TranslationSubGizmo,
+    /// Points of the constraint in world space. A line is given by two points on it.
+    points: Vec<FVec3>,
+    /// Whether the constraint is an infinite line instead of a path
+    is_line: bool,
+    /// Gizmo position when the drag was started
+    start_translation: FVec3,
+    /// Gizmo position during the latest update
+    last_translation: FVec3,
+}
+
+impl ConstrainedSubGizmo {
+    pub(crate) fn new(config: PreparedGizmoConfig, constraint: &TranslationConstraint) -> Self {
+        let (points, is_line) = match constraint {
+            TranslationConstraint::Line { point, direction } => {
+                let point = vec3_from_mint(*point);
+                (vec![point, point + vec3_from_mint(*direction)], true)
+            }
+            TranslationConstraint::Path(points) => {
+                (points.iter().copied().map(vec3_from_mint).collect(), false)
+            }
+        };
+
+        Self {
+            handle: TranslationSubGizmo::new(
+                config,
+                TranslationParams {
+                    mode: GizmoMode::TranslateView,
+                    direction: GizmoDirection::View,
+                    transform_kind: TransformKind::Plane,
+                },
+            ),
+            points,
+            is_line,
+            start_translation: config.translation,
+            last_translation: config.translation,
+        }
+    }
+
+    /// Parameter of the point on the constraint that is closest to the ray,
+    /// as described in [`TranslationConstraint`].
+    fn closest_parameter(&self, ray: Ray) -> Option<Float> {
+        if self.points.len() < 2 {
+            return self.points.first().map(|_| 0.0);
+        }
+
+        if self.is_line {
+            let direction = self.points[1] - self.points[0];
+            let length = direction.length();
+            if length < 1e-8 {
+                return Some(0.0);
+            }
+
+            let (_, t) = ray_to_ray(
+                ray.origin,
+                ray.direction,
+                self.points[0],
+                direction / length,
+            );
+            return Some(t / length);
+        }
+
+        self.points
+            .windows(2)
+            .enumerate()
+            .filter_map(|(index, segment)| {
+                let direction = segment[1] - segment[0];
+                let length = direction.length();
+                if length < 1e-8 {
+                    return None;
+                }
+
+                let direction = direction / length;
+                let (_, t) = ray_to_ray(ray.origin, ray.direction, segment[0], direction);
+                let t = t.clamp(0.0, length);
+
+                let point = segment[0] + direction * t;
+                let distance = (point - ray.origin).cross(ray.direction).length();
+
+                Some((distance, index as Float + t / length))
+            })
+            .min_by(|(first, _), (second, _)| {
+                first
+                    .partial_cmp(second)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(_, t)| t)
+    }
+
+    /// Point on the constraint at the given parameter
+    fn point_at(&self, t: Float) -> FVec3 {
+        if self.points.len() < 2 {
+            return self.points.first().copied().unwrap_or_default();
+        }
+
+        if self.is_line {
+            return self.points[0] + (self.points[1] - self.points[0]) * t;
+        }
+
+        let index = (t.max(0.0) as usize).min(self.points.len() - 2);
+        self.points[index].lerp(self.points[index + 1], t - index as Float)
+    }
+}
+
+impl SubGizmoControl for ConstrainedSubGizmo {
+    fn id(&self) -> u64 {
+        self.handle.id()
+    }
+
+    fn mode(&self) -> GizmoMode {
+        self.handle.mode()
+    }
+
+    fn update_config(&mut self, config: PreparedGizmoConfig) {
+        self.handle.update_config(config);
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.handle.set_focused(focused);
+    }
+
+    fn set_active(&mut self, active: bool) {
+        self.handle.set_active(active);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.handle.is_focused()
+    }
+
+    fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    fn pick(&mut self, ray: Ray) -> Option<Float> {
+        self.start_translation = self.handle.config.translation;
+        self.last_translation = self.handle.config.translation;
+
+        self.handle.pick(ray)
+    }
+
+    fn pick_area(&self) -> Float {
+        self.handle.pick_area()
+    }
+
+    fn last_pick(&self) -> Option<PickResult> {
+        self.handle.last_pick()
+    }
+
+    fn visibility(&self) -> Float {
+        self.handle.visibility()
+    }
+
+    fn update(&mut self, ray: Ray) -> Option<GizmoResult> {
+        let t = self.closest_parameter(ray)?;
+        let translation = self.point_at(t);
+
+        let mut delta = translation - self.last_translation;
+        let mut total = translation - self.start_translation;
+
+        let config = &self.handle.config;
+        if config.orientation() == GizmoOrientation::Local {
+            let inverse_rotation = config.rotation.inverse();
+            delta = inverse_rotation * delta;
+            total = inverse_rotation * total;
+        }
+
+        self.last_translation = translation;
+
+        Some(GizmoResult::Translation {
+            delta: vec3_to_mint(delta),
+            total: vec3_to_mint(total),
+            constraint_position: Some(float_to_f64(t)),
+        })
+    }
+
+    fn snap_crossed(&self) -> bool {
+        false
+    }
+
+    fn wheel(&self, _steps: Float) -> Option<GizmoResult> {
+        None
+    }
+
+    fn interaction_points(&self) -> Option<(FVec3, FVec3)> {
+        Some((self.start_translation, self.last_translation))
+    }
+
+    fn draw(&self) -> GizmoDrawData {
+        let mut draw_data = self.handle.draw();
+
+        // Paths are shown while dragging along them
+        if self.handle.is_active() && !self.is_line {
+            let config = &self.handle.config;
+            let shape_builder = ShapeBuidler::new(
+                config.view_projection,
+                config.viewport,
+                config.pixels_per_point,
+            );
+
+            draw_data += shape_builder
+                .polyline(
+                    &self.points,
+                    (
+                        config.visuals.stroke_width * 0.5,
+                        gizmo_color(config, false, GizmoDirection::View),
+                    ),
+                )
+                .into();
+        }
+
+        draw_data
+    }
+}
@@ -0,0 +1,182 @@
+use crate::math::{
+    quat_to_mint, round_to_interval, segment_to_segment, vec3_to_mint, FMat4, FQuat, FVec3, Float,
+    RAY_LENGTH,
+};
+use crate::shape::ShapeBuidler;
+use crate::subgizmo::common::{circle_area, draw_circle, gizmo_color, pick_circle, PickResult};
+use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
+use crate::{
+    config::PreparedGizmoConfig, gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoResult,
+};
+
+pub(crate) type DirectionSubGizmo = SubGizmoConfig<Direction>;
+
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct DirectionState {
+    /// Direction when the drag was started
+    start_direction: FVec3,
+    /// Rotation from the start direction to the current one, after snapping
+    total_rotation: FQuat,
+    /// Whether the arrow tip follows the far side of the sphere during the drag,
+    /// which is the case when the direction pointed away from the camera
+    far_side: bool,
+}
+
+/// Edits a unit direction, the Z axis of the gizmo, by dragging the tip of an arrow
+/// over a sphere around the gizmo. The great circle of the sphere facing the camera
+/// is drawn as a guide, and can be grabbed as well.
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct Direction;
+
+impl SubGizmoKind for Direction {
+    type Params = ();
+    type State = DirectionState;
+
+    fn pick(subgizmo: &mut DirectionSubGizmo, ray: Ray) -> Option<Float> {
+        let config = subgizmo.config;
+        let direction = edited_direction(&config);
+
+        subgizmo.state.start_direction = direction;
+        subgizmo.state.total_rotation = FQuat::IDENTITY;
+        subgizmo.state.far_side = direction.dot(config.eye_to_model_dir) < 0.0;
+
+        let tip = config.translation + direction * direction_radius(&config);
+        let (ray_t, arrow_t) = segment_to_segment(
+            ray.origin,
+            ray.origin + ray.direction * RAY_LENGTH,
+            config.translation,
+            tip,
+        );
+        let ray_point = ray.origin + ray.direction * RAY_LENGTH * ray_t;
+        let arrow_point = config.translation.lerp(tip, arrow_t);
+
+        if (ray_point - arrow_point).length() <= Float::from(config.focus_distance) {
+            subgizmo.last_pick = Some(PickResult {
+                subgizmo_point: arrow_point,
+                visibility: 1.0,
+                picked: true,
+                t: ray_t * RAY_LENGTH,
+            });
+            return Some(ray_t * RAY_LENGTH);
+        }
+
+        let circle = pick_circle(&config, ray, direction_radius(&config), false);
+        subgizmo.last_pick = Some(circle);
+        circle.picked.then_some(circle.t)
+    }
+
+    fn pick_area(subgizmo: &DirectionSubGizmo) -> Float {
+        circle_area(&subgizmo.config, direction_radius(&subgizmo.config), false)
+    }
+
+    fn mode(_subgizmo: &DirectionSubGizmo) -> GizmoMode {
+        GizmoMode::Direction
+    }
+
+    fn update(subgizmo: &mut DirectionSubGizmo, ray: Ray) -> Option<GizmoResult> {
+        let config = subgizmo.config;
+        let state = subgizmo.state;
+
+        let mut direction =
+            point_on_sphere(&config, ray, state.far_side) - subgizmo.config.translation;
+        direction = direction.try_normalize()?;
+
+        let mut total_rotation = FQuat::from_rotation_arc(state.start_direction, direction);
+
+        if config.snapping {
+            let (axis, angle) = total_rotation.to_axis_angle();
+            let angle = round_to_interval(angle, Float::from(config.snap_angle));
+            total_rotation = FQuat::from_axis_angle(axis, angle);
+            direction = total_rotation * state.start_direction;
+        }
+
+        let delta = total_rotation * state.total_rotation.inverse();
+        subgizmo.snap_crossed = config.snapping && total_rotation != state.total_rotation;
+        subgizmo.state.total_rotation = total_rotation;
+
+        Some(GizmoResult::Direction {
+            direction: vec3_to_mint(direction),
+            delta: quat_to_mint(delta),
+            total: quat_to_mint(total_rotation),
+        })
+    }
+
+    fn interaction_points(subgizmo: &DirectionSubGizmo) -> Option<(FVec3, FVec3)> {
+        let config = subgizmo.config;
+        let radius = direction_radius(&config);
+        let start_direction = subgizmo.state.start_direction;
+        let direction = subgizmo.state.total_rotation * start_direction;
+
+        Some((
+            config.translation + start_direction * radius,
+            config.translation + direction * radius,
+        ))
+    }
+
+    fn draw(subgizmo: &DirectionSubGizmo) -> GizmoDrawData {
+        let config = subgizmo.config;
+        let radius = direction_radius(&config);
+
+        let mut draw_data = draw_circle(
+            &config,
+            gizmo_color(&config, subgizmo.focused, GizmoDirection::View).gamma_multiply(0.5),
+            radius,
+            false,
+        );
+
+        let shape_builder = ShapeBuidler::new(
+            config.view_projection * FMat4::from_translation(config.translation),
+            config.viewport,
+            config.pixels_per_point,
+        );
+
+        let color = gizmo_color(&config, subgizmo.focused, GizmoDirection::Z);
+        let direction = edited_direction(&config);
+        let tip_stroke_width = 2.4 * config.visuals.stroke_width;
+        let tip_length = Float::from(tip_stroke_width * config.scale_factor);
+        let tip_start = direction * (radius - tip_length);
+
+        draw_data += shape_builder
+            .line_segment(FVec3::ZERO, tip_start, (config.visuals.stroke_width, color))
+            .into();
+        draw_data += shape_builder
+            .arrow(tip_start, direction * radius, (tip_stroke_width, color))
+            .into();
+
+        draw_data
+    }
+}
+
+/// The direction being edited, in world space
+fn edited_direction(config: &PreparedGizmoConfig) -> FVec3 {
+    config.rotation * FVec3::Z
+}
+
+/// Radius of the sphere the arrow tip moves on
+fn direction_radius(config: &PreparedGizmoConfig) -> Float {
+    Float::from(config.scale_factor * config.visuals.gizmo_size)
+}
+
+/// Point on the sphere around the gizmo under the ray. If the ray misses the sphere,
+/// the closest point of the great circle facing the camera is used instead.
+fn point_on_sphere(config: &PreparedGizmoConfig, ray: Ray, far_side: bool) -> FVec3 {
+    let center = config.translation;
+    let radius = direction_radius(config);
+
+    let closest_t = (center - ray.origin).dot(ray.direction);
+    let closest = ray.origin + ray.direction * closest_t;
+    let distance_sq = (closest - center).length_squared();
+
+    if distance_sq >= radius * radius {
+        return center + (closest - center).normalize_or_zero() * radius;
+    }
+
+    let half_chord = (radius * radius - distance_sq).sqrt();
+    let t = if far_side {
+        closest_t + half_chord
+    } else {
+        closest_t - half_chord
+    };
+
+    ray.origin + ray.direction * t
+}
@@ -1,15 +1,19 @@
-use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use crate::math::consts::{FRAC_PI_2, PI, TAU};
 
-use ecolor::Color32;
+use crate::config::Color32;
 
+use crate::config::{SnapOrigin, TransformLimit};
 use crate::math::{
-    ray_to_plane_origin, rotation_align, round_to_interval, world_to_screen, DMat3, DMat4, DQuat,
-    DVec2, DVec3, Pos2,
+    float_from_f64, float_to_f64, full_revolutions, ray_to_plane_origin, rotation_align,
+    round_to_interval, vec3_to_mint, world_to_screen, FMat3, FMat4, FQuat, FVec2, FVec3, Float,
+    Pos2,
 };
 use crate::shape::ShapeBuidler;
-use crate::subgizmo::common::{gizmo_color, gizmo_local_normal, gizmo_normal, outer_circle_radius};
+use crate::subgizmo::common::{
+    gizmo_color, gizmo_local_normal, gizmo_normal, outer_circle_radius, rotation_pivot, PickResult,
+};
 use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
-use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoResult};
+use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoResult};
 
 pub(crate) type RotationSubGizmo = SubGizmoConfig<Rotation>;
 
@@ -18,12 +22,24 @@ pub(crate) struct RotationParams {
     pub direction: GizmoDirection,
 }
 
+/// Distance in screen pixels from the gizmo center, inside which
+/// cursor movement does not affect the rotation.
+const CENTER_DEAD_ZONE: Float = 4.0;
+
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct RotationState {
-    start_axis_angle: f64,
-    start_rotation_angle: f64,
-    last_rotation_angle: f64,
-    current_delta: f64,
+    start_axis_angle: Float,
+    /// Cursor position relative to the gizmo center in screen space
+    last_cursor_offset: FVec2,
+    /// Total rotation angle, before snapping
+    total_angle: Float,
+    current_delta: Float,
+    /// Allowed range of the total angle, if the rotation is limited
+    total_angle_limits: Option<(Float, Float)>,
+    /// Twist of the rotation around the rotation axis when the drag was started
+    start_twist_angle: Option<Float>,
+    /// Point on the rotation arc in world space where the drag was started
+    start_point: FVec3,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -33,7 +49,7 @@ impl SubGizmoKind for Rotation {
     type Params = RotationParams;
     type State = RotationState;
 
-    fn pick(subgizmo: &mut RotationSubGizmo, ray: Ray) -> Option<f64> {
+    fn pick(subgizmo: &mut RotationSubGizmo, ray: Ray) -> Option<Float> {
         let radius = arc_radius(subgizmo);
         let config = subgizmo.config;
         let origin = config.translation;
@@ -51,62 +67,146 @@ impl SubGizmoKind for Rotation {
         let offset = (nearest_circle_pos - origin).normalize();
 
         let angle = if subgizmo.direction == GizmoDirection::View {
-            f64::atan2(tangent.cross(normal).dot(offset), tangent.dot(offset))
+            Float::atan2(tangent.cross(normal).dot(offset), tangent.dot(offset))
         } else {
             let mut forward = config.view_forward();
             if config.left_handed {
                 forward *= -1.0;
             }
-            f64::atan2(offset.cross(forward).dot(normal), offset.dot(forward))
+            Float::atan2(offset.cross(forward).dot(normal), offset.dot(forward))
         };
 
-        let rotation_angle = rotation_angle(subgizmo, ray.screen_pos).unwrap_or(0.0);
         subgizmo.state.start_axis_angle = angle;
-        subgizmo.state.start_rotation_angle = rotation_angle;
-        subgizmo.state.last_rotation_angle = rotation_angle;
+        subgizmo.state.start_point = nearest_circle_pos;
+        subgizmo.state.last_cursor_offset =
+            cursor_offset(subgizmo, ray.screen_pos).unwrap_or_default();
+        subgizmo.state.total_angle = 0.0;
         subgizmo.state.current_delta = 0.0;
+        subgizmo.state.total_angle_limits = total_angle_limits(subgizmo);
+        subgizmo.state.start_twist_angle = twist_angle(subgizmo);
+
+        let picked = dist_from_gizmo_edge <= Float::from(config.focus_distance)
+            && angle.abs() < arc_angle(subgizmo);
+        subgizmo.last_pick = Some(PickResult {
+            subgizmo_point: nearest_circle_pos,
+            visibility: 1.0,
+            picked,
+            t,
+        });
+
+        picked.then_some(t)
+    }
 
-        if dist_from_gizmo_edge <= config.focus_distance as f64 && angle.abs() < arc_angle(subgizmo)
-        {
-            Some(t)
-        } else {
-            None
+    fn mode(subgizmo: &RotationSubGizmo) -> GizmoMode {
+        match subgizmo.direction {
+            GizmoDirection::X => GizmoMode::RotateX,
+            GizmoDirection::Y => GizmoMode::RotateY,
+            GizmoDirection::Z => GizmoMode::RotateZ,
+            GizmoDirection::View => GizmoMode::RotateView,
         }
     }
 
+    fn pick_area(subgizmo: &RotationSubGizmo) -> Float {
+        let config = subgizmo.config;
+        let width = Float::from(config.scale_factor * config.visuals.stroke_width);
+
+        // The arc spans the arc angle in both directions
+        2.0 * arc_angle(subgizmo) * arc_radius(subgizmo) * width
+    }
+
     fn update(subgizmo: &mut RotationSubGizmo, ray: Ray) -> Option<GizmoResult> {
         let config = subgizmo.config;
 
-        let mut rotation_angle = rotation_angle(subgizmo, ray.screen_pos)?;
-        if config.snapping {
-            rotation_angle = round_to_interval(
-                rotation_angle - subgizmo.state.start_rotation_angle,
-                config.snap_angle as f64,
-            ) + subgizmo.state.start_rotation_angle;
+        let cursor_offset = cursor_offset(subgizmo, ray.screen_pos)?;
+        let last_cursor_offset = subgizmo.state.last_cursor_offset;
+
+        // Near the gizmo center the cursor direction is unreliable,
+        // so the rotation is only tracked outside of it.
+        let dead_zone = CENTER_DEAD_ZONE / Float::from(subgizmo.config.display_scale());
+        if cursor_offset.length() > dead_zone {
+            let mut angle_delta =
+                signed_angle(last_cursor_offset, cursor_offset) * rotation_sign(subgizmo);
+
+            if swiped_through_center(last_cursor_offset, cursor_offset, dead_zone) {
+                // The cursor direction flips by half a turn when the cursor
+                // passes through the center. That is not an intended rotation.
+                angle_delta -= PI.copysign(angle_delta);
+            }
+
+            subgizmo.state.total_angle += angle_delta;
+            subgizmo.state.last_cursor_offset = cursor_offset;
+        }
+
+        if let Some((min, max)) = subgizmo.state.total_angle_limits {
+            // Clamping the unsnapped angle too makes the rotation
+            // respond immediately when the cursor is moved back.
+            subgizmo.state.total_angle = subgizmo.state.total_angle.clamp(min, max);
         }
 
-        let mut angle_delta = rotation_angle - subgizmo.state.last_rotation_angle;
+        let mut total_angle = subgizmo.state.total_angle;
+        if config.snapping {
+            let snap_angle = Float::from(config.snap_angle);
+            total_angle = match subgizmo.state.start_twist_angle {
+                Some(start) if config.rotation_snap_origin == SnapOrigin::Absolute => {
+                    // Results rotate by the negated total angle
+                    start - round_to_interval(start - total_angle, snap_angle)
+                }
+                _ => round_to_interval(total_angle, snap_angle),
+            };
+        }
 
-        // Always take the smallest angle, e.g. -10° instead of 350°
-        if angle_delta > PI {
-            angle_delta -= TAU;
-        } else if angle_delta < -PI {
-            angle_delta += TAU;
+        if let Some((min, max)) = subgizmo.state.total_angle_limits {
+            total_angle = total_angle.clamp(min, max);
         }
 
-        subgizmo.state.last_rotation_angle = rotation_angle;
-        subgizmo.state.current_delta += angle_delta;
+        let angle_delta = total_angle - subgizmo.state.current_delta;
+
+        subgizmo.snap_crossed = config.snapping && angle_delta != 0.0;
+        subgizmo.state.current_delta = total_angle;
 
         let normal = gizmo_local_normal(&subgizmo.config, subgizmo.direction);
 
         Some(GizmoResult::Rotation {
-            axis: normal.into(),
-            delta: -angle_delta,
-            total: subgizmo.state.current_delta,
+            axis: vec3_to_mint(normal),
+            delta: float_to_f64(-angle_delta),
+            total: float_to_f64(subgizmo.state.current_delta),
+            revolutions: full_revolutions(float_to_f64(subgizmo.state.current_delta)),
             is_view_axis: subgizmo.direction == GizmoDirection::View,
+            pivot: rotation_pivot(&subgizmo.config),
+            snapped_to: None,
         })
     }
 
+    fn wheel(subgizmo: &RotationSubGizmo, steps: Float) -> Option<GizmoResult> {
+        // Results rotate by the negated total angle, like when dragging
+        let mut total_angle = -Float::from(subgizmo.config.snap_angle) * steps;
+        if let Some((min, max)) = total_angle_limits(subgizmo) {
+            total_angle = total_angle.clamp(min, max);
+        }
+        let total = float_to_f64(total_angle);
+
+        Some(GizmoResult::Rotation {
+            axis: vec3_to_mint(gizmo_local_normal(&subgizmo.config, subgizmo.direction)),
+            delta: -total,
+            total,
+            revolutions: full_revolutions(total),
+            is_view_axis: subgizmo.direction == GizmoDirection::View,
+            pivot: rotation_pivot(&subgizmo.config),
+            snapped_to: None,
+        })
+    }
+
+    fn interaction_points(subgizmo: &RotationSubGizmo) -> Option<(FVec3, FVec3)> {
+        let origin = subgizmo.config.translation;
+        let normal = gizmo_normal(&subgizmo.config, subgizmo.direction);
+        let start_point = subgizmo.state.start_point;
+
+        // The targets are rotated by the negated total angle
+        let rotation = FQuat::from_axis_angle(normal, -subgizmo.state.current_delta);
+
+        Some((start_point, origin + rotation * (start_point - origin)))
+    }
+
     fn draw(subgizmo: &RotationSubGizmo) -> GizmoDrawData {
         let config = subgizmo.config;
 
@@ -144,9 +244,9 @@ impl SubGizmoKind for Rotation {
 
             let total_angle = end_angle - start_angle;
 
-            let full_circles = (total_angle / std::f64::consts::TAU).abs() as u32;
+            let full_circles = (total_angle / TAU).abs() as u32;
 
-            end_angle -= TAU * full_circles as f64;
+            end_angle -= TAU * full_circles as Float;
 
             let mut start_angle_2 = end_angle;
             let mut end_angle_2 = start_angle + TAU;
@@ -165,9 +265,9 @@ impl SubGizmoKind for Rotation {
             draw_data += shape_builder
                 .polyline(
                     &[
-                        DVec3::new(start_angle.cos() * radius, 0.0, start_angle.sin() * radius),
-                        DVec3::new(0.0, 0.0, 0.0),
-                        DVec3::new(end_angle.cos() * radius, 0.0, end_angle.sin() * radius),
+                        FVec3::new(start_angle.cos() * radius, 0.0, start_angle.sin() * radius),
+                        FVec3::new(0.0, 0.0, 0.0),
+                        FVec3::new(end_angle.cos() * radius, 0.0, end_angle.sin() * radius),
                     ],
                     stroke,
                 )
@@ -197,12 +297,47 @@ impl SubGizmoKind for Rotation {
 
             draw_data += shape_builder.circle(radius, stroke).into();
 
+            // Draw the allowed range of a limited rotation
+            if let Some((min, max)) = subgizmo.state.total_angle_limits {
+                let start_angle = subgizmo.state.start_axis_angle + FRAC_PI_2;
+                draw_data += shape_builder
+                    .arc(
+                        radius * 1.1,
+                        start_angle + min,
+                        start_angle + max,
+                        (stroke.0 / 2.0, config.visuals.limit_color),
+                    )
+                    .into();
+            }
+
+            let tick = |angle: Float, color: Color32| {
+                let pos = FVec3::new(angle.cos(), 0.0, angle.sin());
+                shape_builder.line_segment(
+                    pos * radius * 0.9,
+                    pos * radius * 1.25,
+                    (stroke.0 / 2.0, color),
+                )
+            };
+
+            if config.visuals.rotation_start_tick {
+                draw_data += tick(subgizmo.state.start_axis_angle + FRAC_PI_2, color).into();
+            }
+
+            if config.visuals.rotation_zero_tick {
+                if let Some(direction) = zero_twist_direction(subgizmo) {
+                    // Angle of the direction on the plane of the drawn ring
+                    let direction = transform.inverse().transform_vector3(direction);
+                    let angle = Float::atan2(direction.z, direction.x);
+                    draw_data += tick(angle, config.visuals.s_color).into();
+                }
+            }
+
             // Draw snapping ticks
             if config.snapping {
                 let stroke_width = stroke.0 / 2.0;
-                for i in 0..((TAU / config.snap_angle as f64) as usize + 1) {
-                    let angle = i as f64 * config.snap_angle as f64 + end_angle;
-                    let pos = DVec3::new(angle.cos(), 0.0, angle.sin());
+                for i in 0..((TAU / Float::from(config.snap_angle)) as usize + 1) {
+                    let angle = i as Float * Float::from(config.snap_angle) + end_angle;
+                    let pos = FVec3::new(angle.cos(), 0.0, angle.sin());
                     draw_data += shape_builder
                         .line_segment(
                             pos * radius * 1.1,
@@ -220,38 +355,101 @@ impl SubGizmoKind for Rotation {
 
 /// Calculates angle of the rotation axis arc.
 /// The arc is a semicircle, which turns into a full circle when viewed
-/// directly from the front.
-fn arc_angle(subgizmo: &SubGizmoConfig<Rotation>) -> f64 {
+/// directly from the front, or always when full circle handles are configured.
+fn arc_angle(subgizmo: &SubGizmoConfig<Rotation>) -> Float {
+    if subgizmo.config.full_circle_rotation_handles {
+        return PI;
+    }
+
     let dot = gizmo_normal(&subgizmo.config, subgizmo.direction)
         .dot(subgizmo.config.view_forward())
         .abs();
     let min_dot = 0.990;
     let max_dot = 0.995;
 
-    let mut angle =
-        f64::min(1.0, f64::max(0.0, dot - min_dot) / (max_dot - min_dot)) * FRAC_PI_2 + FRAC_PI_2;
+    let mut angle = Float::min(1.0, Float::max(0.0, dot - min_dot) / (max_dot - min_dot))
+        * FRAC_PI_2
+        + FRAC_PI_2;
     if (angle - PI).abs() < 1e-2 {
         angle = PI;
     }
     angle
 }
 
+/// Allowed range of the total rotation angle, based on the rotation limits
+/// and the rotation of the gizmo when the rotation was started.
+/// Invalid limits are ignored, so that clamping to the range cannot panic.
+fn total_angle_limits(subgizmo: &SubGizmoConfig<Rotation>) -> Option<(Float, Float)> {
+    let limit =
+        subgizmo.config.rotation_limits[axis_index(subgizmo)?].filter(TransformLimit::is_valid)?;
+    let angle = twist_angle(subgizmo)?;
+
+    // Results rotate by the negated total angle
+    Some((
+        angle - float_from_f64(limit.max),
+        angle - float_from_f64(limit.min),
+    ))
+}
+
+/// Twist of the current rotation around the rotation axis, in the range [-PI, PI].
+/// `None` for the view axis.
+fn twist_angle(subgizmo: &SubGizmoConfig<Rotation>) -> Option<Float> {
+    let index = axis_index(subgizmo)?;
+
+    let rotation = subgizmo.config.rotation;
+    let mut angle = 2.0 * Float::atan2(rotation.xyz()[index], rotation.w);
+    if angle > PI {
+        angle -= TAU;
+    } else if angle < -PI {
+        angle += TAU;
+    }
+
+    Some(angle)
+}
+
+/// World space direction on the rotation plane where the twist around the rotation axis
+/// would be zero, i.e. the direction of the next axis in the unrotated frame.
+/// `None` for the view axis.
+fn zero_twist_direction(subgizmo: &SubGizmoConfig<Rotation>) -> Option<FVec3> {
+    let index = axis_index(subgizmo)?;
+    let reference = FVec3::AXES[(index + 1) % 3];
+
+    if !subgizmo.config.local_space() {
+        return Some(reference);
+    }
+
+    // Undo the twist of the rotated reference axis
+    let normal = gizmo_normal(&subgizmo.config, subgizmo.direction);
+    let untwist = FQuat::from_axis_angle(normal, -twist_angle(subgizmo)?);
+
+    Some(untwist * (subgizmo.config.rotation * reference))
+}
+
+fn axis_index(subgizmo: &SubGizmoConfig<Rotation>) -> Option<usize> {
+    match subgizmo.direction {
+        GizmoDirection::X => Some(0),
+        GizmoDirection::Y => Some(1),
+        GizmoDirection::Z => Some(2),
+        GizmoDirection::View => None,
+    }
+}
+
 /// Calculates a matrix used when rendering the rotation axis.
-fn rotation_matrix(subgizmo: &SubGizmoConfig<Rotation>) -> DMat4 {
+fn rotation_matrix(subgizmo: &SubGizmoConfig<Rotation>) -> FMat4 {
     if subgizmo.direction == GizmoDirection::View {
         let forward = subgizmo.config.view_forward();
         let right = subgizmo.config.view_right();
         let up = subgizmo.config.view_up();
 
-        let rotation = DQuat::from_mat3(&DMat3::from_cols(up, -forward, -right));
+        let rotation = FQuat::from_mat3(&FMat3::from_cols(up, -forward, -right));
 
-        return DMat4::from_rotation_translation(rotation, subgizmo.config.translation);
+        return FMat4::from_rotation_translation(rotation, subgizmo.config.translation);
     }
 
     // First rotate towards the gizmo normal
     let local_normal = gizmo_local_normal(&subgizmo.config, subgizmo.direction);
-    let rotation = rotation_align(DVec3::Y, local_normal);
-    let mut rotation = DQuat::from_mat3(&rotation);
+    let rotation = rotation_align(FVec3::Y, local_normal);
+    let mut rotation = FQuat::from_mat3(&rotation);
     let config = subgizmo.config;
 
     if config.local_space() {
@@ -264,44 +462,58 @@ fn rotation_matrix(subgizmo: &SubGizmoConfig<Rotation>) -> DMat4 {
     if config.left_handed {
         forward *= -1.0;
     }
-    let angle = f64::atan2(tangent.cross(forward).dot(normal), tangent.dot(forward));
+    let angle = Float::atan2(tangent.cross(forward).dot(normal), tangent.dot(forward));
 
     // Rotate towards the camera, along the rotation axis.
-    rotation = DQuat::from_axis_angle(normal, angle) * rotation;
+    rotation = FQuat::from_axis_angle(normal, angle) * rotation;
 
-    DMat4::from_rotation_translation(rotation, config.translation)
+    FMat4::from_rotation_translation(rotation, config.translation)
 }
 
-fn rotation_angle(subgizmo: &SubGizmoConfig<Rotation>, cursor_pos: Pos2) -> Option<f64> {
+/// Cursor position relative to the gizmo center in screen space.
+fn cursor_offset(subgizmo: &SubGizmoConfig<Rotation>, cursor_pos: Pos2) -> Option<FVec2> {
     let viewport = subgizmo.config.viewport;
-    let gizmo_pos = world_to_screen(viewport, subgizmo.config.mvp, DVec3::new(0.0, 0.0, 0.0))?;
-    let delta = DVec2::new(
-        cursor_pos.x as f64 - gizmo_pos.x as f64,
-        cursor_pos.y as f64 - gizmo_pos.y as f64,
-    )
-    .normalize();
-
-    if delta.is_nan() {
-        return None;
-    }
+    let gizmo_pos = world_to_screen(viewport, subgizmo.config.mvp, FVec3::new(0.0, 0.0, 0.0))?;
 
-    let mut angle = f64::atan2(delta.y, delta.x);
+    Some(FVec2::new(
+        Float::from(cursor_pos.x) - Float::from(gizmo_pos.x),
+        Float::from(cursor_pos.y) - Float::from(gizmo_pos.y),
+    ))
+}
+
+/// Direction of the rotation in screen space, depending on
+/// which side of the rotation axis the camera is.
+fn rotation_sign(subgizmo: &SubGizmoConfig<Rotation>) -> Float {
     if subgizmo
         .config
         .view_forward()
         .dot(gizmo_normal(&subgizmo.config, subgizmo.direction))
         < 0.0
     {
-        angle *= -1.0;
+        -1.0
+    } else {
+        1.0
     }
+}
 
-    Some(angle)
+/// Signed angle from `from` to `to`, between -PI and PI.
+fn signed_angle(from: FVec2, to: FVec2) -> Float {
+    Float::atan2(from.perp_dot(to), from.dot(to))
 }
 
-fn tangent(subgizmo: &SubGizmoConfig<Rotation>) -> DVec3 {
+/// Whether the cursor passed through the dead zone around the gizmo center
+/// while moving from `from` to `to`, relative to the gizmo center.
+fn swiped_through_center(from: FVec2, to: FVec2, dead_zone: Float) -> bool {
+    let movement = to - from;
+    let t = -from.dot(movement) / movement.length_squared();
+
+    (0.0..=1.0).contains(&t) && (from + movement * t).length() <= dead_zone
+}
+
+fn tangent(subgizmo: &SubGizmoConfig<Rotation>) -> FVec3 {
     let mut tangent = match subgizmo.direction {
-        GizmoDirection::X | GizmoDirection::Y => DVec3::Z,
-        GizmoDirection::Z => -DVec3::Y,
+        GizmoDirection::X | GizmoDirection::Y => FVec3::Z,
+        GizmoDirection::Z => -FVec3::Y,
         GizmoDirection::View => -subgizmo.config.view_right(),
     };
 
@@ -312,10 +524,17 @@ fn tangent(subgizmo: &SubGizmoConfig<Rotation>) -> DVec3 {
     tangent
 }
 
-fn arc_radius(subgizmo: &SubGizmoConfig<Rotation>) -> f64 {
-    if subgizmo.direction == GizmoDirection::View {
-        outer_circle_radius(&subgizmo.config)
-    } else {
-        (subgizmo.config.scale_factor * subgizmo.config.visuals.gizmo_size) as f64
-    }
+fn arc_radius(subgizmo: &SubGizmoConfig<Rotation>) -> Float {
+    let config = &subgizmo.config;
+    let radius_scale = match subgizmo.direction {
+        GizmoDirection::View => {
+            return outer_circle_radius(config)
+                * Float::from(config.visuals.view_rotation_radius_factor)
+        }
+        GizmoDirection::X => config.visuals.rotation_radius_scale[0],
+        GizmoDirection::Y => config.visuals.rotation_radius_scale[1],
+        GizmoDirection::Z => config.visuals.rotation_radius_scale[2],
+    };
+
+    Float::from(config.scale_factor * config.visuals.gizmo_size * radius_scale)
 }
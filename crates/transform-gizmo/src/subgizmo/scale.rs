@@ -1,10 +1,17 @@
-use glam::DVec3;
+use crate::config::Color32;
 
-use crate::math::{round_to_interval, world_to_screen, Pos2};
+use crate::config::{CircleStyle, PreparedGizmoConfig, ScaleInteraction};
+use crate::math::consts::TAU;
+use crate::math::{
+    float_to_f32, intersect_plane, ray_to_ray, round_to_interval, vec3_to_mint, world_to_screen,
+    FMat4, FVec3, Float, Pos2,
+};
+use crate::shape::ShapeBuidler;
 
 use crate::subgizmo::common::{
-    draw_arrow, draw_circle, draw_plane, gizmo_color, gizmo_local_normal, outer_circle_radius,
-    pick_arrow, pick_circle, pick_plane, plane_bitangent, plane_tangent,
+    arrow_area, arrow_direction, circle_area, corner_area, draw_arrow, draw_corner, draw_plane,
+    gizmo_color, gizmo_local_normal, gizmo_normal, outer_circle_radius, pick_arrow, pick_circle,
+    pick_corner, pick_plane, plane_area, plane_bitangent, plane_tangent, view_plane_rotation,
 };
 use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
 use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoResult};
@@ -20,7 +27,14 @@ pub(crate) struct ScaleParams {
 
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct ScaleState {
-    start_delta: f64,
+    start_delta: Float,
+    /// Point in world space where scaling was started.
+    /// Used with [`ScaleInteraction::AxisProjection`].
+    start_point: FVec3,
+    /// Point in world space where the cursor was during the latest update
+    last_point: FVec3,
+    /// Total scale of the previous update
+    last_scale: FVec3,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -30,25 +44,37 @@ impl SubGizmoKind for Scale {
     type Params = ScaleParams;
     type State = ScaleState;
 
-    fn pick(subgizmo: &mut ScaleSubGizmo, ray: Ray) -> Option<f64> {
+    fn pick(subgizmo: &mut ScaleSubGizmo, ray: Ray) -> Option<Float> {
         let pick_result = match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Plane, GizmoDirection::View) => pick_circle(
                 &subgizmo.config,
                 ray,
-                outer_circle_radius(&subgizmo.config),
+                uniform_circle_radius(&subgizmo.config),
                 false,
             ),
             (TransformKind::Plane, _) => pick_plane(&subgizmo.config, ray, subgizmo.direction),
+            (TransformKind::Corner, _) => pick_corner(&subgizmo.config, ray, subgizmo.direction),
             (TransformKind::Axis, _) => {
                 pick_arrow(&subgizmo.config, ray, subgizmo.direction, subgizmo.mode)
             }
         };
 
-        let start_delta = distance_from_origin_2d(subgizmo, ray.screen_pos)?;
+        let start_delta = distance_from_origin_2d(subgizmo, ray.screen_pos);
+        if start_delta.is_none()
+            && subgizmo.config.scale_interaction == ScaleInteraction::CursorDistance
+        {
+            // Scaling by cursor distance requires the gizmo center to be on screen
+            return None;
+        }
 
-        subgizmo.opacity = pick_result.visibility as _;
+        subgizmo.opacity = float_to_f32(pick_result.visibility);
+        subgizmo.last_pick = Some(pick_result);
 
-        subgizmo.state.start_delta = start_delta;
+        subgizmo.state.start_delta = start_delta.unwrap_or_default();
+        subgizmo.state.start_point =
+            point_on_scale_axis(subgizmo, ray).unwrap_or(subgizmo.config.translation);
+        subgizmo.state.last_point = subgizmo.state.start_point;
+        subgizmo.state.last_scale = FVec3::ONE;
 
         if pick_result.picked {
             Some(pick_result.t)
@@ -57,30 +83,67 @@ impl SubGizmoKind for Scale {
         }
     }
 
+    fn mode(subgizmo: &ScaleSubGizmo) -> GizmoMode {
+        subgizmo.mode
+    }
+
+    fn pick_area(subgizmo: &ScaleSubGizmo) -> Float {
+        match (subgizmo.transform_kind, subgizmo.direction) {
+            (TransformKind::Plane, GizmoDirection::View) => circle_area(
+                &subgizmo.config,
+                uniform_circle_radius(&subgizmo.config),
+                false,
+            ),
+            (TransformKind::Plane, _) => plane_area(&subgizmo.config, subgizmo.direction),
+            (TransformKind::Corner, _) => corner_area(&subgizmo.config, subgizmo.direction),
+            (TransformKind::Axis, _) => {
+                arrow_area(&subgizmo.config, subgizmo.direction, subgizmo.mode)
+            }
+        }
+    }
+
     fn update(subgizmo: &mut ScaleSubGizmo, ray: Ray) -> Option<GizmoResult> {
-        let mut delta = distance_from_origin_2d(subgizmo, ray.screen_pos)?;
-        delta /= subgizmo.state.start_delta;
+        if let Some(point) = point_on_scale_axis(subgizmo, ray) {
+            subgizmo.state.last_point = point;
+        }
+
+        let mut delta = match subgizmo.config.scale_interaction {
+            ScaleInteraction::CursorDistance => {
+                distance_from_origin_2d(subgizmo, ray.screen_pos)? / subgizmo.state.start_delta
+            }
+            ScaleInteraction::AxisProjection => projected_scale(subgizmo, ray)?,
+        };
 
         if subgizmo.config.snapping {
-            delta = round_to_interval(delta, subgizmo.config.snap_scale as f64);
+            delta = round_to_interval(delta, Float::from(subgizmo.config.snap_scale));
         }
         delta = delta.max(1e-4) - 1.0;
 
-        let direction = match (subgizmo.transform_kind, subgizmo.direction) {
-            (TransformKind::Axis, _) => gizmo_local_normal(&subgizmo.config, subgizmo.direction),
-            (TransformKind::Plane, GizmoDirection::View) => DVec3::ONE,
-            (TransformKind::Plane, _) => (plane_bitangent(subgizmo.direction)
-                + plane_tangent(subgizmo.direction))
-            .normalize(),
-        };
+        let scale = FVec3::ONE + (scale_direction(subgizmo) * delta);
+        let scale_delta = scale / subgizmo.state.last_scale;
+        subgizmo.snap_crossed = subgizmo.config.snapping && scale != subgizmo.state.last_scale;
+        subgizmo.state.last_scale = scale;
+
+        Some(GizmoResult::Scale {
+            delta: vec3_to_mint(scale_delta),
+            total: vec3_to_mint(scale),
+        })
+    }
 
-        let scale = DVec3::ONE + (direction * delta);
+    fn wheel(subgizmo: &ScaleSubGizmo, steps: Float) -> Option<GizmoResult> {
+        let delta = (1.0 + Float::from(subgizmo.config.snap_scale) * steps).max(1e-4) - 1.0;
+        let scale = FVec3::ONE + (scale_direction(subgizmo) * delta);
 
         Some(GizmoResult::Scale {
-            total: scale.into(),
+            delta: vec3_to_mint(scale),
+            total: vec3_to_mint(scale),
         })
     }
 
+    fn interaction_points(subgizmo: &ScaleSubGizmo) -> Option<(FVec3, FVec3)> {
+        Some((subgizmo.state.start_point, subgizmo.state.last_point))
+    }
+
     fn draw(subgizmo: &ScaleSubGizmo) -> GizmoDrawData {
         match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Axis, _) => draw_arrow(
@@ -90,11 +153,9 @@ impl SubGizmoKind for Scale {
                 subgizmo.direction,
                 subgizmo.mode,
             ),
-            (TransformKind::Plane, GizmoDirection::View) => draw_circle(
+            (TransformKind::Plane, GizmoDirection::View) => draw_uniform_circle(
                 &subgizmo.config,
                 gizmo_color(&subgizmo.config, subgizmo.focused, subgizmo.direction),
-                outer_circle_radius(&subgizmo.config),
-                false,
             ),
             (TransformKind::Plane, _) => draw_plane(
                 &subgizmo.config,
@@ -102,16 +163,160 @@ impl SubGizmoKind for Scale {
                 subgizmo.focused,
                 subgizmo.direction,
             ),
+            (TransformKind::Corner, _) => draw_corner(
+                &subgizmo.config,
+                subgizmo.opacity,
+                subgizmo.focused,
+                subgizmo.direction,
+            ),
+        }
+    }
+}
+
+/// Axes scaled by the subgizmo, in the space of the gizmo
+fn scale_direction(subgizmo: &SubGizmoConfig<Scale>) -> FVec3 {
+    match (subgizmo.transform_kind, subgizmo.direction) {
+        (TransformKind::Axis, _) => gizmo_local_normal(&subgizmo.config, subgizmo.direction),
+        (TransformKind::Plane, GizmoDirection::View) | (TransformKind::Corner, _) => FVec3::ONE,
+        (TransformKind::Plane, _) => {
+            (plane_bitangent(subgizmo.direction) + plane_tangent(subgizmo.direction)).normalize()
+        }
+    }
+}
+
+/// Radius of the uniform scale circle.
+///
+/// The circle is moved outwards when the view rotation circle is also shown,
+/// since they would overlap otherwise.
+fn uniform_circle_radius(config: &PreparedGizmoConfig) -> Float {
+    let rotate_view_enabled = config
+        .mode_override
+        .map_or(config.modes.contains(GizmoMode::RotateView), |mode| {
+            mode == GizmoMode::RotateView
+        });
+
+    let offset = if rotate_view_enabled {
+        config.scale_factor * config.visuals.uniform_scale_offset
+    } else {
+        0.0
+    };
+
+    outer_circle_radius(config) * Float::from(config.visuals.uniform_scale_radius_factor)
+        + Float::from(offset)
+}
+
+/// Draws the uniform scale circle using [`GizmoVisuals::uniform_scale_style`](crate::GizmoVisuals::uniform_scale_style)
+fn draw_uniform_circle(config: &PreparedGizmoConfig, color: Color32) -> GizmoDrawData {
+    /// Number of dashes in a dashed circle
+    const DASH_COUNT: u32 = 24;
+
+    if color.a() == 0 {
+        return GizmoDrawData::default();
+    }
+
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection
+            * FMat4::from_rotation_translation(view_plane_rotation(config), config.translation),
+        config.viewport,
+        config.pixels_per_point,
+    );
+
+    let radius = uniform_circle_radius(config);
+    let stroke_width = config.visuals.stroke_width;
+
+    let mut draw_data = GizmoDrawData::default();
+
+    match config.visuals.uniform_scale_style {
+        CircleStyle::Solid => {
+            draw_data += shape_builder.circle(radius, (stroke_width, color)).into();
+        }
+        CircleStyle::Dashed => {
+            let step = TAU / DASH_COUNT as Float;
+            for i in 0..DASH_COUNT {
+                let start = step * i as Float;
+                draw_data += shape_builder
+                    .arc(radius, start, start + step / 2.0, (stroke_width, color))
+                    .into();
+            }
+        }
+        CircleStyle::Double => {
+            let spacing = Float::from(config.scale_factor * stroke_width * 0.5);
+            for radius in [radius - spacing, radius + spacing] {
+                draw_data += shape_builder
+                    .circle(radius, (stroke_width / 2.0, color))
+                    .into();
+            }
+        }
+    }
+
+    draw_data
+}
+
+/// Scale from cursor movement projected onto the scaled axis or plane.
+fn projected_scale(subgizmo: &ScaleSubGizmo, ray: Ray) -> Option<Float> {
+    let config = subgizmo.config;
+    let origin = config.translation;
+    let start_point = subgizmo.state.start_point;
+
+    let direction = match (subgizmo.transform_kind, subgizmo.direction) {
+        // Scaling grows when moving along the arrow, even if it is flipped
+        (TransformKind::Axis, _) => arrow_direction(&config, subgizmo.direction),
+        // Uniform scaling grows when moving away from the center
+        (TransformKind::Plane, GizmoDirection::View) | (TransformKind::Corner, _) => {
+            (start_point - origin).normalize_or_zero()
         }
+        (TransformKind::Plane, _) => {
+            let direction = (plane_bitangent(subgizmo.direction)
+                + plane_tangent(subgizmo.direction))
+            .normalize();
+            if config.local_space() {
+                config.rotation * direction
+            } else {
+                direction
+            }
+        }
+    };
+
+    // Size of the gizmo in world space
+    let reference_length = Float::from(config.scale_factor * config.visuals.gizmo_size);
+
+    let point = point_on_scale_axis(subgizmo, ray)?;
+
+    Some(1.0 + (point - start_point).dot(direction) / reference_length)
+}
+
+/// Finds the point on the scaled axis or plane nearest to the ray
+fn point_on_scale_axis(subgizmo: &ScaleSubGizmo, ray: Ray) -> Option<FVec3> {
+    let config = subgizmo.config;
+    let origin = config.translation;
+
+    if subgizmo.transform_kind == TransformKind::Axis {
+        let direction = gizmo_normal(&config, subgizmo.direction);
+        let (_ray_t, subgizmo_t) = ray_to_ray(ray.origin, ray.direction, origin, direction);
+
+        return Some(origin + direction * subgizmo_t);
+    }
+
+    let plane_normal = if subgizmo.direction == GizmoDirection::View {
+        config.view_forward()
+    } else {
+        gizmo_normal(&config, subgizmo.direction)
+    };
+
+    let mut t = 0.0;
+    if intersect_plane(plane_normal, origin, ray.origin, ray.direction, &mut t) {
+        Some(ray.origin + ray.direction * t)
+    } else {
+        None
     }
 }
 
 fn distance_from_origin_2d<T: SubGizmoKind>(
     subgizmo: &SubGizmoConfig<T>,
     cursor_pos: Pos2,
-) -> Option<f64> {
+) -> Option<Float> {
     let viewport = subgizmo.config.viewport;
-    let gizmo_pos = world_to_screen(viewport, subgizmo.config.mvp, DVec3::new(0.0, 0.0, 0.0))?;
+    let gizmo_pos = world_to_screen(viewport, subgizmo.config.mvp, FVec3::new(0.0, 0.0, 0.0))?;
 
-    Some(cursor_pos.distance(gizmo_pos) as f64)
+    Some(Float::from(cursor_pos.distance(gizmo_pos)))
 }
@@ -1,14 +1,23 @@
-use crate::math::{intersect_plane, ray_to_ray, round_to_interval, DVec3};
+use crate::math::{
+    float_from_f64, float_to_f32, float_to_f64, intersect_plane, ray_to_ray, round_to_interval,
+    vec3_from_mint, vec3_to_mint, world_to_screen, FVec3, Float,
+};
 
+use crate::config::SnapOrigin;
+use crate::shape::ShapeBuidler;
 use crate::subgizmo::common::{
-    draw_arrow, draw_circle, draw_plane, gizmo_color, gizmo_normal, inner_circle_radius,
-    pick_arrow, pick_circle, pick_plane, plane_bitangent, plane_global_origin, plane_tangent,
+    arrow_area, circle_area, draw_arrow, draw_circle, draw_plane, gizmo_color, gizmo_normal,
+    inner_circle_radius, pick_arrow, pick_circle, pick_plane, plane_area, plane_bitangent,
+    plane_global_origin, plane_tangent, snap_to_absolute_interval,
 };
 use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
 use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoOrientation, GizmoResult};
 
 pub(crate) type TranslationSubGizmo = SubGizmoConfig<Translation>;
 
+/// Number of snapping grid cells drawn on each side of the drag start point
+const SNAP_GRID_CELLS: i32 = 4;
+
 #[derive(Debug, Copy, Clone, Hash)]
 pub(crate) struct TranslationParams {
     pub mode: GizmoMode,
@@ -18,10 +27,14 @@ pub(crate) struct TranslationParams {
 
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct TranslationState {
-    start_view_dir: DVec3,
-    start_point: DVec3,
-    last_point: DVec3,
-    current_delta: DVec3,
+    start_view_dir: FVec3,
+    start_point: FVec3,
+    last_point: FVec3,
+    current_delta: FVec3,
+    /// Gizmo position when translation was started
+    start_translation: FVec3,
+    /// Whether the translation is currently clamped to the limits
+    clamped: bool,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -31,7 +44,7 @@ impl SubGizmoKind for Translation {
     type Params = TranslationParams;
     type State = TranslationState;
 
-    fn pick(subgizmo: &mut TranslationSubGizmo, ray: Ray) -> Option<f64> {
+    fn pick(subgizmo: &mut TranslationSubGizmo, ray: Ray) -> Option<Float> {
         let pick_result = match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Plane, GizmoDirection::View) => pick_circle(
                 &subgizmo.config,
@@ -39,18 +52,22 @@ impl SubGizmoKind for Translation {
                 inner_circle_radius(&subgizmo.config),
                 true,
             ),
-            (TransformKind::Plane, _) => pick_plane(&subgizmo.config, ray, subgizmo.direction),
             (TransformKind::Axis, _) => {
                 pick_arrow(&subgizmo.config, ray, subgizmo.direction, subgizmo.mode)
             }
+            // Planes, and the corners of scaling that translation handles like planes
+            _ => pick_plane(&subgizmo.config, ray, subgizmo.direction),
         };
 
-        subgizmo.opacity = pick_result.visibility as _;
+        subgizmo.opacity = float_to_f32(pick_result.visibility);
+        subgizmo.last_pick = Some(pick_result);
 
         subgizmo.state.start_view_dir = subgizmo.config.view_forward();
         subgizmo.state.start_point = pick_result.subgizmo_point;
         subgizmo.state.last_point = pick_result.subgizmo_point;
-        subgizmo.state.current_delta = DVec3::ZERO;
+        subgizmo.state.current_delta = FVec3::ZERO;
+        subgizmo.state.start_translation = subgizmo.config.translation;
+        subgizmo.state.clamped = false;
 
         if pick_result.picked {
             Some(pick_result.t)
@@ -59,6 +76,24 @@ impl SubGizmoKind for Translation {
         }
     }
 
+    fn mode(subgizmo: &TranslationSubGizmo) -> GizmoMode {
+        subgizmo.mode
+    }
+
+    fn pick_area(subgizmo: &TranslationSubGizmo) -> Float {
+        match (subgizmo.transform_kind, subgizmo.direction) {
+            (TransformKind::Plane, GizmoDirection::View) => circle_area(
+                &subgizmo.config,
+                inner_circle_radius(&subgizmo.config),
+                true,
+            ),
+            (TransformKind::Axis, _) => {
+                arrow_area(&subgizmo.config, subgizmo.direction, subgizmo.mode)
+            }
+            _ => plane_area(&subgizmo.config, subgizmo.direction),
+        }
+    }
+
     fn update(subgizmo: &mut TranslationSubGizmo, ray: Ray) -> Option<GizmoResult> {
         if subgizmo.config.view_forward() != subgizmo.state.start_view_dir {
             // If the view_forward direction has changed, i.e. camera has rotated,
@@ -78,10 +113,19 @@ impl SubGizmoKind for Translation {
             )?
         };
 
+        if subgizmo.config.mirrored && subgizmo.direction == GizmoDirection::View {
+            // Reflect the horizontal screen space movement
+            let right = subgizmo.config.view_right();
+            let delta = new_point - subgizmo.state.start_point;
+            new_point -= right * 2.0 * delta.dot(right);
+        }
+
         let mut new_delta = new_point - subgizmo.state.start_point;
 
         if subgizmo.config.snapping {
-            new_delta = if subgizmo.transform_kind == TransformKind::Axis {
+            new_delta = if subgizmo.config.translation_snap_origin == SnapOrigin::Absolute {
+                snap_translation_absolute(subgizmo, new_delta)
+            } else if subgizmo.transform_kind == TransformKind::Axis {
                 snap_translation_vector(subgizmo, new_delta)
             } else {
                 snap_translation_plane(subgizmo, new_delta)
@@ -89,6 +133,11 @@ impl SubGizmoKind for Translation {
             new_point = subgizmo.state.start_point + new_delta;
         }
 
+        let clamped_point = clamp_to_limits(subgizmo, new_point);
+        subgizmo.state.clamped = clamped_point != new_point;
+        new_point = clamped_point;
+        new_delta = new_point - subgizmo.state.start_point;
+
         let mut translation_delta = new_point - subgizmo.state.last_point;
         let mut total_translation = new_point - subgizmo.state.start_point;
 
@@ -98,17 +147,63 @@ impl SubGizmoKind for Translation {
             total_translation = inverse_rotation * total_translation;
         }
 
+        subgizmo.snap_crossed =
+            subgizmo.config.snapping && new_delta != subgizmo.state.current_delta;
         subgizmo.state.last_point = new_point;
         subgizmo.state.current_delta = new_delta;
 
         Some(GizmoResult::Translation {
-            delta: translation_delta.into(),
-            total: total_translation.into(),
+            delta: vec3_to_mint(translation_delta),
+            total: vec3_to_mint(total_translation),
+            constraint_position: None,
+        })
+    }
+
+    fn wheel(subgizmo: &TranslationSubGizmo, steps: Float) -> Option<GizmoResult> {
+        // Only the axes have an unambiguous direction
+        if subgizmo.transform_kind != TransformKind::Axis {
+            return None;
+        }
+
+        let direction = gizmo_normal(&subgizmo.config, subgizmo.direction);
+        let distance = subgizmo.config.translation_snap_distance_along(direction) * steps;
+
+        let start_point = subgizmo.state.start_point;
+        let mut delta = clamp_to_limits(subgizmo, start_point + direction * distance) - start_point;
+
+        if subgizmo.config.orientation() == GizmoOrientation::Local {
+            delta = subgizmo.config.rotation.inverse() * delta;
+        }
+
+        Some(GizmoResult::Translation {
+            delta: vec3_to_mint(delta),
+            total: vec3_to_mint(delta),
+            constraint_position: None,
         })
     }
 
+    fn interaction_points(subgizmo: &TranslationSubGizmo) -> Option<(FVec3, FVec3)> {
+        Some((subgizmo.state.start_point, subgizmo.state.last_point))
+    }
+
     fn draw(subgizmo: &TranslationSubGizmo) -> GizmoDrawData {
-        match (subgizmo.transform_kind, subgizmo.direction) {
+        let mut draw_data = GizmoDrawData::default();
+
+        if subgizmo.active && subgizmo.state.clamped {
+            // Indicate that the translation has reached its limits
+            draw_data += draw_circle(
+                &subgizmo.config,
+                subgizmo.config.visuals.limit_color,
+                inner_circle_radius(&subgizmo.config),
+                true,
+            );
+        }
+
+        if subgizmo.active && subgizmo.transform_kind != TransformKind::Axis {
+            draw_data += draw_snap_grid(subgizmo);
+        }
+
+        draw_data += match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Axis, _) => draw_arrow(
                 &subgizmo.config,
                 subgizmo.opacity,
@@ -122,18 +217,117 @@ impl SubGizmoKind for Translation {
                 inner_circle_radius(&subgizmo.config),
                 false,
             ),
-            (TransformKind::Plane, _) => draw_plane(
+            _ => draw_plane(
                 &subgizmo.config,
                 subgizmo.opacity,
                 subgizmo.focused,
                 subgizmo.direction,
             ),
+        };
+
+        draw_data
+    }
+}
+
+/// Draws the grid of snapped gizmo positions on the plane of the subgizmo,
+/// around the position where the translation was started
+fn draw_snap_grid(subgizmo: &TranslationSubGizmo) -> GizmoDrawData {
+    let config = &subgizmo.config;
+    if !config.visuals.snap_grid || !config.snapping || subgizmo.direction == GizmoDirection::View {
+        return GizmoDrawData::default();
+    }
+
+    let mut bitangent = plane_bitangent(subgizmo.direction);
+    let mut tangent = plane_tangent(subgizmo.direction);
+    if config.local_space() {
+        bitangent = config.rotation * bitangent;
+        tangent = config.rotation * tangent;
+    }
+
+    let bitangent_interval = config.translation_snap_distance_along(bitangent);
+    let tangent_interval = config.translation_snap_distance_along(tangent);
+    if bitangent_interval <= 0.0 || tangent_interval <= 0.0 {
+        return GizmoDrawData::default();
+    }
+
+    let mut center = subgizmo.state.start_translation;
+    if config.translation_snap_origin == SnapOrigin::Absolute {
+        let origin = vec3_from_mint(config.snap_origin);
+        center +=
+            snap_to_absolute_interval(center - origin, FVec3::ZERO, bitangent, bitangent_interval);
+        center +=
+            snap_to_absolute_interval(center - origin, FVec3::ZERO, tangent, tangent_interval);
+    }
+
+    // Skip the grid if it would be too dense to be of any use
+    let Some(screen_center) = world_to_screen(config.viewport, config.view_projection, center)
+    else {
+        return GizmoDrawData::default();
+    };
+    let too_dense = [(bitangent, bitangent_interval), (tangent, tangent_interval)]
+        .into_iter()
+        .any(|(axis, interval)| {
+            world_to_screen(
+                config.viewport,
+                config.view_projection,
+                center + axis * interval,
+            )
+            .map_or(true, |pos| {
+                pos.distance(screen_center) < config.visuals.snap_grid_min_spacing
+            })
+        });
+    if too_dense {
+        return GizmoDrawData::default();
+    }
+
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection,
+        config.viewport,
+        config.pixels_per_point,
+    );
+
+    let stroke = (
+        config.visuals.stroke_width / 4.0,
+        gizmo_color(config, false, subgizmo.direction).gamma_multiply(0.3),
+    );
+
+    let mut draw_data = GizmoDrawData::default();
+    for i in -SNAP_GRID_CELLS..=SNAP_GRID_CELLS {
+        for (axis, axis_interval, other, other_interval) in [
+            (bitangent, bitangent_interval, tangent, tangent_interval),
+            (tangent, tangent_interval, bitangent, bitangent_interval),
+        ] {
+            let extent = SNAP_GRID_CELLS as Float * axis_interval;
+            let line_center = center + other * (i as Float * other_interval);
+            draw_data += shape_builder
+                .line_segment(
+                    line_center - axis * extent,
+                    line_center + axis * extent,
+                    stroke,
+                )
+                .into();
         }
     }
+
+    draw_data
+}
+
+/// Clamps a point so that the resulting gizmo position stays within the translation limits
+fn clamp_to_limits(subgizmo: &SubGizmoConfig<Translation>, point: FVec3) -> FVec3 {
+    let offset = subgizmo.state.start_translation - subgizmo.state.start_point;
+    let mut translation = point + offset;
+
+    for (i, limit) in subgizmo.config.translation_limits.iter().enumerate() {
+        if let Some(limit) = limit {
+            translation[i] = float_from_f64(limit.clamp(float_to_f64(translation[i])));
+        }
+    }
+
+    translation - offset
 }
 
 /// Finds the nearest point on line that points in translation subgizmo direction
-fn point_on_axis(subgizmo: &SubGizmoConfig<Translation>, ray: Ray) -> DVec3 {
+fn point_on_axis(subgizmo: &SubGizmoConfig<Translation>, ray: Ray) -> FVec3 {
     let origin = subgizmo.config.translation;
     let direction = gizmo_normal(&subgizmo.config, subgizmo.direction);
 
@@ -142,7 +336,7 @@ fn point_on_axis(subgizmo: &SubGizmoConfig<Translation>, ray: Ray) -> DVec3 {
     origin + direction * subgizmo_t
 }
 
-fn point_on_plane(plane_normal: DVec3, plane_origin: DVec3, ray: Ray) -> Option<DVec3> {
+fn point_on_plane(plane_normal: FVec3, plane_origin: FVec3, ray: Ray) -> Option<FVec3> {
     let mut t = 0.0;
     if !intersect_plane(
         plane_normal,
@@ -157,17 +351,52 @@ fn point_on_plane(plane_normal: DVec3, plane_origin: DVec3, ray: Ray) -> Option<
     }
 }
 
-fn snap_translation_vector(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVec3) -> DVec3 {
+fn snap_translation_vector(subgizmo: &SubGizmoConfig<Translation>, new_delta: FVec3) -> FVec3 {
     let delta_length = new_delta.length();
     if delta_length > 1e-5 {
         new_delta / delta_length
-            * round_to_interval(delta_length, subgizmo.config.snap_distance as f64)
+            * round_to_interval(
+                delta_length,
+                subgizmo.config.translation_snap_distance_along(new_delta),
+            )
     } else {
         new_delta
     }
 }
 
-fn snap_translation_plane(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVec3) -> DVec3 {
+/// Snaps the translation so that the gizmo position is a multiple of
+/// the snapping distance along each axis of the subgizmo.
+fn snap_translation_absolute(subgizmo: &SubGizmoConfig<Translation>, new_delta: FVec3) -> FVec3 {
+    let config = &subgizmo.config;
+    let axes = if subgizmo.transform_kind == TransformKind::Axis {
+        [gizmo_normal(config, subgizmo.direction), FVec3::ZERO]
+    } else if subgizmo.direction == GizmoDirection::View {
+        // View plane translations are not snapped
+        return new_delta;
+    } else {
+        let mut bitangent = plane_bitangent(subgizmo.direction);
+        let mut tangent = plane_tangent(subgizmo.direction);
+        if config.local_space() {
+            bitangent = config.rotation * bitangent;
+            tangent = config.rotation * tangent;
+        }
+        [bitangent, tangent]
+    };
+
+    // Positions are snapped relative to the origin of the snapping lattice
+    let start = subgizmo.state.start_translation - vec3_from_mint(config.snap_origin);
+
+    axes.into_iter().fold(new_delta, |delta, axis| {
+        snap_to_absolute_interval(
+            start,
+            delta,
+            axis,
+            config.translation_snap_distance_along(axis),
+        )
+    })
+}
+
+fn snap_translation_plane(subgizmo: &SubGizmoConfig<Translation>, new_delta: FVec3) -> FVec3 {
     let mut bitangent = plane_bitangent(subgizmo.direction);
     let mut tangent = plane_tangent(subgizmo.direction);
     if subgizmo.config.local_space() {
@@ -181,9 +410,14 @@ fn snap_translation_plane(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVe
     let n = gizmo_normal(&subgizmo.config, subgizmo.direction);
 
     if lb > 1e-5 && lt > 1e-5 {
-        bitangent * round_to_interval(lt, subgizmo.config.snap_distance as f64) * (ct / lt).dot(n)
+        bitangent
+            * round_to_interval(
+                lt,
+                subgizmo.config.translation_snap_distance_along(bitangent),
+            )
+            * (ct / lt).dot(n)
             + tangent
-                * round_to_interval(lb, subgizmo.config.snap_distance as f64)
+                * round_to_interval(lb, subgizmo.config.translation_snap_distance_along(tangent))
                 * (cb / lb).dot(n)
     } else {
         new_delta
@@ -0,0 +1,242 @@
+//! Minimal tessellator for the shapes of the gizmo, used when the `epaint` feature is disabled.
+//!
+//! Supports the subset of epaint shapes drawn by the gizmo: lines and convex polygons.
+//! The edges are anti-aliased by feathering them, like epaint does.
+
+use crate::config::Color32;
+use crate::math::{Pos2, Vec2};
+use crate::shape::{Mesh, Shape, Stroke, Vertex};
+
+/// Maximum length of the offset of a path corner, relative to the half width of the stroke.
+/// Limits the length of the corners at very sharp angles.
+const MAX_MITER_LENGTH: f32 = 2.0;
+
+impl Stroke {
+    fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.color == Color32::TRANSPARENT
+    }
+}
+
+impl Mesh {
+    fn add_vertex(&mut self, pos: Pos2, color: Color32) -> u32 {
+        self.vertices.push(Vertex { pos, color });
+        self.vertices.len() as u32 - 1
+    }
+
+    fn add_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    /// Adds two triangles between the edges `a0`-`a1` and `b0`-`b1`
+    fn add_quad(&mut self, a0: u32, a1: u32, b0: u32, b1: u32) {
+        self.add_triangle(a0, a1, b1);
+        self.add_triangle(a0, b1, b0);
+    }
+}
+
+/// Tessellates the shape, feathering its edges by one physical pixel
+pub(crate) fn tessellate(shape: Shape, pixels_per_point: f32) -> Mesh {
+    let feathering = 1.0 / pixels_per_point.max(f32::EPSILON);
+    let mut mesh = Mesh::default();
+
+    match shape {
+        Shape::Noop => {}
+        Shape::LineSegment { points, stroke } => {
+            stroke_path(&mut mesh, &points, false, stroke, feathering);
+        }
+        Shape::Path {
+            mut points,
+            closed,
+            fill,
+            stroke,
+        } => {
+            points.dedup_by(|a, b| a.distance_sq(*b) < 1e-12);
+
+            if closed && fill != Color32::TRANSPARENT {
+                fill_convex_path(&mut mesh, &points, fill, feathering);
+            }
+            stroke_path(&mut mesh, &points, closed, stroke, feathering);
+        }
+    }
+
+    mesh
+}
+
+/// Normals of the path at each point, scaled so that offsetting the points
+/// by the normals keeps the offset edges at a unit distance from the path
+fn path_normals(points: &[Pos2], closed: bool) -> Vec<Vec2> {
+    let count = points.len();
+
+    let edge_normal = |from: usize, to: usize| (points[to] - points[from]).normalized().rot90();
+
+    (0..count)
+        .map(|i| {
+            let previous = if i > 0 {
+                Some(edge_normal(i - 1, i))
+            } else if closed {
+                Some(edge_normal(count - 1, 0))
+            } else {
+                None
+            };
+            let next = if i + 1 < count {
+                Some(edge_normal(i, i + 1))
+            } else if closed {
+                Some(edge_normal(count - 1, 0))
+            } else {
+                None
+            };
+
+            match (previous, next) {
+                (Some(previous), Some(next)) => {
+                    let normal = (previous + next) / 2.0;
+                    let length_sq = normal.length_sq();
+                    if length_sq < 1.0 / (MAX_MITER_LENGTH * MAX_MITER_LENGTH) {
+                        normal.normalized() * MAX_MITER_LENGTH
+                    } else {
+                        normal / length_sq
+                    }
+                }
+                (Some(normal), None) | (None, Some(normal)) => normal,
+                (None, None) => Vec2::ZERO,
+            }
+        })
+        .collect()
+}
+
+fn fill_convex_path(mesh: &mut Mesh, points: &[Pos2], color: Color32, feathering: f32) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let normals = path_normals(points, true);
+
+    // The projected polygons may be wound either way, so the normals are
+    // flipped if they point towards the center of the polygon
+    let center = points
+        .iter()
+        .fold(Vec2::ZERO, |sum, point| sum + point.to_vec2())
+        / points.len() as f32;
+    let outwards = if (points[0].to_vec2() - center).dot(normals[0]) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+
+    let first = mesh.vertices.len() as u32;
+    for (point, normal) in points.iter().zip(&normals) {
+        let offset = *normal * outwards * feathering / 2.0;
+        mesh.add_vertex(*point - offset, color);
+        mesh.add_vertex(*point + offset, Color32::TRANSPARENT);
+    }
+
+    let count = points.len() as u32;
+    for i in 2..count {
+        mesh.add_triangle(first, first + 2 * (i - 1), first + 2 * i);
+    }
+    for i in 0..count {
+        let j = (i + 1) % count;
+        mesh.add_quad(
+            first + 2 * i,
+            first + 2 * i + 1,
+            first + 2 * j,
+            first + 2 * j + 1,
+        );
+    }
+}
+
+fn stroke_path(mesh: &mut Mesh, points: &[Pos2], closed: bool, stroke: Stroke, feathering: f32) {
+    if stroke.is_empty() || points.len() < 2 {
+        return;
+    }
+
+    let normals = path_normals(points, closed);
+    let first = mesh.vertices.len() as u32;
+    let count = points.len() as u32;
+
+    // Strokes thinner than the feathering are drawn as feathered hairlines with reduced opacity
+    let thin = stroke.width <= feathering;
+    let (color, inner_radius, outer_radius) = if thin {
+        (
+            stroke.color.gamma_multiply(stroke.width / feathering),
+            0.0,
+            feathering,
+        )
+    } else {
+        (
+            stroke.color,
+            (stroke.width - feathering) / 2.0,
+            (stroke.width + feathering) / 2.0,
+        )
+    };
+
+    // Vertices across the path at each point, from the transparent outer edge on one side
+    // to the transparent outer edge on the other side
+    let per_point = if thin { 3 } else { 4 };
+    for (point, normal) in points.iter().zip(&normals) {
+        mesh.add_vertex(*point - *normal * outer_radius, Color32::TRANSPARENT);
+        if thin {
+            mesh.add_vertex(*point, color);
+        } else {
+            mesh.add_vertex(*point - *normal * inner_radius, color);
+            mesh.add_vertex(*point + *normal * inner_radius, color);
+        }
+        mesh.add_vertex(*point + *normal * outer_radius, Color32::TRANSPARENT);
+    }
+
+    let segments = if closed { count } else { count - 1 };
+    for i in 0..segments {
+        let a = first + i * per_point;
+        let b = first + ((i + 1) % count) * per_point;
+        for lane in 0..per_point - 1 {
+            mesh.add_quad(a + lane, a + lane + 1, b + lane, b + lane + 1);
+        }
+    }
+
+    if !closed {
+        // Feather the ends of the path
+        let start_direction = (points[1] - points[0]).normalized();
+        let end_direction = (points[points.len() - 1] - points[points.len() - 2]).normalized();
+
+        add_end_cap(
+            mesh,
+            points[0],
+            normals[0],
+            -start_direction * feathering,
+            first,
+            per_point,
+            inner_radius,
+        );
+        add_end_cap(
+            mesh,
+            points[points.len() - 1],
+            normals[points.len() - 1],
+            end_direction * feathering,
+            first + (count - 1) * per_point,
+            per_point,
+            inner_radius,
+        );
+    }
+}
+
+/// Adds a transparent edge beyond the end of a stroke, `offset` away from the end point
+fn add_end_cap(
+    mesh: &mut Mesh,
+    point: Pos2,
+    normal: Vec2,
+    offset: Vec2,
+    first: u32,
+    per_point: u32,
+    inner_radius: f32,
+) {
+    let cap_start = mesh.add_vertex(point + offset - normal * inner_radius, Color32::TRANSPARENT);
+    let cap_end = mesh.add_vertex(point + offset + normal * inner_radius, Color32::TRANSPARENT);
+
+    let outer_start = first;
+    let inner_start = first + 1;
+    let inner_end = first + per_point - 2;
+    let outer_end = first + per_point - 1;
+
+    mesh.add_triangle(outer_start, cap_start, inner_start);
+    mesh.add_quad(cap_start, cap_end, inner_start, inner_end);
+    mesh.add_triangle(cap_end, outer_end, inner_end);
+}
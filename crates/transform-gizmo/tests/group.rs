@@ -0,0 +1,172 @@
+//! Tests for the arbitration between the gizmos of a [`GizmoGroup`].
+//!
+//! The camera is 10 units away from the origin with a 90 degree field of view
+//! and a square 800x800 viewport. The near gizmo is halfway between the camera
+//! and the origin, so that its handles are in front of the handles of a gizmo
+//! at the origin.
+
+use std::f64::consts::FRAC_PI_2;
+
+use transform_gizmo::math::{DMat4, DQuat, DVec3, Pos2, Rect, Transform, Vec2};
+use transform_gizmo::prelude::*;
+
+const VIEWPORT_SIZE: f32 = 800.0;
+
+const NEAR: u32 = 1;
+const FAR: u32 = 2;
+
+const NEAR_POSITION: DVec3 = DVec3::new(0.0, 0.0, 5.0);
+/// Behind the near gizmo, with overlapping handles
+const BEHIND_POSITION: DVec3 = DVec3::ZERO;
+/// Farther than the near gizmo, with separate handles
+const ASIDE_POSITION: DVec3 = DVec3::new(-4.0, 0.0, 0.0);
+
+fn config() -> GizmoConfig {
+    GizmoConfig {
+        view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y).into(),
+        projection_matrix: DMat4::perspective_rh_gl(FRAC_PI_2, 1.0, 0.1, 100.0).into(),
+        viewport: Rect::from_min_size(Pos2::ZERO, Vec2::splat(VIEWPORT_SIZE)),
+        modes: enum_set!(GizmoMode::TranslateX),
+        ..Default::default()
+    }
+}
+
+/// Screen position on the X axis arrow of a gizmo at given position
+fn arrow_pos(position: DVec3) -> Pos2 {
+    config().world_to_screen(position.into()).unwrap() + Vec2::new(50.0, 0.0)
+}
+
+fn interaction(pos: Pos2, drag_started: bool, dragging: bool) -> GizmoInteraction {
+    GizmoInteraction {
+        cursor_pos: (pos.x, pos.y),
+        drag_started,
+        dragging,
+        ..Default::default()
+    }
+}
+
+/// Updates the gizmos at given positions in the given order with the same interaction.
+///
+/// Returns the keys of the gizmos that produced a result.
+fn update(
+    group: &mut GizmoGroup<u32>,
+    gizmos: &[(u32, DVec3)],
+    interaction: GizmoInteraction,
+) -> Vec<u32> {
+    gizmos
+        .iter()
+        .filter_map(|&(key, position)| {
+            let targets = [Transform::from_scale_rotation_translation(
+                DVec3::ONE,
+                DQuat::IDENTITY,
+                position,
+            )];
+
+            group.update(key, interaction, &targets, &[]).map(|_| key)
+        })
+        .collect()
+}
+
+#[test]
+fn hovered_gizmo_is_closest_to_camera() {
+    let overlapping = [(FAR, BEHIND_POSITION), (NEAR, NEAR_POSITION)];
+    let pos = arrow_pos(NEAR_POSITION);
+
+    // The order of the updates does not matter
+    for gizmos in [overlapping, [overlapping[1], overlapping[0]]] {
+        let mut group = GizmoGroup::new(config());
+
+        update(&mut group, &gizmos, interaction(pos, false, false));
+
+        assert!(group.get(FAR).unwrap().focused_distance().is_some());
+        assert_eq!(group.hovered(), Some(NEAR));
+    }
+}
+
+#[test]
+fn occluded_gizmo_does_not_start_drag() {
+    let mut group = GizmoGroup::new(config());
+    // The occluded gizmo is updated first, before the near gizmo is picked in this frame
+    let gizmos = [(FAR, BEHIND_POSITION), (NEAR, NEAR_POSITION)];
+    let pos = arrow_pos(NEAR_POSITION);
+
+    update(&mut group, &gizmos, interaction(pos, false, false));
+    update(&mut group, &gizmos, interaction(pos, true, true));
+
+    assert_eq!(group.active(), Some(NEAR));
+    assert!(group.get(FAR).unwrap().active_mode().is_none());
+}
+
+#[test]
+fn active_gizmo_blocks_others() {
+    let mut group = GizmoGroup::new(config());
+    let gizmos = [(NEAR, NEAR_POSITION), (FAR, ASIDE_POSITION)];
+    let pos = arrow_pos(NEAR_POSITION);
+
+    update(&mut group, &gizmos, interaction(pos, false, false));
+    update(&mut group, &gizmos, interaction(pos, true, true));
+
+    // Starting a drag on the handle of the other gizmo is ignored during the drag
+    let aside_pos = arrow_pos(ASIDE_POSITION);
+    let dragged = update(&mut group, &gizmos, interaction(aside_pos, true, true));
+
+    assert_eq!(dragged, [NEAR]);
+    assert_eq!(group.active(), Some(NEAR));
+    assert_eq!(group.hovered(), Some(NEAR));
+
+    // The other gizmo can be dragged after the drag ends
+    update(&mut group, &gizmos, interaction(aside_pos, false, false));
+    update(&mut group, &gizmos, interaction(aside_pos, true, true));
+
+    assert_eq!(group.active(), Some(FAR));
+}
+
+#[test]
+fn draw_order_is_back_to_front_with_hovered_last() {
+    let mut group = GizmoGroup::new(config());
+    // Added from the closest to the farthest
+    let gizmos = [(NEAR, NEAR_POSITION), (FAR, ASIDE_POSITION)];
+
+    update(&mut group, &gizmos, interaction(Pos2::ZERO, false, false));
+
+    assert_eq!(group.hovered(), None);
+    assert_eq!(group.draw_order().collect::<Vec<_>>(), [FAR, NEAR]);
+
+    update(
+        &mut group,
+        &gizmos,
+        interaction(arrow_pos(ASIDE_POSITION), false, false),
+    );
+
+    assert_eq!(group.hovered(), Some(FAR));
+    assert_eq!(group.draw_order().collect::<Vec<_>>(), [NEAR, FAR]);
+}
+
+#[test]
+fn removing_active_gizmo_clears_active() {
+    let mut group = GizmoGroup::new(config());
+    let gizmos = [(FAR, BEHIND_POSITION), (NEAR, NEAR_POSITION)];
+    let pos = arrow_pos(NEAR_POSITION);
+
+    update(&mut group, &gizmos, interaction(pos, false, false));
+    update(&mut group, &gizmos, interaction(pos, true, true));
+    assert_eq!(group.active(), Some(NEAR));
+
+    group.retain(|key| *key != NEAR);
+
+    assert_eq!(group.active(), None);
+    assert_eq!(group.len(), 1);
+
+    // The remaining gizmo is no longer occluded
+    let gizmos = [(FAR, BEHIND_POSITION)];
+    update(&mut group, &gizmos, interaction(pos, false, false));
+    update(&mut group, &gizmos, interaction(pos, true, true));
+
+    assert_eq!(group.active(), Some(FAR));
+
+    assert!(group.remove(FAR).is_some());
+
+    assert_eq!(group.active(), None);
+    assert_eq!(group.hovered(), None);
+    assert!(group.is_empty());
+}
@@ -0,0 +1,1552 @@
+//! End-to-end tests that drag the gizmo along known screen space paths
+//! and compare the resulting transforms against analytically computed values.
+//!
+//! The camera is 10 units away from the origin with a 90 degree field of view
+//! and a square 800x800 viewport, so one pixel at the origin is 0.025 world units.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+use std::time::Duration;
+
+use transform_gizmo::config::{
+    ApplyMode, GizmoModeKind, GlobalScalePolicy, LocalRotationAxes, SnapOrigin, TransformChannel,
+    TransformLimit, TransformPivotPoint, TranslationConstraint,
+};
+use transform_gizmo::math::{Aabb, DMat4, DQuat, DVec3, Pos2, Rect, Transform, Transform2d, Vec2};
+use transform_gizmo::prelude::*;
+
+const VIEWPORT_SIZE: f32 = 800.0;
+const WORLD_UNITS_PER_PIXEL: f64 = 0.025;
+const EPSILON: f64 = 1e-3;
+
+fn config(modes: EnumSet<GizmoMode>) -> GizmoConfig {
+    GizmoConfig {
+        view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 0.0, 10.0), DVec3::ZERO, DVec3::Y).into(),
+        projection_matrix: DMat4::perspective_rh_gl(FRAC_PI_2, 1.0, 0.1, 100.0).into(),
+        viewport: Rect::from_min_size(Pos2::ZERO, Vec2::splat(VIEWPORT_SIZE)),
+        modes,
+        ..Default::default()
+    }
+}
+
+/// Screen position of the gizmo center for given targets
+fn gizmo_center(config: &GizmoConfig, targets: &[Transform]) -> Pos2 {
+    let center = targets
+        .iter()
+        .map(|target| DVec3::from(target.translation))
+        .sum::<DVec3>()
+        / targets.len() as f64;
+
+    config.world_to_screen(center.into()).unwrap()
+}
+
+/// Drags the cursor along the path, starting the drag at the first point
+/// and releasing it at the last. The targets are updated after each step.
+///
+/// Returns the last result produced by the gizmo, if any.
+fn drag(gizmo: &mut Gizmo, path: &[Pos2], targets: &mut Vec<Transform>) -> Option<GizmoResult> {
+    let mut last_result = None;
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((result, new_targets)) = gizmo.update(interaction, targets) {
+            *targets = new_targets;
+            last_result = Some(result);
+        }
+    }
+
+    if let Some(pos) = path.last() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: false,
+            dragging: false,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        assert!(gizmo.update(interaction, targets).is_none());
+    }
+
+    last_result
+}
+
+/// Straight path from `start` to `end` pixels from the center, in given number of steps
+fn line(center: Pos2, start: Vec2, end: Vec2, steps: usize) -> Vec<Pos2> {
+    (0..=steps)
+        .map(|i| center + start + (end - start) * (i as f32 / steps as f32))
+        .collect()
+}
+
+/// Counterclockwise path along a circle around the center, in given number of steps
+fn arc(center: Pos2, radius: f32, end_angle: f32, steps: usize) -> Vec<Pos2> {
+    (0..=steps)
+        .map(|i| {
+            let angle = end_angle * i as f32 / steps as f32;
+            // Screen space y axis points down
+            center + Vec2::new(angle.cos(), -angle.sin()) * radius
+        })
+        .collect()
+}
+
+fn assert_vec3_eq(actual: impl Into<DVec3>, expected: DVec3) {
+    let actual = actual.into();
+    assert!(
+        actual.abs_diff_eq(expected, EPSILON),
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn translate_with_absolute_snapping() {
+    let config = GizmoConfig {
+        snapping: true,
+        snap_distance: 1.0,
+        translation_snap_origin: SnapOrigin::Absolute,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::IDENTITY,
+        DVec3::X * 0.3,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    // Relative snapping would end up at 1.3
+    assert_vec3_eq(targets[0].translation, DVec3::X);
+}
+
+#[test]
+fn translate_with_offset_snap_origin() {
+    let config = GizmoConfig {
+        snapping: true,
+        snap_distance: 1.0,
+        translation_snap_origin: SnapOrigin::Absolute,
+        snap_origin: (DVec3::X * 0.25).into(),
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::IDENTITY,
+        DVec3::X * 0.3,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    // The lattice is offset by the origin, so the target snaps to 1.25 instead of 1
+    assert_vec3_eq(targets[0].translation, DVec3::X * 1.25);
+}
+
+#[test]
+fn translate_with_per_axis_snap_distance() {
+    let config = GizmoConfig {
+        snapping: true,
+        snap_distance: 1.0,
+        snap_distance_per_axis: [None, Some(0.5), None],
+        ..config(enum_set!(GizmoMode::TranslateY))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(0.0, -50.0), Vec2::new(0.0, -74.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    // 0.6 units would snap to 1.0 with the shared snapping distance
+    assert_vec3_eq(targets[0].translation, DVec3::Y * 0.5);
+}
+
+#[test]
+fn translate_in_bounds_size_increments() {
+    let config = GizmoConfig {
+        snapping: true,
+        snap_distance: 0.1,
+        snap_mode: SnapMode::BoundsSize,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+    let bounds = [Some(Aabb::new([-1.0, -0.25, -0.25], [1.0, 0.25, 0.25]))];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(110.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            ..Default::default()
+        };
+
+        if let Some((_, new_targets)) = gizmo.update_with_bounds(interaction, &targets, &bounds) {
+            targets = new_targets;
+        }
+    }
+
+    // Dragged 1.5 units, snapped to the 2 unit width of the bounds
+    assert_vec3_eq(targets[0].translation, DVec3::X * 2.0);
+}
+
+#[test]
+fn translate_stops_at_limit() {
+    let config = GizmoConfig {
+        translation_limits: [Some(TransformLimit::new(-1.0, 1.0)), None, None],
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    // 100 pixels to the right
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(150.0, 0.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(targets[0].translation, DVec3::X);
+}
+
+#[test]
+fn translate_ignores_reversed_limit() {
+    let config = GizmoConfig {
+        translation_limits: [Some(TransformLimit::new(1.0, -1.0)), None, None],
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(150.0, 0.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::X * 100.0 * WORLD_UNITS_PER_PIXEL,
+    );
+}
+
+#[test]
+fn translate_along_global_axis() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert_eq!(
+        result.map(|result| result.kind()),
+        Some(GizmoModeKind::Translate)
+    );
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL,
+    );
+}
+
+#[test]
+fn translate_in_place() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::Y),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::Y),
+    ];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        gizmo.update_in_place(interaction, &mut targets);
+    }
+
+    let offset = DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL;
+    assert_vec3_eq(targets[0].translation, offset - DVec3::Y);
+    assert_vec3_eq(targets[1].translation, offset + DVec3::Y);
+}
+
+#[test]
+fn translate_in_place_with_locked_channels() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::Y),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::Y),
+    ];
+    let locked_channels = [enum_set!(TransformChannel::TranslateX)];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        gizmo.update_in_place_with_params(
+            interaction,
+            &mut targets,
+            UpdateParams {
+                locked_channels: &locked_channels,
+                ..Default::default()
+            },
+        );
+    }
+
+    // Only the first target is locked
+    let offset = DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL;
+    assert_vec3_eq(targets[0].translation, -DVec3::Y);
+    assert_vec3_eq(targets[1].translation, offset + DVec3::Y);
+}
+
+#[test]
+fn translate_along_local_axis() {
+    let config = GizmoConfig {
+        orientation: GizmoOrientation::Local,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+
+    // Local X axis points along global Y axis
+    let rotation = DQuat::from_rotation_z(FRAC_PI_2);
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        rotation,
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(0.0, -50.0), Vec2::new(0.0, -90.0), 4);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::Y * 40.0 * WORLD_UNITS_PER_PIXEL,
+    );
+    assert_vec3_eq(
+        DQuat::from(targets[0].rotation) * DVec3::X,
+        rotation * DVec3::X,
+    );
+}
+
+#[test]
+fn rotate_around_median_point() {
+    let config = GizmoConfig {
+        pivot_point: TransformPivotPoint::MedianPoint,
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::X),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::X),
+    ];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    let path = arc(center, radius, std::f32::consts::FRAC_PI_2, 9);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert_eq!(
+        result.map(|result| result.kind()),
+        Some(GizmoModeKind::Rotate)
+    );
+    if let Some(GizmoResult::Rotation { pivot, .. }) = result {
+        assert_vec3_eq(pivot.expect("median point pivot"), DVec3::ZERO);
+    }
+
+    // Quarter turn counterclockwise around the Z axis
+    assert_vec3_eq(targets[0].translation, DVec3::Y);
+    assert_vec3_eq(targets[1].translation, -DVec3::Y);
+
+    for target in &targets {
+        assert_vec3_eq(DQuat::from(target.rotation) * DVec3::X, DVec3::Y);
+    }
+}
+
+#[test]
+fn locked_rotation_does_not_orbit_median_point() {
+    let config = GizmoConfig {
+        pivot_point: TransformPivotPoint::MedianPoint,
+        ..config(enum_set!(GizmoMode::Arcball))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::X),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::X),
+    ];
+    let locked_channels = [enum_set!(TransformChannel::RotateY); 2];
+
+    // Diagonal drag rotates around both the X and the Y axis
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::ZERO, Vec2::new(40.0, 40.0), 8);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((_, new_targets)) =
+            gizmo.update_with_locked_channels(interaction, &targets, &locked_channels)
+        {
+            targets = new_targets;
+        }
+    }
+
+    // Only the rotation around the X axis is left, which keeps the targets in place
+    for target in &targets {
+        let rotation = DQuat::from(target.rotation).to_scaled_axis();
+        assert!(
+            rotation.x.abs() > EPSILON,
+            "expected a rotation, got {rotation}"
+        );
+        assert!(
+            rotation.y.abs() < EPSILON,
+            "expected no rotation around Y, got {rotation}"
+        );
+    }
+    assert_vec3_eq(targets[0].translation, DVec3::X);
+    assert_vec3_eq(targets[1].translation, -DVec3::X);
+}
+
+#[test]
+fn rotation_counts_full_revolutions() {
+    let config = config(enum_set!(GizmoMode::RotateZ));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // One and a quarter turns counterclockwise
+    let path = arc(center, radius, 2.5 * std::f32::consts::PI, 25);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    // The total angle is negated, and so are the revolutions
+    assert!(matches!(
+        result,
+        Some(GizmoResult::Rotation {
+            revolutions: -1,
+            ..
+        })
+    ));
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::Y);
+}
+
+#[test]
+fn rotation_stops_at_limit() {
+    let config = GizmoConfig {
+        rotation_limits: [None, None, Some(TransformLimit::new(-FRAC_PI_4, FRAC_PI_4))],
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // Half a turn counterclockwise
+    let path = arc(center, radius, std::f32::consts::PI, 10);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(
+        DQuat::from(targets[0].rotation) * DVec3::X,
+        DQuat::from_rotation_z(FRAC_PI_4) * DVec3::X,
+    );
+}
+
+#[test]
+fn rotation_ignores_reversed_limit() {
+    let config = GizmoConfig {
+        rotation_limits: [None, None, Some(TransformLimit::new(FRAC_PI_4, -FRAC_PI_4))],
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // A quarter turn counterclockwise
+    let path = arc(center, radius, std::f32::consts::FRAC_PI_2, 10);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::Y);
+}
+
+#[test]
+fn rotation_swiped_through_center_does_not_flip() {
+    let config = config(enum_set!(GizmoMode::RotateZ));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // Straight across the gizmo, from one side of the ring to the other
+    let path = line(center, Vec2::new(radius, 0.0), Vec2::new(-radius, 0.0), 11);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    let Some(GizmoResult::Rotation { total, .. }) = result else {
+        panic!("expected a rotation, got {result:?}");
+    };
+    assert!(total.abs() < EPSILON, "expected no rotation, got {total}");
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::X);
+}
+
+#[test]
+fn rotation_ignores_cursor_in_center_dead_zone() {
+    let config = config(enum_set!(GizmoMode::RotateZ));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // Grab the ring, move to the center and circle around it within the dead zone
+    let mut path = line(center, Vec2::new(radius, 0.0), Vec2::ZERO, 5);
+    path.extend(arc(center, 3.0, std::f32::consts::TAU, 12));
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    let Some(GizmoResult::Rotation { total, .. }) = result else {
+        panic!("expected a rotation, got {result:?}");
+    };
+    assert!(total.abs() < EPSILON, "expected no rotation, got {total}");
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::X);
+}
+
+#[test]
+fn rotation_accumulates_full_turn_without_wrapping() {
+    let config = config(enum_set!(GizmoMode::RotateZ));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    let path = arc(center, radius, std::f32::consts::TAU, 24);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    let Some(GizmoResult::Rotation { total, .. }) = result else {
+        panic!("expected a rotation, got {result:?}");
+    };
+    // The total angle is negated
+    assert!(
+        (total + std::f64::consts::TAU).abs() < EPSILON,
+        "expected a total of -2π, got {total}"
+    );
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::X);
+}
+
+#[test]
+fn rotation_euler_angles_continue_past_half_turn() {
+    let config = config(enum_set!(GizmoMode::RotateZ));
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // One and a quarter turns counterclockwise
+    let path = arc(center, radius, 2.5 * std::f32::consts::PI, 25);
+
+    let mut angles = DVec3::ZERO;
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((result, _)) = gizmo.update(interaction, &targets) {
+            angles = result
+                .euler_near(EulerOrder::XYZ, angles.into())
+                .unwrap()
+                .into();
+        }
+    }
+
+    assert_vec3_eq(angles, DVec3::Z * 2.5 * std::f64::consts::PI);
+}
+
+#[test]
+fn rotate_2d_transform_past_half_turn() {
+    let config = config(GizmoMode::preset_2d());
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform2d::new((1.0, 0.0), 0.0, (2.0, 1.0))];
+
+    let center = config.world_to_screen(DVec3::X.into()).unwrap();
+    let radius = config.visuals.gizmo_size;
+    // One and a quarter turns counterclockwise
+    let path = arc(center, radius, 2.5 * std::f32::consts::PI, 25);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((_, new_targets)) = gizmo.update_2d(interaction, &targets) {
+            targets = new_targets;
+        }
+    }
+
+    let target = targets[0];
+    assert!(
+        (target.rotation - 2.5 * std::f64::consts::PI).abs() < EPSILON,
+        "expected a rotation of 2.5π, got {}",
+        target.rotation
+    );
+    assert_vec3_eq(Transform::from(target).translation, DVec3::X);
+    assert_vec3_eq(Transform::from(target).scale, DVec3::new(2.0, 1.0, 1.0));
+}
+
+#[test]
+fn rotation_snaps_to_world_alignment() {
+    let config = GizmoConfig {
+        alignment_snap_angle: Some(10f32.to_radians()),
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::IDENTITY,
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    // A few degrees short of a quarter turn
+    let path = arc(center, radius, 85f32.to_radians(), 9);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert!(matches!(
+        result,
+        Some(GizmoResult::Rotation {
+            snapped_to: Some(ReferenceId::World),
+            ..
+        })
+    ));
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::X, DVec3::Y);
+}
+
+#[test]
+fn rotate_around_individual_origins() {
+    let config = GizmoConfig {
+        pivot_point: TransformPivotPoint::IndividualOrigins,
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::X),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::X),
+    ];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    let path = arc(center, radius, std::f32::consts::FRAC_PI_2, 9);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert!(matches!(
+        result,
+        Some(GizmoResult::Rotation { pivot: None, .. })
+    ));
+    assert_vec3_eq(targets[0].translation, DVec3::X);
+    assert_vec3_eq(targets[1].translation, -DVec3::X);
+
+    for target in &targets {
+        assert_vec3_eq(DQuat::from(target.rotation) * DVec3::X, DVec3::Y);
+    }
+}
+
+#[test]
+fn rotate_around_gizmo_local_axis() {
+    let config = GizmoConfig {
+        orientation: GizmoOrientation::Local,
+        pivot_point: TransformPivotPoint::IndividualOrigins,
+        local_rotation_axes: LocalRotationAxes::Gizmo,
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    // The gizmo is oriented like the last target
+    let mut targets = vec![
+        Transform::from_scale_rotation_translation(
+            DVec3::ONE,
+            DQuat::from_rotation_x(FRAC_PI_2),
+            DVec3::X,
+        ),
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::X),
+    ];
+
+    let center = gizmo_center(&config, &targets);
+    let radius = config.visuals.gizmo_size;
+    let path = arc(center, radius, std::f32::consts::FRAC_PI_2, 9);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    // Both targets are rotated around the Z axis of the gizmo,
+    // instead of the first one rotating around its own Z axis
+    for target in &targets {
+        assert_vec3_eq(DQuat::from(target.rotation) * DVec3::X, DVec3::Y);
+    }
+}
+
+#[test]
+fn handle_ids_are_stable_and_unique() {
+    let config = config(enum_set!(GizmoMode::Arcball | GizmoMode::Direction));
+    let gizmo = Gizmo::new(config);
+
+    let arcball = gizmo.handle(GizmoMode::Arcball).unwrap();
+    let direction = gizmo.handle(GizmoMode::Direction).unwrap();
+
+    // Handles without parameters are told apart by their kind
+    assert_ne!(arcball.id, direction.id);
+    assert_eq!(Gizmo::new(config).handle(GizmoMode::Arcball), Some(arcball));
+}
+
+#[test]
+fn drag_direction_towards_camera() {
+    let config = config(enum_set!(GizmoMode::Direction | GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    // The direction is the local Z axis, pointing along the global X axis here
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::from_rotation_y(FRAC_PI_2),
+        DVec3::ZERO,
+    )];
+
+    // Translation handles are not used with directions
+    assert!(gizmo.handle(GizmoMode::TranslateX).is_none());
+
+    // Grab the arrow halfway and drag to the gizmo center, which is in front of the camera
+    let center = gizmo_center(&config, &targets);
+    let path = line(
+        center,
+        Vec2::new(config.visuals.gizmo_size * 0.5, 0.0),
+        Vec2::ZERO,
+        10,
+    );
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    let Some(GizmoResult::Direction { direction, .. }) = result else {
+        panic!("expected a direction result, got {result:?}");
+    };
+    assert_vec3_eq(direction, DVec3::Z);
+    assert_vec3_eq(DQuat::from(targets[0].rotation) * DVec3::Z, DVec3::Z);
+    assert_vec3_eq(targets[0].translation, DVec3::ZERO);
+}
+
+#[test]
+fn scale_along_axis() {
+    let config = config(enum_set!(GizmoMode::ScaleX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+
+    // Scale is the ratio of the cursor distances from the gizmo center
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(100.0, 0.0), 5);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert_eq!(
+        result.map(|result| result.kind()),
+        Some(GizmoModeKind::Scale)
+    );
+    if let Some(GizmoResult::Scale { delta, total }) = result {
+        // The last step moves the cursor from 90 to 100 pixels
+        assert_vec3_eq(delta, DVec3::new(100.0 / 90.0, 1.0, 1.0));
+        assert_vec3_eq(total, DVec3::new(2.0, 1.0, 1.0));
+    }
+    assert_vec3_eq(targets[0].scale, DVec3::new(2.0, 1.0, 1.0));
+    assert_vec3_eq(targets[0].translation, DVec3::ZERO);
+}
+
+#[test]
+fn scale_along_axis_flipped_toward_camera() {
+    let mut config = GizmoConfig {
+        orientation: GizmoOrientation::Local,
+        ..config(enum_set!(GizmoMode::ScaleX))
+    };
+    config.visuals.flip_axes_toward_camera = true;
+    let mut gizmo = Gizmo::new(config);
+
+    // Local X axis points away from the camera, so its arrow is flipped to the left
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::from_rotation_y(FRAC_PI_2 / 3.0),
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(-40.0, 0.0), Vec2::new(-60.0, 0.0), 4);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    assert_eq!(
+        result.map(|result| result.kind()),
+        Some(GizmoModeKind::Scale)
+    );
+    assert!(targets[0].scale.x > 1.0);
+    assert_vec3_eq(targets[0].translation, DVec3::ZERO);
+}
+
+/// Config for scaling along the global X axis, viewed from above so that
+/// both the global X axis and the global Z axis are visible on screen
+fn global_scale_config(global_scale_policy: GlobalScalePolicy) -> GizmoConfig {
+    GizmoConfig {
+        // Global X axis points right and global Z axis points down on screen
+        view_matrix: DMat4::look_at_rh(DVec3::new(0.0, 10.0, 0.0), DVec3::ZERO, DVec3::NEG_Z)
+            .into(),
+        orientation: GizmoOrientation::Global,
+        global_scale_policy,
+        ..config(enum_set!(GizmoMode::ScaleX))
+    }
+}
+
+#[test]
+fn global_scale_maps_to_closest_local_axis() {
+    let config = global_scale_config(GlobalScalePolicy::ClosestAxis);
+    let mut gizmo = Gizmo::new(config);
+    // Local Z axis points along the global X axis
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::from_rotation_y(FRAC_PI_2),
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(100.0, 0.0), 5);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(targets[0].scale, DVec3::new(1.0, 1.0, 2.0));
+}
+
+#[test]
+fn global_scale_forced_to_local_axis() {
+    let config = global_scale_config(GlobalScalePolicy::ForceLocal);
+    let mut gizmo = Gizmo::new(config);
+    // Local X axis points along the negative global Z axis, which is up on screen
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::from_rotation_y(FRAC_PI_2),
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(0.0, -50.0), Vec2::new(0.0, -100.0), 5);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    assert_vec3_eq(targets[0].scale, DVec3::new(2.0, 1.0, 1.0));
+}
+
+#[test]
+fn scale_zero_scale_axis_additively() {
+    let config = config(enum_set!(GizmoMode::ScaleX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::from_scale_rotation_translation(
+        DVec3::new(0.0, 1.0, 1.0),
+        DQuat::IDENTITY,
+        DVec3::ZERO,
+    )];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(100.0, 0.0), 5);
+
+    drag(&mut gizmo, &path, &mut targets);
+
+    // Doubling the scale adds one to the flattened axis
+    assert_vec3_eq(targets[0].scale, DVec3::new(1.0, 1.0, 1.0));
+}
+
+#[test]
+fn interaction_points_follow_the_grab_point() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((_, new_targets)) = gizmo.update(interaction, &targets) {
+            targets = new_targets;
+        }
+    }
+
+    let (start, current) = gizmo.interaction_points().unwrap();
+    assert_vec3_eq(start, DVec3::X * 50.0 * WORLD_UNITS_PER_PIXEL);
+    assert_vec3_eq(current, DVec3::X * 90.0 * WORLD_UNITS_PER_PIXEL);
+
+    let last = path.last().unwrap();
+    gizmo.update(
+        GizmoInteraction {
+            cursor_pos: (last.x, last.y),
+            ..Default::default()
+        },
+        &targets,
+    );
+    assert!(gizmo.interaction_points().is_none());
+}
+
+#[test]
+fn programmatic_interaction() {
+    let config = config(enum_set!(GizmoMode::TranslateX | GizmoMode::TranslateY));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let handle = gizmo.handle(GizmoMode::TranslateY).unwrap();
+    assert!(gizmo.handle(GizmoMode::RotateX).is_none());
+
+    // Far away from the handle, which does not matter when the drag is started explicitly
+    let start = Pos2::new(10.0, 10.0);
+    assert!(gizmo.begin_interaction(handle, start));
+
+    let path = line(start, Vec2::ZERO, Vec2::new(0.0, -80.0), 4);
+    let mut last_result = None;
+    for pos in &path {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            dragging: true,
+            ..Default::default()
+        };
+
+        if let Some((result, new_targets)) = gizmo.update(interaction, &targets) {
+            targets = new_targets;
+            last_result = Some(result);
+        }
+    }
+
+    assert_eq!(gizmo.active_handle(), Some(handle));
+    assert!(matches!(last_result, Some(GizmoResult::Translation { .. })));
+    assert!(DVec3::from(targets[0].translation).y > 0.0);
+    assert!(DVec3::from(targets[0].translation).x.abs() < EPSILON);
+
+    assert!(gizmo.end_interaction().is_none());
+    assert!(gizmo.active_handle().is_none());
+}
+
+#[test]
+fn pick_does_not_change_state() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let on_arrow = center + Vec2::new(50.0, 0.0);
+
+    assert_eq!(
+        gizmo.pick((on_arrow.x, on_arrow.y)),
+        gizmo.handle(GizmoMode::TranslateX)
+    );
+    assert!(gizmo.pick((center.x, center.y - 100.0)).is_none());
+    assert!(gizmo.focused_mode().is_none());
+
+    gizmo.update(
+        GizmoInteraction {
+            cursor_pos: (on_arrow.x, on_arrow.y),
+            ..Default::default()
+        },
+        &targets,
+    );
+    assert_eq!(gizmo.focused_mode(), Some(GizmoMode::TranslateX));
+}
+
+#[test]
+fn pick_in_letterboxed_display_rect() {
+    // The square viewport is displayed at half size in a wide area, with bars at the sides
+    let area = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 400.0));
+    let config = GizmoConfig {
+        display_rect: Some(GizmoConfig::letterboxed_display_rect(
+            Vec2::splat(VIEWPORT_SIZE),
+            area,
+        )),
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    assert_eq!(
+        config.display_rect,
+        Some(Rect::from_min_size(
+            Pos2::new(200.0, 0.0),
+            Vec2::splat(400.0)
+        ))
+    );
+    assert_eq!(config.display_scale(), 0.5);
+
+    let on_arrow =
+        config.viewport_to_display(gizmo_center(&config, &targets) + Vec2::new(50.0, 0.0));
+    assert_eq!(on_arrow, Pos2::new(425.0, 200.0));
+    assert_eq!(
+        gizmo.pick((on_arrow.x, on_arrow.y)),
+        gizmo.handle(GizmoMode::TranslateX)
+    );
+}
+
+#[test]
+fn pick_candidates_include_unpicked_handles() {
+    let config = config(enum_set!(GizmoMode::TranslateX | GizmoMode::TranslateY));
+    let gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let on_arrow = gizmo_center(&config, &targets) + Vec2::new(50.0, 0.0);
+    let candidates = gizmo.pick_candidates((on_arrow.x, on_arrow.y));
+
+    let candidate = |mode| {
+        candidates
+            .iter()
+            .find(|candidate| candidate.handle.mode == mode)
+            .copied()
+            .unwrap()
+    };
+
+    let x = candidate(GizmoMode::TranslateX);
+    assert!(x.picked && x.chosen);
+    assert_vec3_eq(x.point.unwrap(), DVec3::X * 50.0 * WORLD_UNITS_PER_PIXEL);
+
+    let y = candidate(GizmoMode::TranslateY);
+    assert!(!y.picked && !y.chosen);
+    assert!(y.t.is_some());
+
+    assert!(!gizmo.draw_pick_candidates(&candidates).vertices.is_empty());
+}
+
+#[test]
+fn handle_usability_of_axis_facing_camera() {
+    let gizmo = Gizmo::new(config(enum_set!(
+        GizmoMode::TranslateX | GizmoMode::TranslateZ
+    )));
+
+    // The camera looks along the Z axis, so its arrow is faded out
+    assert_eq!(
+        gizmo.handle_usability(GizmoMode::TranslateX),
+        HandleUsability::Visible
+    );
+    assert_eq!(
+        gizmo.handle_usability(GizmoMode::TranslateZ),
+        HandleUsability::FadedOut
+    );
+    assert_eq!(
+        gizmo.handle_usability(GizmoMode::TranslateY),
+        HandleUsability::Disabled
+    );
+}
+
+#[test]
+fn wheel_over_axis_translates_by_snap_distance() {
+    let mut config = config(enum_set!(GizmoMode::TranslateX));
+    config.snap_distance = 0.5;
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let on_arrow = gizmo_center(&config, &targets) + Vec2::new(50.0, 0.0);
+    let interaction = GizmoInteraction {
+        cursor_pos: (on_arrow.x, on_arrow.y),
+        wheel_delta: 2.0,
+        ..Default::default()
+    };
+
+    // Disabled by default
+    assert!(gizmo.update(interaction, &targets).is_none());
+
+    gizmo.update_config(GizmoConfig {
+        wheel_interaction: true,
+        ..config
+    });
+
+    let (result, new_targets) = gizmo.update(interaction, &targets).unwrap();
+    targets = new_targets;
+
+    assert!(matches!(result, GizmoResult::Translation { .. }));
+    assert_vec3_eq(targets[0].translation, DVec3::X);
+}
+
+#[test]
+fn wheel_over_ring_stops_at_rotation_limit() {
+    let config = GizmoConfig {
+        wheel_interaction: true,
+        rotation_limits: [None, None, Some(TransformLimit::new(-FRAC_PI_4, FRAC_PI_4))],
+        ..config(enum_set!(GizmoMode::RotateZ))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let on_ring = gizmo_center(&config, &targets) + Vec2::new(config.visuals.gizmo_size, 0.0);
+    let interaction = GizmoInteraction {
+        cursor_pos: (on_ring.x, on_ring.y),
+        wheel_delta: 10.0,
+        ..Default::default()
+    };
+
+    let (result, targets) = gizmo.update(interaction, &targets).unwrap();
+
+    let GizmoResult::Rotation { total, .. } = result else {
+        panic!("expected a rotation, got {result:?}");
+    };
+    assert!(
+        (total.abs() - FRAC_PI_4).abs() < EPSILON,
+        "expected a rotation by the limit, got {total}"
+    );
+    assert_vec3_eq(
+        DQuat::from(targets[0].rotation) * DVec3::X,
+        DQuat::from_rotation_z(-total) * DVec3::X,
+    );
+}
+
+#[test]
+fn events_report_drag_start_and_snap_ticks() {
+    let config = GizmoConfig {
+        snapping: true,
+        snap_distance: 1.0,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let handle = gizmo.handle(GizmoMode::TranslateX).unwrap();
+
+    // 210 pixels is 5.25 world units, crossing five snapping increments
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(260.0, 0.0), 20);
+    let mut events = vec![];
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            ..Default::default()
+        };
+
+        if let Some((_, new_targets)) = gizmo.update(interaction, &targets) {
+            targets = new_targets;
+        }
+        events.extend_from_slice(gizmo.events());
+    }
+
+    assert_eq!(
+        events[0],
+        GizmoEvent::DragStarted {
+            handle,
+            duplicate_requested: false
+        }
+    );
+    assert_eq!(events[1], GizmoEvent::FocusChanged(Some(handle)));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| **event == GizmoEvent::SnapTick(handle))
+            .count(),
+        5
+    );
+    assert_vec3_eq(targets[0].translation, DVec3::X * 5.0);
+}
+
+#[test]
+fn drag_continues_on_rebased_targets() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let handle = gizmo.handle(GizmoMode::TranslateX).unwrap();
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            duplicate: true,
+            ..Default::default()
+        };
+
+        if let Some((_, new_targets)) = gizmo.update(interaction, &targets) {
+            targets = new_targets;
+        }
+
+        let duplicate_requested = gizmo.events().contains(&GizmoEvent::DragStarted {
+            handle,
+            duplicate_requested: true,
+        });
+        assert_eq!(duplicate_requested, i == 0);
+
+        if duplicate_requested {
+            // The original stays where it was, and the drag continues on its duplicate
+            let duplicate =
+                Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::Y);
+            gizmo.rebase_targets(&[duplicate]);
+            targets = vec![duplicate];
+        }
+    }
+
+    assert_vec3_eq(targets[0].translation, DVec3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn rebased_deferred_result_keeps_locked_channels() {
+    let config = GizmoConfig {
+        apply_mode: ApplyMode::OnRelease,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+    let locked_channels = [enum_set!(TransformChannel::TranslateX)];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            ..Default::default()
+        };
+
+        assert!(gizmo
+            .update_with_locked_channels(interaction, &targets, &locked_channels)
+            .is_none());
+    }
+
+    let duplicate =
+        Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::Y);
+    gizmo.rebase_targets(&[duplicate]);
+
+    // The locked channel is not moved on the rebased target either
+    assert_vec3_eq(gizmo.preview_transforms().unwrap()[0].translation, DVec3::Y);
+}
+
+#[test]
+fn unchanged_drag_keeps_its_result() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    let mut last_output = None;
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        last_output = gizmo.update_if_changed(config, interaction, &targets);
+    }
+
+    // The cursor stays still, so the inputs are unchanged
+    let pos = path.last().unwrap();
+    let interaction = GizmoInteraction {
+        cursor_pos: (pos.x, pos.y),
+        drag_started: false,
+        dragging: true,
+        drag_anywhere: false,
+        wheel_delta: 0.0,
+        duplicate: false,
+    };
+    let output = gizmo.update_if_changed(config, interaction, &targets);
+
+    let Some((result, transforms)) = output else {
+        panic!("expected the result of the drag");
+    };
+    assert_eq!(result.kind(), GizmoModeKind::Translate);
+    assert_eq!(
+        Some(transforms),
+        last_output.map(|(_, transforms)| transforms)
+    );
+}
+
+#[test]
+fn translate_along_path_constraint() {
+    let config = config(enum_set!(GizmoMode::TranslateView));
+    let mut gizmo = Gizmo::new(config);
+    gizmo.set_translation_constraint(Some(TranslationConstraint::Path(vec![
+        DVec3::ZERO.into(),
+        DVec3::new(2.0, 0.0, 0.0).into(),
+        DVec3::new(2.0, 2.0, 0.0).into(),
+    ])));
+    let mut targets = vec![Transform::default()];
+
+    // Towards the middle of the second segment, at (2, 1, 0)
+    let center = gizmo_center(&config, &targets);
+    let end = Vec2::new(2.0, -1.0) / WORLD_UNITS_PER_PIXEL as f32;
+    let path = line(center, Vec2::ZERO, end, 10);
+
+    let result = drag(&mut gizmo, &path, &mut targets);
+
+    let Some(GizmoResult::Translation {
+        constraint_position,
+        ..
+    }) = result
+    else {
+        panic!("expected a translation, got {result:?}");
+    };
+
+    assert!((constraint_position.unwrap() - 1.5).abs() < EPSILON);
+    assert_vec3_eq(targets[0].translation, DVec3::new(2.0, 1.0, 0.0));
+}
+
+#[test]
+fn drag_outside_gizmo_does_nothing() {
+    let config = config(GizmoMode::all());
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let path = line(Pos2::ZERO, Vec2::new(10.0, 10.0), Vec2::new(50.0, 50.0), 4);
+
+    assert!(drag(&mut gizmo, &path, &mut targets).is_none());
+    assert_eq!(targets[0], Transform::default());
+}
+
+#[test]
+fn mode_change_during_drag_is_deferred() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        if i == 2 {
+            // Modes are toggled in the middle of the drag, e.g. with a hotkey
+            gizmo.update_config(GizmoConfig {
+                modes: enum_set!(GizmoMode::RotateZ),
+                ..config
+            });
+        }
+
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((result, new_targets)) = gizmo.update(interaction, &targets) {
+            assert_eq!(result.kind(), GizmoModeKind::Translate);
+            targets = new_targets;
+        }
+    }
+
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL,
+    );
+}
+
+#[test]
+fn apply_on_release() {
+    let config = GizmoConfig {
+        apply_mode: ApplyMode::OnRelease,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        // Targets are not updated while dragging
+        assert!(gizmo.update(interaction, &targets).is_none());
+    }
+
+    assert_vec3_eq(
+        gizmo.preview_transforms().unwrap()[0].translation,
+        DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL,
+    );
+
+    let end = path.last().unwrap();
+    let interaction = GizmoInteraction {
+        cursor_pos: (end.x, end.y),
+        drag_started: false,
+        dragging: false,
+        drag_anywhere: false,
+        wheel_delta: 0.0,
+        duplicate: false,
+    };
+
+    let (result, targets) = gizmo.update(interaction, &targets).unwrap();
+
+    assert_eq!(result.kind(), GizmoModeKind::Translate);
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL,
+    );
+    assert!(gizmo.preview_transforms().is_none());
+}
+
+#[test]
+fn latest_result_is_retained_until_drag_ends() {
+    let config = GizmoConfig {
+        apply_mode: ApplyMode::OnRelease,
+        ..config(enum_set!(GizmoMode::TranslateX))
+    };
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        assert!(gizmo.update(interaction, &targets).is_none());
+    }
+
+    // Update returns nothing until the release, but the totals are still available
+    let result = gizmo.latest_result();
+    let Some(GizmoResult::Translation { total, .. }) = result else {
+        panic!("expected a translation, got {result:?}");
+    };
+    assert_vec3_eq(total, DVec3::X * 40.0 * WORLD_UNITS_PER_PIXEL);
+
+    let end = path.last().unwrap();
+    let interaction = GizmoInteraction {
+        cursor_pos: (end.x, end.y),
+        drag_started: false,
+        dragging: false,
+        drag_anywhere: false,
+        wheel_delta: 0.0,
+        duplicate: false,
+    };
+
+    assert!(gizmo.update(interaction, &targets).is_some());
+    assert!(gizmo.latest_result().is_none());
+}
+
+#[test]
+fn translate_with_scaled_parent() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let parents: [mint::ColumnMatrix4<f64>; 1] =
+        [DMat4::from_scale(DVec3::new(2.0, 1.0, 1.0)).into()];
+    let mut targets = vec![Transform::default()];
+
+    let center = gizmo_center(&config, &targets);
+    let path = line(center, Vec2::new(50.0, 0.0), Vec2::new(90.0, 0.0), 4);
+
+    for (i, pos) in path.iter().enumerate() {
+        let interaction = GizmoInteraction {
+            cursor_pos: (pos.x, pos.y),
+            drag_started: i == 0,
+            dragging: true,
+            drag_anywhere: false,
+            wheel_delta: 0.0,
+            duplicate: false,
+        };
+
+        if let Some((_, new_targets)) = gizmo.update_with_parents(interaction, &targets, &parents) {
+            targets = new_targets;
+        }
+    }
+
+    // The parent doubles distances along the X axis
+    assert_vec3_eq(
+        targets[0].translation,
+        DVec3::X * 20.0 * WORLD_UNITS_PER_PIXEL,
+    );
+    assert_vec3_eq(targets[0].scale, DVec3::ONE);
+}
+
+#[test]
+fn highlight_expires_on_update() {
+    let config = config(enum_set!(GizmoMode::TranslateX));
+    let mut gizmo = Gizmo::new(config);
+    let targets = vec![Transform::default()];
+
+    gizmo.highlight_handle(GizmoMode::TranslateX, Duration::ZERO);
+
+    // Expiry is only resolved by updates, so that drawing does not depend on the current time
+    assert_eq!(gizmo.highlighted_mode(), Some(GizmoMode::TranslateX));
+
+    gizmo.update(GizmoInteraction::default(), &targets);
+
+    assert_eq!(gizmo.highlighted_mode(), None);
+}
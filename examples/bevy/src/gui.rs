@@ -40,7 +40,10 @@ fn draw_gizmo_result(ui: &mut egui::Ui, gizmo_result: Option<GizmoResult>) {
                 axis,
                 delta: _,
                 total,
+                revolutions: _,
                 is_view_axis: _,
+                pivot: _,
+                snapped_to: _,
             } => {
                 format!(
                     "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
@@ -50,16 +53,21 @@ fn draw_gizmo_result(ui: &mut egui::Ui, gizmo_result: Option<GizmoResult>) {
                     total.to_degrees()
                 )
             }
-            GizmoResult::Translation { delta: _, total } => {
+            GizmoResult::Translation { total, .. } => {
                 format!(
                     "Translation: ({:.2}, {:.2}, {:.2})",
                     total.x, total.y, total.z,
                 )
             }
-            GizmoResult::Scale { total } => {
+            GizmoResult::Scale { delta: _, total } => {
                 format!("Scale: ({:.2}, {:.2}, {:.2})", total.x, total.y, total.z,)
             }
-            GizmoResult::Arcball { delta: _, total } => {
+            GizmoResult::Arcball {
+                delta: _,
+                total,
+                pivot: _,
+                snapped_to: _,
+            } => {
                 let (axis, angle) = DQuat::from(total).to_axis_angle();
                 format!(
                     "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
@@ -69,6 +77,22 @@ fn draw_gizmo_result(ui: &mut egui::Ui, gizmo_result: Option<GizmoResult>) {
                     angle.to_degrees()
                 )
             }
+            GizmoResult::Direction { direction, .. } => {
+                format!(
+                    "Direction: ({:.2}, {:.2}, {:.2})",
+                    direction.x, direction.y, direction.z
+                )
+            }
+            GizmoResult::Bounds {
+                scale,
+                rotation: _,
+                anchor: _,
+            } => {
+                format!(
+                    "Bounds scale: ({:.2}, {:.2}, {:.2})",
+                    scale.x, scale.y, scale.z
+                )
+            }
         };
 
         egui::Frame::none()
@@ -83,7 +107,7 @@ fn draw_options(ui: &mut egui::Ui, gizmo_options: &mut GizmoOptions) {
     ui.heading("Options");
     ui.separator();
 
-    egui::Grid::new("modes_grid").num_columns(7).show(ui, |ui| {
+    egui::Grid::new("modes_grid").num_columns(9).show(ui, |ui| {
         ui.label(RichText::new("Mode").strong());
         ui.label(RichText::new("View").strong());
         ui.label(RichText::new("X").strong());
@@ -92,6 +116,7 @@ fn draw_options(ui: &mut egui::Ui, gizmo_options: &mut GizmoOptions) {
         ui.label(RichText::new("XZ").strong());
         ui.label(RichText::new("XY").strong());
         ui.label(RichText::new("YZ").strong());
+        ui.label(RichText::new("Best").strong());
         ui.end_row();
 
         ui.label("Rotation");
@@ -109,15 +134,15 @@ fn draw_options(ui: &mut egui::Ui, gizmo_options: &mut GizmoOptions) {
         draw_mode_picker(ui, GizmoMode::TranslateXZ, &mut gizmo_options.gizmo_modes);
         draw_mode_picker(ui, GizmoMode::TranslateXY, &mut gizmo_options.gizmo_modes);
         draw_mode_picker(ui, GizmoMode::TranslateYZ, &mut gizmo_options.gizmo_modes);
+        draw_mode_picker(
+            ui,
+            GizmoMode::TranslateBestPlane,
+            &mut gizmo_options.gizmo_modes,
+        );
         ui.end_row();
 
         ui.label("Scale");
-        ui.add_enabled_ui(
-            !gizmo_options.gizmo_modes.contains(GizmoMode::RotateView),
-            |ui| {
-                draw_mode_picker(ui, GizmoMode::ScaleUniform, &mut gizmo_options.gizmo_modes);
-            },
-        );
+        draw_mode_picker(ui, GizmoMode::ScaleUniform, &mut gizmo_options.gizmo_modes);
         draw_mode_picker(ui, GizmoMode::ScaleX, &mut gizmo_options.gizmo_modes);
         draw_mode_picker(ui, GizmoMode::ScaleY, &mut gizmo_options.gizmo_modes);
         draw_mode_picker(ui, GizmoMode::ScaleZ, &mut gizmo_options.gizmo_modes);
@@ -3,7 +3,7 @@ use bevy_mod_outline::*;
 use bevy_mod_picking::{
     picking_core::PickingPluginsSettings, prelude::*, selection::SelectionPluginSettings,
 };
-use transform_gizmo_bevy::GizmoTarget;
+use transform_gizmo_bevy::{GizmoSystems, GizmoTarget};
 
 /// Integrates picking with gizmo and highlighting.
 pub struct PickingPlugin;
@@ -16,7 +16,10 @@ impl Plugin for PickingPlugin {
                 click_nothing_deselect_all: false,
                 ..default()
             })
-            .add_systems(PreUpdate, toggle_picking_enabled)
+            .add_systems(
+                PreUpdate,
+                toggle_picking_enabled.after(GizmoSystems::Update),
+            )
             .add_systems(Update, update_picking);
     }
 }
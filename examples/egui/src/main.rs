@@ -47,7 +47,7 @@ impl ExampleApp {
         self.gizmo.update_config(GizmoConfig {
             view_matrix: view_matrix.into(),
             projection_matrix: projection_matrix.into(),
-            viewport,
+            viewport: viewport.into(),
             modes: self.gizmo_modes,
             orientation: self.gizmo_orientation,
             snapping,
@@ -73,7 +73,10 @@ impl ExampleApp {
                     axis,
                     delta: _,
                     total,
+                    revolutions: _,
                     is_view_axis: _,
+                    pivot: _,
+                    snapped_to: _,
                 } => {
                     format!(
                         "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
@@ -83,16 +86,21 @@ impl ExampleApp {
                         total.to_degrees()
                     )
                 }
-                GizmoResult::Translation { delta: _, total } => {
+                GizmoResult::Translation { total, .. } => {
                     format!(
                         "Translation: ({:.2}, {:.2}, {:.2})",
                         total.x, total.y, total.z,
                     )
                 }
-                GizmoResult::Scale { total } => {
+                GizmoResult::Scale { delta: _, total } => {
                     format!("Scale: ({:.2}, {:.2}, {:.2})", total.x, total.y, total.z,)
                 }
-                GizmoResult::Arcball { delta: _, total } => {
+                GizmoResult::Arcball {
+                    delta: _,
+                    total,
+                    pivot: _,
+                    snapped_to: _,
+                } => {
                     let (axis, angle) = DQuat::from(total).to_axis_angle();
                     format!(
                         "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
@@ -102,6 +110,22 @@ impl ExampleApp {
                         angle.to_degrees()
                     )
                 }
+                GizmoResult::Direction { direction, .. } => {
+                    format!(
+                        "Direction: ({:.2}, {:.2}, {:.2})",
+                        direction.x, direction.y, direction.z
+                    )
+                }
+                GizmoResult::Bounds {
+                    scale,
+                    rotation: _,
+                    anchor: _,
+                } => {
+                    format!(
+                        "Bounds scale: ({:.2}, {:.2}, {:.2})",
+                        scale.x, scale.y, scale.z
+                    )
+                }
             };
 
             ui.label(text);